@@ -0,0 +1,205 @@
+//! # Importing mazes
+//!
+//! This module contains functions reconstructing mazes from other
+//! representations.
+
+use maze::matrix;
+
+/// The intensity below which a pixel is considered part of a wall.
+///
+/// Anti-aliased wall pixels are lighter than the wall colour, so this is
+/// deliberately not close to `0.0`.
+const WALL_THRESHOLD: f32 = 0.5;
+
+/// Reconstructs a maze from an image of its walls.
+///
+/// The image is assumed to cover exactly the view box of the maze, and walls
+/// are assumed to be drawn in a dark colour on a light background. A wall is
+/// considered closed if the darkest pixel around its midpoint has an
+/// intensity below a threshold.
+///
+/// Walls leading out of the maze are reconstructed as well, so gaps in the
+/// outer border are preserved.
+///
+/// # Arguments
+/// *  `image` - The image to sample.
+/// *  `shape` - The shape of the rooms.
+/// *  `width` - The width, in rooms, of the maze.
+/// *  `height` - The height, in rooms, of the maze.
+pub fn from_wall_image(
+    image: &image::RgbImage,
+    shape: maze::Shape,
+    width: usize,
+    height: usize,
+) -> maze::Maze<()> {
+    let mut maze = maze::Maze::new(shape, width, height);
+    let viewbox = maze.viewbox();
+    let (cols, rows) = image.dimensions();
+
+    let positions = maze.positions().collect::<Vec<_>>();
+    for pos in positions {
+        for &wall in maze.walls(pos) {
+            let (start, end) = maze.corners((pos, wall));
+            let midpoint = start.midpoint(end);
+            let x = (midpoint.x - viewbox.corner.x) / viewbox.width;
+            let y = (midpoint.y - viewbox.corner.y) / viewbox.height;
+            let closed = darkest(
+                image,
+                matrix::Pos {
+                    col: (x * cols as f32) as isize,
+                    row: (y * rows as f32) as isize,
+                },
+            )
+            .map(|intensity| intensity < WALL_THRESHOLD)
+            .unwrap_or(true);
+            if !closed {
+                maze.open((pos, wall));
+            }
+        }
+    }
+
+    maze
+}
+
+/// Finds the lowest intensity of the pixels surrounding a point.
+///
+/// The intensity is a value between `0.0` and `1.0`. If no pixel surrounding
+/// the point is inside the image, `None` is returned.
+///
+/// # Arguments
+/// *  `image` - The image to sample.
+/// *  `pos` - The pixel position.
+fn darkest(image: &image::RgbImage, pos: matrix::Pos) -> Option<f32> {
+    let (cols, rows) = image.dimensions();
+    (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| (pos.col + dx, pos.row + dy)))
+        .filter(|&(x, y)| {
+            (0..cols as isize).contains(&x) && (0..rows as isize).contains(&y)
+        })
+        .map(|(x, y)| {
+            let image::Rgb([r, g, b]) = *image.get_pixel(x as u32, y as u32);
+            (f32::from(r) + f32::from(g) + f32::from(b)) / (3.0 * 255.0)
+        })
+        .reduce(f32::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use maze::initialize;
+
+    use super::*;
+
+    /// The number of pixels per maze unit in rendered images.
+    const RESOLUTION: f32 = 40.0;
+
+    /// Renders the closed walls of a maze to an image.
+    ///
+    /// Walls are drawn as black lines with a width of four pixels, with
+    /// anti-aliased grey edges.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to render.
+    fn render(maze: &maze::Maze<()>) -> image::RgbImage {
+        let viewbox = maze.viewbox();
+        let mut image = image::RgbImage::from_pixel(
+            (viewbox.width * RESOLUTION).ceil() as u32,
+            (viewbox.height * RESOLUTION).ceil() as u32,
+            image::Rgb([255, 255, 255]),
+        );
+        let (cols, rows) = image.dimensions();
+        let mut paint = |x: f32, y: f32, radius: f32, value: u8| {
+            let r = radius.ceil() as i64;
+            for dy in -r..=r {
+                for dx in -r..=r {
+                    let (px, py) = (x as i64 + dx, y as i64 + dy);
+                    let d = ((dx * dx + dy * dy) as f32).sqrt();
+                    if d <= radius
+                        && (0..cols as i64).contains(&px)
+                        && (0..rows as i64).contains(&py)
+                    {
+                        let pixel = image.get_pixel_mut(px as u32, py as u32);
+                        if pixel.0[0] > value {
+                            *pixel = image::Rgb([value, value, value]);
+                        }
+                    }
+                }
+            }
+        };
+
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                if maze.is_open((pos, wall)) {
+                    continue;
+                }
+                let (start, end) = maze.corners((pos, wall));
+                let steps = 200;
+                for i in 0..=steps {
+                    let t = i as f32 / steps as f32;
+                    let x = RESOLUTION
+                        * (start.x + t * (end.x - start.x) - viewbox.corner.x);
+                    let y = RESOLUTION
+                        * (start.y + t * (end.y - start.y) - viewbox.corner.y);
+                    paint(x, y, 3.0, 160);
+                    paint(x, y, 2.0, 0);
+                }
+            }
+        }
+
+        image
+    }
+
+    #[test]
+    fn round_trip() {
+        for shape in [maze::Shape::Hex, maze::Shape::Quad, maze::Shape::Tri] {
+            let (width, height) = (10, 5);
+            let mut maze = maze::Maze::new(shape, width, height).initialize(
+                initialize::Method::Winding,
+                &mut initialize::LFSR::new(12345),
+            );
+
+            // Open an entrance to ensure gaps in the border are imported
+            let pos = maze::matrix::Pos { col: 0, row: 0 };
+            let wall = *maze
+                .walls(pos)
+                .iter()
+                .find(|&wall| !maze.is_inside(maze.back((pos, wall)).0))
+                .unwrap();
+            maze.open((pos, wall));
+
+            let imported =
+                from_wall_image(&render(&maze), shape, width, height);
+
+            for pos in maze.positions() {
+                for &wall in maze.walls(pos) {
+                    assert_eq!(
+                        maze.is_open((pos, wall)),
+                        imported.is_open((pos, wall)),
+                        "for {:?} at {:?} in {:?}",
+                        wall,
+                        pos,
+                        shape,
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn blank_image() {
+        let maze = maze::Maze::<()>::new(maze::Shape::Quad, 3, 3);
+        let viewbox = maze.viewbox();
+        let image = image::RgbImage::from_pixel(
+            (viewbox.width * RESOLUTION) as u32,
+            (viewbox.height * RESOLUTION) as u32,
+            image::Rgb([255, 255, 255]),
+        );
+        let imported = from_wall_image(&image, maze::Shape::Quad, 3, 3);
+
+        for pos in imported.positions() {
+            assert!(imported
+                .walls(pos)
+                .iter()
+                .all(|&wall| imported.is_open((pos, wall))));
+        }
+    }
+}
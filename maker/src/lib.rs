@@ -0,0 +1,8 @@
+//! # Maze maker
+//!
+//! This crate contains the renderers, initialisers and post-processors used by
+//! the maze maker application.
+
+pub mod import;
+pub mod render;
+pub mod types;
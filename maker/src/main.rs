@@ -1,12 +1,11 @@
+use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 
-use clap::{arg, Parser};
-use svg::Node;
+use clap::{CommandFactory, Parser};
 
-use maze::render::svg::ToPath;
-
-mod types;
-use self::types::*;
+use maze_maker::render;
+use maze_maker::types::*;
 
 /// Generates mazes.
 #[derive(Parser)]
@@ -46,15 +45,16 @@ struct Arguments {
     #[arg(id = "METHOD", long = "method", required(true))]
     methods: Methods<Random>,
 
-    /// A relative size for the maze, applied to rooms.
+    /// The size of rooms, in pixels.
     #[arg(id = "SCALE", long = "scale", default_value_t = 10.0)]
     scale: f32,
 
-    /// A seed for the random number generator.
+    /// A seed for the random number generator. Using the same seed and
+    /// arguments yields identical output.
     #[arg(id = "SEED", long = "seed")]
     seed: Option<u64>,
 
-    /// The margin around the maze.
+    /// The margin around the maze, in pixels.
     #[arg(id = "MARGIN", long = "margin", default_value_t = 10.0)]
     margin: f32,
 
@@ -63,7 +63,8 @@ struct Arguments {
     #[arg(id = "INITIALIZE", long = "mask")]
     initialize_mask: Option<MaskInitializer<Random>>,
 
-    /// Whether to create a heat map.
+    /// Whether to create a heat map. Colours with eight hex digits are read
+    /// as "#RRGGBBAA"; earlier versions read them as "#AARRGGBB".
     #[arg(id = "HEATMAP", long = "heat-map")]
     render_heatmap: Option<HeatMapRenderer>,
 
@@ -85,7 +86,10 @@ struct Arguments {
     render_text: Option<TextRenderer>,
 
     /// Whether to solve the maze, and the solution colour. If not specified,
-    /// the colour defaults to "black".
+    /// the colour defaults to "black". Append ",smooth" to draw the solution
+    /// as a smooth curve, and ",nowalls" to omit the walls of the maze.
+    /// Colours with eight hex digits are read as "#RRGGBBAA"; earlier
+    /// versions read them as "#AARRGGBB".
     #[arg(
         id = "SOLVE",
         long = "solve",
@@ -94,16 +98,61 @@ struct Arguments {
     )]
     render_solve: Option<SolveRenderer>,
 
+    /// Whether to draw the outer border of the maze, and its colour and
+    /// optionally width on the form "colour,width".
+    #[arg(id = "BORDER", long = "border")]
+    render_border: Option<BorderRenderer>,
+
+    /// Whether to overlay the room lattice for debugging, and its colour. If
+    /// not specified, the colour defaults to "red".
+    #[arg(id = "GRID", long = "grid", default_missing_value = "red")]
+    render_grid: Option<GridRenderer>,
+
+    /// Whether to mark dead ends and junctions, and their colours on the form
+    /// "dead-end-colour,junction-colour". If not specified, the colours
+    /// default to "red,blue".
+    #[arg(
+        id = "ANNOTATE",
+        long = "annotate",
+        default_missing_value = "red,blue"
+    )]
+    render_annotate: Option<AnnotationRenderer>,
+
+    /// Whether to draw room indices along the top and left edges for
+    /// debugging, and their colour. If not specified, the colour defaults to
+    /// "red". This requires a margin.
+    #[arg(id = "RULER", long = "ruler", default_missing_value = "red")]
+    render_ruler: Option<RulerRenderer>,
+
+    /// A path to which to write the solution as JSON: an array of rooms on
+    /// the form {"col": 0, "row": 0, "center": {"x": 0.7, "y": 0.7}}.
+    #[arg(id = "SOLUTION_JSON", long = "solution-json")]
+    solution_json: Option<PathBuf>,
+
+    /// The endpoints of the solution written by --solution-json, on the form
+    /// "from_col:from_row:to_col:to_row". If not specified, the solution
+    /// leads from the top left room to the bottom right room.
+    #[arg(
+        id = "SOLUTION_ENDPOINTS",
+        long = "solution-endpoints",
+        requires("SOLUTION_JSON")
+    )]
+    solution_endpoints: Option<Solution>,
+
     /// Whether to break the maze.
     #[arg(long = "break")]
     post_break: Option<BreakPostProcessor>,
 
+    /// The fraction, in the range [0, 1], of closed walls between rooms to
+    /// open at random to add loops to the maze.
+    #[arg(long = "loopify")]
+    post_loopify: Option<LoopifyPostProcessor>,
+
     /// The output SVG.
     #[arg(id = "PATH", required(true))]
     output: PathBuf,
 }
 
-#[allow(unused_variables, clippy::too_many_arguments)]
 fn run<P>(
     maze: Maze,
     scale: f32,
@@ -113,42 +162,16 @@ fn run<P>(
 ) where
     P: AsRef<Path>,
 {
-    let document = svg::Document::new()
-        .set("viewBox", maze_to_viewbox(&maze, scale, margin));
-    let mut container = svg::node::element::Group::new()
-        .set("transform", format!("scale({})", scale));
-
-    for renderer in renderers {
-        renderer.render(&maze, &mut container);
-    }
-
-    // Draw the maze
-    container.append(
-        svg::node::element::Path::new()
-            .set("fill", "none")
-            .set("stroke", "black")
-            .set("stroke-linecap", "round")
-            .set("stroke-linejoin", "round")
-            .set("stroke-width", 0.4)
-            .set("vector-effect", "non-scaling-stroke")
-            .set("d", maze.to_path_d()),
-    );
-
-    svg::save(output, &document.add(container)).expect("failed to write SVG");
-}
-
-/// Calculates the view box for a maze with a margin.
-///
-/// # Arguments
-/// *  `maze` - The maze for which to generate a view box.
-/// *  `scale` - A scale multiplier.
-/// *  `margin` - The margin to apply to all sides.
-fn maze_to_viewbox(
-    maze: &Maze,
-    scale: f32,
-    margin: f32,
-) -> (f32, f32, f32, f32) {
-    (maze.viewbox() * scale).expand(margin).tuple()
+    render::write_svg_scaled(
+        &maze,
+        render::Scale(scale),
+        margin,
+        renderers,
+        io::BufWriter::new(
+            fs::File::create(output).expect("failed to create SVG"),
+        ),
+    )
+    .expect("failed to write SVG");
 }
 
 #[allow(unused_mut)]
@@ -176,19 +199,45 @@ fn main() {
         .map(Random::from_seed)
         .unwrap_or_else(Random::from_os);
 
+    // Make sure that all heat maps refer to rooms inside of the maze
+    let maze = args.shape.create(width, height);
+    for map_type in [
+        args.post_break.as_ref().map(|action| &action.map_type),
+        args.render_heatmap.as_ref().map(|action| &action.map_type),
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if let Err(e) = map_type.validate(&maze) {
+            Arguments::command()
+                .error(clap::error::ErrorKind::ValueValidation, e)
+                .exit();
+        }
+    }
+
     // Make sure the maze is initialised
     let maze = {
-        let mut maze = args.initialize_mask.initialize(
-            args.shape.create(width, height),
-            &mut rng,
-            args.methods,
-        );
-
-        [&args.post_break as &dyn PostProcessor<_>]
-            .iter()
-            .fold(maze, |maze, a| a.post_process(maze, &mut rng))
+        let mut maze =
+            args.initialize_mask
+                .initialize(maze, &mut rng, args.methods);
+
+        [
+            &args.post_break as &dyn PostProcessor<_>,
+            &args.post_loopify as &dyn PostProcessor<_>,
+        ]
+        .iter()
+        .fold(maze, |maze, a| a.post_process(maze, &mut rng))
     };
 
+    if let Some(path) = args.solution_json.as_ref() {
+        let json = args
+            .solution_endpoints
+            .unwrap_or_else(|| Solution::corners(&maze))
+            .to_json(&maze)
+            .expect("failed to solve maze");
+        fs::write(path, json).expect("failed to write solution");
+    }
+
     run(
         maze,
         args.scale,
@@ -198,6 +247,10 @@ fn main() {
             &args.render_text,
             &args.render_heatmap,
             &args.render_solve,
+            &args.render_border,
+            &args.render_grid,
+            &args.render_annotate,
+            &args.render_ruler,
         ],
         &args.output,
     );
@@ -0,0 +1,1081 @@
+//! # Rendering of complete documents
+//!
+//! The functions in this module create complete SVG documents. The writing
+//! functions do not build the entire document tree in memory; rather, content
+//! is written element by element as it is produced. Use [`document`] to build
+//! a document tree instead.
+
+use std::collections::HashSet;
+use std::io;
+
+use svg::Node;
+
+use maze::physical;
+use maze::render::svg::ToPath;
+
+use crate::types::Renderer;
+
+/// A scale applied to rendered documents, in pixels per maze unit.
+///
+/// The view box of a document is always expressed in maze units; the scale
+/// only determines its `width` and `height` attributes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scale(pub f32);
+
+impl Default for Scale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Options for documents created by [`document`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DocumentOptions {
+    /// The scale of the document.
+    pub scale: Scale,
+
+    /// The margin to apply to all sides, in pixels.
+    pub margin: f32,
+
+    /// The background colour, if any.
+    ///
+    /// If this is `None`, the background is transparent.
+    pub background: Option<String>,
+
+    /// The seed used to generate the maze, if known.
+    ///
+    /// This is only used for the metadata of the document.
+    pub seed: Option<u64>,
+
+    /// The number of decimals to keep for the coordinates of the walls.
+    ///
+    /// If this is `None`, coordinates are not rounded.
+    pub precision: Option<usize>,
+
+    /// The drop shadow of the walls, if any.
+    pub shadow: Option<ShadowOptions>,
+
+    /// How elements are styled.
+    pub style: StyleMode,
+
+    /// The stylesheet to embed when styling with classes.
+    ///
+    /// If this is `None`, [`DEFAULT_STYLESHEET`] is used. This is ignored for
+    /// [`StyleMode::Inline`].
+    pub stylesheet: Option<String>,
+}
+
+/// How the elements of a document are styled.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StyleMode {
+    /// Presentation attributes such as `fill` and `stroke` are set on every
+    /// element.
+    #[default]
+    Inline,
+
+    /// Elements are given a `class` attribute, and their presentation is left
+    /// to a stylesheet.
+    ///
+    /// The classes used are [`WALL_CLASS`], [`SOLUTION_CLASS`] and
+    /// [`BACKGROUND_CLASS`]. Of the renderers, only
+    /// [`SolveRenderer`](crate::types::SolveRenderer) supports classes; the
+    /// output of all others keeps its presentation attributes.
+    Classes,
+}
+
+/// The class of the walls when styling with classes.
+pub const WALL_CLASS: &str = "maze-wall";
+
+/// The class of the solution when styling with classes.
+pub const SOLUTION_CLASS: &str = "maze-solution";
+
+/// The class of the background when styling with classes.
+pub const BACKGROUND_CLASS: &str = "maze-background";
+
+/// The stylesheet embedded by [`document`] when styling with classes, unless
+/// another one is specified.
+///
+/// This reproduces the inline style of the walls, and draws the solution in
+/// red.
+pub const DEFAULT_STYLESHEET: &str = "\
+.maze-wall, .maze-solution {
+    fill: none;
+    stroke-linecap: round;
+    stroke-linejoin: round;
+    stroke-width: 0.4;
+    vector-effect: non-scaling-stroke;
+}
+.maze-wall {
+    stroke: black;
+}
+.maze-solution {
+    stroke: red;
+}
+.maze-background {
+    fill: white;
+}
+";
+
+/// Options for the drop shadow of the walls of a document.
+///
+/// The shadow is an SVG filter blurring and offsetting the walls; it does not
+/// affect the geometry of the document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShadowOptions {
+    /// The horizontal offset of the shadow, in maze units.
+    pub dx: f32,
+
+    /// The vertical offset of the shadow, in maze units.
+    pub dy: f32,
+
+    /// The standard deviation of the blur, in maze units.
+    pub blur: f32,
+
+    /// The colour of the shadow.
+    pub color: String,
+}
+
+/// The ID of the drop shadow filter of documents created by [`document`].
+pub const SHADOW_FILTER_ID: &str = "shadow";
+
+impl ShadowOptions {
+    /// Creates the SVG filter for this shadow.
+    ///
+    /// # Arguments
+    /// *  `id` - The ID of the filter element.
+    pub fn filter(&self, id: &str) -> svg::node::element::Filter {
+        use svg::node::element::*;
+        Filter::new()
+            .set("id", id)
+            .add(
+                FilterEffectGaussianBlur::new()
+                    .set("in", "SourceAlpha")
+                    .set("stdDeviation", self.blur)
+                    .set("result", "blur"),
+            )
+            .add(
+                FilterEffectOffset::new()
+                    .set("in", "blur")
+                    .set("dx", self.dx)
+                    .set("dy", self.dy)
+                    .set("result", "offset"),
+            )
+            .add(
+                FilterEffectFlood::new()
+                    .set("flood-color", self.color.as_str())
+                    .set("result", "color"),
+            )
+            .add(
+                FilterEffectComposite::new()
+                    .set("in", "color")
+                    .set("in2", "offset")
+                    .set("operator", "in")
+                    .set("result", "shadow"),
+            )
+            .add(
+                FilterEffectMerge::new()
+                    .add(FilterEffectMergeNode::new().set("in", "shadow"))
+                    .add(
+                        FilterEffectMergeNode::new().set("in", "SourceGraphic"),
+                    ),
+            )
+    }
+}
+
+/// Creates a complete SVG document for a maze.
+///
+/// The document has a view box matching the maze, and `width` and `height`
+/// attributes according to the scale. If a background colour is specified,
+/// the view box is filled with it first, followed by the output of each
+/// renderer in turn and finally the walls of the maze.
+///
+/// The shape and dimensions of the maze, and the seed if specified, are
+/// embedded as a `<metadata>` element on the form
+/// `<metadata><maze shape="…" width="…" height="…" seed="…"/></metadata>`.
+///
+/// The walls are omitted if any renderer opts out of them; see
+/// [`Renderer::walls`]. If a shadow is specified and the walls are drawn, the
+/// shadow filter is defined in `<defs>` with the ID [`SHADOW_FILTER_ID`] and
+/// applied to a group containing the walls.
+///
+/// When styling with [`StyleMode::Classes`], a `<style>` element containing
+/// the stylesheet is added after the metadata, and the background, walls and
+/// renderers supporting it are given classes instead of presentation
+/// attributes. The background colour is then ignored in favour of the
+/// stylesheet.
+///
+/// # Arguments
+/// *  `maze` - The maze to render.
+/// *  `renderers` - Renderers applied before the walls are drawn.
+/// *  `options` - Options for the document.
+pub fn document<T>(
+    maze: &maze::Maze<T>,
+    renderers: &[&dyn Renderer<T>],
+    options: &DocumentOptions,
+) -> svg::Document
+where
+    T: Clone,
+{
+    let viewbox = maze_to_viewbox(maze, options.scale, options.margin);
+
+    let mut description = svg::node::element::Element::new("maze");
+    description.assign("shape", maze.shape().to_string());
+    description.assign("width", maze.width());
+    description.assign("height", maze.height());
+    if let Some(seed) = options.seed {
+        description.assign("seed", seed);
+    }
+    let mut metadata = svg::node::element::Element::new("metadata");
+    metadata.append(description);
+
+    let mut document = svg::Document::new()
+        .set("viewBox", viewbox.tuple())
+        .set("width", viewbox.width * options.scale.0)
+        .set("height", viewbox.height * options.scale.0)
+        .add(metadata);
+
+    if options.style == StyleMode::Classes {
+        document.append(svg::node::element::Style::new(
+            options.stylesheet.as_deref().unwrap_or(DEFAULT_STYLESHEET),
+        ));
+    }
+
+    if let Some(background) = &options.background {
+        let rectangle = svg::node::element::Rectangle::new()
+            .set("x", viewbox.corner.x)
+            .set("y", viewbox.corner.y)
+            .set("width", viewbox.width)
+            .set("height", viewbox.height);
+        document.append(match options.style {
+            StyleMode::Inline => rectangle.set("fill", background.as_str()),
+            StyleMode::Classes => rectangle.set("class", BACKGROUND_CLASS),
+        });
+    }
+
+    for renderer in renderers {
+        let mut group = svg::node::element::Group::new();
+        renderer.render_styled(maze, &mut group, options.style);
+        for child in group.get_children() {
+            document.append(child.clone());
+        }
+    }
+
+    if renderers.iter().all(|renderer| renderer.walls()) {
+        let data = match options.precision {
+            Some(precision) => maze.to_rounded_path_d(precision),
+            None => maze.to_path_d(),
+        };
+        let walls = match options.style {
+            StyleMode::Inline => walls_path(data),
+            StyleMode::Classes => svg::node::element::Path::new()
+                .set("class", WALL_CLASS)
+                .set("d", data),
+        };
+        if let Some(shadow) = &options.shadow {
+            document.append(
+                svg::node::element::Definitions::new()
+                    .add(shadow.filter(SHADOW_FILTER_ID)),
+            );
+            document.append(
+                svg::node::element::Group::new()
+                    .set("filter", format!("url(#{})", SHADOW_FILTER_ID))
+                    .add(walls),
+            );
+        } else {
+            document.append(walls);
+        }
+    }
+
+    document
+}
+
+/// Creates an SVG document laying out several mazes in a grid.
+///
+/// The mazes are placed row by row, with `cols` mazes per row. Every column is
+/// as wide as its widest maze and every row as tall as its tallest maze, and
+/// `spacing` units are left between cells. Each maze is drawn in its own
+/// group, translated into its cell, and the view box of the document starts
+/// at the origin.
+///
+/// # Arguments
+/// *  `mazes` - The mazes to render.
+/// *  `cols` - The number of mazes per row. A value of `0` is treated as `1`.
+/// *  `spacing` - The space between cells, in maze units.
+pub fn contact_sheet<T>(
+    mazes: &[&maze::Maze<T>],
+    cols: usize,
+    spacing: f32,
+) -> svg::Document
+where
+    T: Clone,
+{
+    let cols = cols.max(1);
+    let viewboxes = mazes.iter().map(|maze| maze.viewbox()).collect::<Vec<_>>();
+
+    // Determine the size of every column and row, and then their offsets
+    let mut widths = vec![0.0f32; cols.min(mazes.len())];
+    let mut heights = vec![0.0f32; mazes.len().div_ceil(cols)];
+    for (i, viewbox) in viewboxes.iter().enumerate() {
+        widths[i % cols] = widths[i % cols].max(viewbox.width);
+        heights[i / cols] = heights[i / cols].max(viewbox.height);
+    }
+    let offsets = |sizes: &[f32]| {
+        sizes
+            .iter()
+            .scan(0.0, |offset, size| {
+                let current = *offset;
+                *offset += size + spacing;
+                Some(current)
+            })
+            .collect::<Vec<_>>()
+    };
+    let (xs, ys) = (offsets(&widths), offsets(&heights));
+
+    let mut document = svg::Document::new().set(
+        "viewBox",
+        (
+            0.0,
+            0.0,
+            xs.last().zip(widths.last()).map_or(0.0, |(x, w)| x + w),
+            ys.last().zip(heights.last()).map_or(0.0, |(y, h)| y + h),
+        ),
+    );
+    for (i, (maze, viewbox)) in mazes.iter().zip(viewboxes).enumerate() {
+        document.append(
+            svg::node::element::Group::new()
+                .set(
+                    "transform",
+                    format!(
+                        "translate({} {})",
+                        xs[i % cols] - viewbox.corner.x,
+                        ys[i / cols] - viewbox.corner.y,
+                    ),
+                )
+                .add(walls_path(maze.to_path_d())),
+        );
+    }
+
+    document
+}
+
+/// Writes a maze as an SVG document.
+///
+/// This is equivalent to calling [`write_svg_scaled`] with the default scale
+/// and no margin.
+///
+/// # Arguments
+/// *  `maze` - The maze to write.
+/// *  `renderers` - Renderers applied before the walls are drawn.
+/// *  `w` - The target.
+pub fn write_svg<T, W>(
+    maze: &maze::Maze<T>,
+    renderers: &[&dyn Renderer<T>],
+    w: W,
+) -> io::Result<()>
+where
+    T: Clone,
+    W: io::Write,
+{
+    write_svg_scaled(maze, Scale::default(), 0.0, renderers, w)
+}
+
+/// Writes a scaled maze as an SVG document.
+///
+/// The root element, including the view box, is written first, followed by
+/// the output of each renderer in turn and finally the walls of the maze. The
+/// output of a renderer is discarded once written.
+///
+/// The walls are omitted if any renderer opts out of them; see
+/// [`Renderer::walls`].
+///
+/// # Arguments
+/// *  `maze` - The maze to write.
+/// *  `scale` - The scale of the document.
+/// *  `margin` - The margin to apply to all sides, in pixels.
+/// *  `renderers` - Renderers applied before the walls are drawn.
+/// *  `w` - The target.
+pub fn write_svg_scaled<T, W>(
+    maze: &maze::Maze<T>,
+    scale: Scale,
+    margin: f32,
+    renderers: &[&dyn Renderer<T>],
+    w: W,
+) -> io::Result<()>
+where
+    T: Clone,
+    W: io::Write,
+{
+    let viewbox = maze_to_viewbox(maze, scale, margin);
+    write_document(maze, scale, viewbox, None, renderers, w)
+}
+
+/// Writes a scaled maze as an SVG document with a view box at the origin.
+///
+/// This behaves like [`write_svg_scaled`], but the view box is translated so
+/// that its corner is at `(0, 0)`, and the content is translated accordingly;
+/// see [`maze::Maze::normalized_viewbox`]. All coordinates inside of the
+/// document are thus non-negative, which simplifies embedding.
+///
+/// # Arguments
+/// *  `maze` - The maze to write.
+/// *  `scale` - The scale of the document.
+/// *  `margin` - The margin to apply to all sides, in pixels.
+/// *  `renderers` - Renderers applied before the walls are drawn.
+/// *  `w` - The target.
+pub fn write_svg_normalized<T, W>(
+    maze: &maze::Maze<T>,
+    scale: Scale,
+    margin: f32,
+    renderers: &[&dyn Renderer<T>],
+    w: W,
+) -> io::Result<()>
+where
+    T: Clone,
+    W: io::Write,
+{
+    let (viewbox, offset) = maze.normalized_viewbox();
+    let margin = margin / scale.0;
+    let viewbox = physical::ViewBox {
+        width: viewbox.width + 2.0 * margin,
+        height: viewbox.height + 2.0 * margin,
+        ..viewbox
+    };
+    let offset = physical::Pos {
+        x: offset.x + margin,
+        y: offset.y + margin,
+    };
+    write_document(maze, scale, viewbox, Some(offset), renderers, w)
+}
+
+/// Writes a maze as an SVG document with a specific view box.
+///
+/// # Arguments
+/// *  `maze` - The maze to write.
+/// *  `scale` - The scale of the document.
+/// *  `viewbox` - The view box of the document.
+/// *  `offset` - A translation to apply to all content, if any.
+/// *  `renderers` - Renderers applied before the walls are drawn.
+/// *  `w` - The target.
+fn write_document<T, W>(
+    maze: &maze::Maze<T>,
+    scale: Scale,
+    viewbox: physical::ViewBox,
+    offset: Option<physical::Pos>,
+    renderers: &[&dyn Renderer<T>],
+    mut w: W,
+) -> io::Result<()>
+where
+    T: Clone,
+    W: io::Write,
+{
+    let (x, y, width, height) = viewbox.tuple();
+    write!(
+        w,
+        r#"<svg height="{}" viewBox="{} {} {} {}" width="{}" "#,
+        height * scale.0,
+        x,
+        y,
+        width,
+        height,
+        width * scale.0,
+    )?;
+    write!(w, r#"xmlns="http://www.w3.org/2000/svg">"#)?;
+    if let Some(offset) = offset {
+        write!(
+            w,
+            r#"
+<g transform="translate({} {})">"#,
+            offset.x, offset.y,
+        )?;
+    }
+
+    for renderer in renderers {
+        let mut group = svg::node::element::Group::new();
+        renderer.render(maze, &mut group);
+        for child in group.get_children() {
+            write!(w, "\n{}", child)?;
+        }
+    }
+
+    // Draw the maze
+    if renderers.iter().all(|renderer| renderer.walls()) {
+        write!(w, "\n{}", walls_path(maze.to_path_d()),)?;
+    }
+
+    if offset.is_some() {
+        write!(w, "\n</g>")?;
+    }
+    write!(w, "\n</svg>")?;
+    w.flush()
+}
+
+/// Renders the part of a maze inside of a view box.
+///
+/// Only the walls of rooms touched by the view box are drawn. Walls of rooms
+/// on the boundary are drawn in full, so adjacent regions line up. The
+/// renderers are applied to the entire maze and rely on the view box of the
+/// document for clipping.
+///
+/// The walls are omitted if any renderer opts out of them; see
+/// [`Renderer::walls`].
+///
+/// # Arguments
+/// *  `maze` - The maze to render.
+/// *  `viewbox` - The region to render.
+/// *  `renderers` - Renderers applied before the walls are drawn.
+pub fn render_region<T>(
+    maze: &maze::Maze<T>,
+    viewbox: physical::ViewBox,
+    renderers: &[&dyn Renderer<T>],
+) -> svg::Document
+where
+    T: Clone,
+{
+    let mut group = svg::node::element::Group::new();
+    for renderer in renderers {
+        renderer.render(maze, &mut group);
+    }
+    if !renderers.iter().all(|renderer| renderer.walls()) {
+        return svg::Document::new()
+            .set("viewBox", viewbox.tuple())
+            .add(group);
+    }
+
+    let rooms = maze
+        .rooms_touched_by(viewbox)
+        .into_iter()
+        .filter(|&pos| maze.is_inside(pos))
+        .collect::<Vec<_>>();
+    let mut drawn = HashSet::new();
+    let mut data = svg::node::element::path::Data::new();
+    for &pos in rooms.iter() {
+        for &wall in maze.walls(pos) {
+            let (back, _) = maze.back((pos, wall));
+            if maze.is_open((pos, wall)) || drawn.contains(&back) {
+                continue;
+            }
+            let (start, end) = maze.corners((pos, wall));
+            data = data.move_to((start.x, start.y)).line_to((end.x, end.y));
+        }
+        drawn.insert(pos);
+    }
+
+    group.append(walls_path(data));
+
+    svg::Document::new()
+        .set("viewBox", viewbox.tuple())
+        .add(group)
+}
+
+/// Creates the path element used to draw walls.
+///
+/// # Arguments
+/// *  `data` - The path data of the walls.
+fn walls_path(
+    data: svg::node::element::path::Data,
+) -> svg::node::element::Path {
+    svg::node::element::Path::new()
+        .set("fill", "none")
+        .set("stroke", "black")
+        .set("stroke-linecap", "round")
+        .set("stroke-linejoin", "round")
+        .set("stroke-width", 0.4)
+        .set("vector-effect", "non-scaling-stroke")
+        .set("d", data)
+}
+
+/// Calculates the view box, in maze units, for a maze with a margin.
+///
+/// # Arguments
+/// *  `maze` - The maze for which to generate a view box.
+/// *  `scale` - The scale of the document.
+/// *  `margin` - The margin to apply to all sides, in pixels.
+pub fn maze_to_viewbox<T>(
+    maze: &maze::Maze<T>,
+    scale: Scale,
+    margin: f32,
+) -> maze::physical::ViewBox
+where
+    T: Clone,
+{
+    maze.viewbox().expand(margin / scale.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use svg::Node;
+
+    use super::*;
+    use crate::types::{BorderRenderer, GridRenderer, Maze, SolveRenderer};
+
+    /// A renderer adding a single marker element.
+    struct Marker;
+
+    impl Renderer for Marker {
+        fn render(&self, _maze: &Maze, group: &mut svg::node::element::Group) {
+            group.append(svg::node::element::Circle::new().set("id", "marker"));
+        }
+    }
+
+    #[test]
+    fn write_svg_room_data() {
+        let plain = Maze::new(maze::Shape::Hex, 5, 3).initialize(
+            maze::initialize::Method::Winding,
+            &mut maze::initialize::LFSR::new(12345),
+        );
+        let data = plain.map(|pos, _| pos.col as u32);
+
+        let border = "black".parse::<BorderRenderer>().unwrap();
+        let grid = "red".parse::<GridRenderer>().unwrap();
+        let solve = "blue".parse::<SolveRenderer>().unwrap();
+
+        let mut plain_buffer = Vec::new();
+        write_svg(&plain, &[&border, &grid, &solve], &mut plain_buffer)
+            .unwrap();
+        let mut data_buffer = Vec::new();
+        write_svg(&data, &[&border, &grid, &solve], &mut data_buffer).unwrap();
+
+        assert_eq!(plain_buffer, data_buffer);
+    }
+
+    #[test]
+    fn write_svg_header() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 3);
+        let mut buffer = Vec::new();
+        write_svg(&maze, &[&Marker], &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        let (x, y, width, height) = maze.viewbox().tuple();
+        assert_eq!(
+            format!("{} {} {} {}", x, y, width, height),
+            root(&output)["viewBox"].to_string(),
+        );
+        assert!(output.starts_with("<svg "));
+        assert!(output.find("<svg ").unwrap() < output.find("marker").unwrap());
+        assert!(output.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn write_svg_scale() {
+        let maze = Maze::new(maze::Shape::Hex, 5, 3);
+        let render = |scale| {
+            let mut buffer = Vec::new();
+            write_svg_scaled(&maze, Scale(scale), 0.0, &[], &mut buffer)
+                .unwrap();
+            let attributes = root(&String::from_utf8(buffer).unwrap());
+            let viewbox = attributes["viewBox"]
+                .split(' ')
+                .map(|v| v.parse::<f32>().unwrap())
+                .collect::<Vec<_>>();
+            (
+                attributes["width"].parse::<f32>().unwrap(),
+                viewbox[2] / viewbox[3],
+            )
+        };
+
+        let (width1, ratio1) = render(10.0);
+        let (width2, ratio2) = render(20.0);
+        assert_eq!(width1 * 2.0, width2);
+        assert_eq!(ratio1, ratio2);
+    }
+
+    #[test]
+    fn document_metadata() {
+        let maze = Maze::new(maze::Shape::Hex, 5, 3);
+        let options = DocumentOptions {
+            scale: Scale(10.0),
+            background: Some("white".to_owned()),
+            seed: Some(12345),
+            ..Default::default()
+        };
+        let output = document(&maze, &[&Marker], &options).to_string();
+
+        let (x, y, width, height) = maze.viewbox().tuple();
+        let attributes = root(&output);
+        assert_eq!(
+            format!("{} {} {} {}", x, y, width, height),
+            attributes["viewBox"].to_string(),
+        );
+        assert_eq!((width * 10.0).to_string(), attributes["width"].to_string());
+
+        let maze_tag = svg::read(&output)
+            .unwrap()
+            .find_map(|event| match event {
+                svg::parser::Event::Tag("maze", _, attributes) => {
+                    Some(attributes)
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!("hex", maze_tag["shape"].to_string());
+        assert_eq!("5", maze_tag["width"].to_string());
+        assert_eq!("3", maze_tag["height"].to_string());
+        assert_eq!("12345", maze_tag["seed"].to_string());
+        assert!(
+            output.find("<metadata>").unwrap() < output.find("<rect").unwrap()
+        );
+        assert!(output.find("<rect").unwrap() < output.find("marker").unwrap());
+        assert!(output.contains(r#"fill="white""#));
+    }
+
+    #[test]
+    fn document_precision() {
+        let maze = Maze::new(maze::Shape::Hex, 5, 3).initialize(
+            maze::initialize::Method::Branching,
+            &mut maze::initialize::LFSR::new(12345),
+        );
+        let decimals = |options: &DocumentOptions| {
+            let output = document(&maze, &[], options).to_string();
+            let d = svg::read(&output)
+                .unwrap()
+                .find_map(|event| match event {
+                    svg::parser::Event::Tag("path", _, attributes) => {
+                        Some(attributes["d"].to_string())
+                    }
+                    _ => None,
+                })
+                .unwrap();
+            d.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+                .filter_map(|number| number.split_once('.'))
+                .map(|(_, decimals)| decimals.len())
+                .max()
+                .unwrap_or(0)
+        };
+
+        assert!(decimals(&DocumentOptions::default()) > 2);
+        assert_eq!(
+            2,
+            decimals(&DocumentOptions {
+                precision: Some(2),
+                ..Default::default()
+            }),
+        );
+    }
+
+    #[test]
+    fn document_shadow() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 3);
+        let options = DocumentOptions {
+            shadow: Some(ShadowOptions {
+                dx: 0.2,
+                dy: 0.3,
+                blur: 0.1,
+                color: "gray".to_owned(),
+            }),
+            ..Default::default()
+        };
+        let output = document(&maze, &[], &options).to_string();
+
+        let reference = format!(r#"filter="url(#{})""#, SHADOW_FILTER_ID);
+        assert!(output.contains("<defs>"));
+        assert!(
+            output.contains(&format!(r#"<filter id="{}""#, SHADOW_FILTER_ID))
+        );
+        assert!(output.contains("<feGaussianBlur"));
+        assert!(output.contains("<feOffset"));
+        assert!(output.contains(&reference));
+        assert!(
+            output.find("<filter").unwrap() < output.find(&reference).unwrap()
+        );
+
+        let plain =
+            document(&maze, &[], &DocumentOptions::default()).to_string();
+        assert!(!plain.contains("<filter") && !plain.contains("filter="));
+    }
+
+    #[test]
+    fn document_classes() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 3).initialize(
+            maze::initialize::Method::Winding,
+            &mut maze::initialize::LFSR::new(12345),
+        );
+        let solve = "blue".parse::<SolveRenderer>().unwrap();
+        let options = DocumentOptions {
+            background: Some("white".to_owned()),
+            style: StyleMode::Classes,
+            ..Default::default()
+        };
+        let output = document(&maze, &[&solve], &options).to_string();
+
+        let classes = svg::read(&output)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag(_, _, attributes) => {
+                    assert!(!attributes.contains_key("fill"));
+                    assert!(!attributes.contains_key("stroke"));
+                    attributes.get("class").map(|class| class.to_string())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(vec![BACKGROUND_CLASS, SOLUTION_CLASS, WALL_CLASS], classes);
+        assert!(output.contains("<style>"));
+        assert!(output.contains(".maze-wall"));
+
+        let custom = document(
+            &maze,
+            &[&solve],
+            &DocumentOptions {
+                stylesheet: Some(".maze-wall {}".to_owned()),
+                ..options.clone()
+            },
+        )
+        .to_string();
+        assert!(custom.contains(".maze-wall {}"));
+        assert!(!custom.contains(DEFAULT_STYLESHEET));
+
+        let inline =
+            document(&maze, &[&solve], &DocumentOptions::default()).to_string();
+        assert!(!inline.contains("<style>") && !inline.contains("class="));
+    }
+
+    #[test]
+    fn document_classes_unsupported() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 3);
+        let grid = "red".parse::<GridRenderer>().unwrap();
+        let options = DocumentOptions {
+            style: StyleMode::Classes,
+            ..Default::default()
+        };
+        let output = document(&maze, &[&grid], &options).to_string();
+
+        let (styled, classed) = svg::read(&output)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag(_, _, attributes) => Some(attributes),
+                _ => None,
+            })
+            .fold((0, 0), |(styled, classed), attributes| {
+                (
+                    styled + attributes.contains_key("stroke") as usize,
+                    classed + attributes.contains_key("class") as usize,
+                )
+            });
+        assert_eq!((1, 1), (styled, classed));
+        assert!(output.contains(&format!(r#"class="{}""#, WALL_CLASS)));
+    }
+
+    #[test]
+    fn contact_sheet_layout() {
+        let mazes = [(5, 3), (2, 7), (4, 4), (6, 1), (3, 3)]
+            .iter()
+            .zip(
+                [maze::Shape::Hex, maze::Shape::Quad, maze::Shape::Tri]
+                    .iter()
+                    .cycle(),
+            )
+            .map(|(&(width, height), &shape)| Maze::new(shape, width, height))
+            .collect::<Vec<_>>();
+        let output = contact_sheet(&mazes.iter().collect::<Vec<_>>(), 2, 0.5)
+            .to_string();
+
+        let viewbox = root(&output)["viewBox"]
+            .split(' ')
+            .map(|v| v.parse::<f32>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(0.0, viewbox[0]);
+        assert_eq!(0.0, viewbox[1]);
+
+        let translations = svg::read(&output)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag(
+                    "g",
+                    svg::node::element::tag::Type::Start,
+                    attributes,
+                ) => Some(
+                    attributes["transform"]
+                        .trim_start_matches("translate(")
+                        .trim_end_matches(')')
+                        .split(' ')
+                        .map(|v| v.parse::<f32>().unwrap())
+                        .collect::<Vec<_>>(),
+                ),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(mazes.len(), translations.len());
+
+        let boxes = mazes
+            .iter()
+            .zip(translations)
+            .map(|(maze, translation)| {
+                let viewbox = maze.viewbox();
+                physical::ViewBox {
+                    corner: physical::Pos {
+                        x: viewbox.corner.x + translation[0],
+                        y: viewbox.corner.y + translation[1],
+                    },
+                    ..viewbox
+                }
+            })
+            .collect::<Vec<_>>();
+        let epsilon = 0.0001;
+        for (i, a) in boxes.iter().enumerate() {
+            assert!(a.corner.x > -epsilon && a.corner.y > -epsilon);
+            assert!(a.corner.x + a.width < viewbox[2] + epsilon);
+            assert!(a.corner.y + a.height < viewbox[3] + epsilon);
+            for b in boxes.iter().skip(i + 1) {
+                assert!(
+                    a.corner.x + a.width <= b.corner.x + epsilon
+                        || b.corner.x + b.width <= a.corner.x + epsilon
+                        || a.corner.y + a.height <= b.corner.y + epsilon
+                        || b.corner.y + b.height <= a.corner.y + epsilon,
+                    "{:?} overlaps {:?}",
+                    a,
+                    b,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn contact_sheet_empty() {
+        let output = contact_sheet::<()>(&[], 3, 1.0).to_string();
+        assert!(output.contains(r#"viewBox="0 0 0 0""#));
+        assert!(!output.contains("<g"));
+    }
+
+    #[test]
+    fn document_transparent() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 3);
+        let output =
+            document(&maze, &[], &DocumentOptions::default()).to_string();
+
+        assert!(!output.contains("<rect"));
+        assert!(!output.contains("seed="));
+        assert!(output.contains("<path"));
+    }
+
+    #[test]
+    fn write_svg_normalized_origin() {
+        let maze = Maze::new(maze::Shape::Tri, 5, 3);
+        let (scale, margin) = (Scale(10.0), 5.0);
+        let mut buffer = Vec::new();
+        write_svg_normalized(&maze, scale, margin, &[], &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        let expected = maze_to_viewbox(&maze, scale, margin);
+        let viewbox = root(&output)["viewBox"]
+            .split(' ')
+            .map(|v| v.parse::<f32>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![0.0, 0.0, expected.width, expected.height], viewbox,);
+
+        let (_, offset) = maze.normalized_viewbox();
+        assert!(output.contains(&format!(
+            r#"<g transform="translate({} {})">"#,
+            offset.x + margin / scale.0,
+            offset.y + margin / scale.0,
+        )));
+        assert!(output.trim_end().ends_with("</g>\n</svg>"));
+    }
+
+    #[test]
+    fn render_region_matches_maze() {
+        let maze = Maze::new(maze::Shape::Hex, 12, 10).initialize(
+            maze::initialize::Method::Winding,
+            &mut maze::initialize::LFSR::new(12345),
+        );
+        let viewbox = physical::ViewBox {
+            corner: physical::Pos { x: 3.0, y: 2.5 },
+            width: 5.0,
+            height: 4.0,
+        };
+        let document = render_region(&maze, viewbox, &[]).to_string();
+
+        let (x, y, width, height) = viewbox.tuple();
+        assert_eq!(
+            format!("{} {} {} {}", x, y, width, height),
+            root(&document)["viewBox"].to_string(),
+        );
+
+        let mut full = Vec::new();
+        write_svg(&maze, &[], &mut full).unwrap();
+        let full = segments(&String::from_utf8(full).unwrap());
+        let region = segments(&document);
+
+        let inside = |((x1, y1), (x2, y2)): ((i32, i32), (i32, i32))| {
+            let (left, top) = ((x * 1000.0) as i32, (y * 1000.0) as i32);
+            let (right, bottom) = (
+                ((x + width) * 1000.0) as i32,
+                ((y + height) * 1000.0) as i32,
+            );
+            [(x1, y1), (x2, y2)].iter().all(|&(x, y)| {
+                x >= left && y >= top && x <= right && y <= bottom
+            })
+        };
+        assert!(region.iter().any(|&s| inside(s)));
+        for &s in full.iter().filter(|&&s| inside(s)) {
+            assert!(region.contains(&s));
+        }
+        assert!(region.is_subset(&full));
+    }
+
+    /// Extracts all line segments from the paths of a document.
+    ///
+    /// # Arguments
+    /// *  `document` - The SVG source.
+    fn segments(document: &str) -> HashSet<((i32, i32), (i32, i32))> {
+        use svg::node::element::path::{Command, Data};
+
+        let mut result = HashSet::new();
+        for event in svg::read(document).unwrap() {
+            if let svg::parser::Event::Tag("path", _, attributes) = event {
+                let mut current = physical::Pos { x: 0.0, y: 0.0 };
+                for command in Data::parse(&attributes["d"]).unwrap().iter() {
+                    match command {
+                        Command::Move(_, p) => {
+                            current = physical::Pos { x: p[0], y: p[1] };
+                        }
+                        Command::Line(_, p) => {
+                            let next = physical::Pos { x: p[0], y: p[1] };
+                            result.insert(segment(current, next));
+                            current = next;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    /// A comparable representation of an undirected line segment.
+    ///
+    /// # Arguments
+    /// *  `start` - The start of the segment.
+    /// *  `end` - The end of the segment.
+    fn segment(
+        start: physical::Pos,
+        end: physical::Pos,
+    ) -> ((i32, i32), (i32, i32)) {
+        let round = |p: physical::Pos| {
+            ((p.x * 1000.0).round() as i32, (p.y * 1000.0).round() as i32)
+        };
+        let (a, b) = (round(start), round(end));
+        if a < b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// Extracts the attributes of the root element of a document.
+    ///
+    /// # Arguments
+    /// *  `document` - The SVG source.
+    fn root(document: &str) -> svg::node::Attributes {
+        svg::read(document)
+            .unwrap()
+            .find_map(|event| match event {
+                svg::parser::Event::Tag(
+                    "svg",
+                    svg::node::element::tag::Type::Start,
+                    attributes,
+                ) => Some(attributes),
+                _ => None,
+            })
+            .unwrap()
+    }
+}
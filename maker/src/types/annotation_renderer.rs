@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use svg::Node;
+
+use maze_tools::image::Color;
+
+use crate::types::*;
+
+/// An overlay marking the structure of a maze.
+///
+/// The centre of every dead end, a room with one open wall, and of every
+/// junction, a room with at least three open walls, is marked with a dot.
+#[derive(Clone)]
+pub struct AnnotationRenderer {
+    /// The colour of dots marking dead ends.
+    pub dead_end: Color,
+
+    /// The colour of dots marking junctions.
+    pub junction: Color,
+}
+
+impl AnnotationRenderer {
+    /// Whether a room is a dead end.
+    ///
+    /// # Arguments
+    /// *  `room` - The room.
+    pub fn is_dead_end<T>(room: &maze::room::Room<T>) -> bool
+    where
+        T: Clone,
+    {
+        room.visited && room.open_walls() == 1
+    }
+
+    /// Whether a room is a junction.
+    ///
+    /// # Arguments
+    /// *  `room` - The room.
+    pub fn is_junction<T>(room: &maze::room::Room<T>) -> bool
+    where
+        T: Clone,
+    {
+        room.visited && room.open_walls() >= 3
+    }
+}
+
+impl FromStr for AnnotationRenderer {
+    type Err = String;
+
+    /// Converts a string to an annotation description.
+    ///
+    /// The string must be on the form `"dead-end-colour,junction-colour"`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut parts = s.split(',').map(str::trim);
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some(dead_end), Some(junction), None) => Ok(Self {
+                dead_end: dead_end.parse()?,
+                junction: junction.parse()?,
+            }),
+            _ => Err(format!("invalid annotation: {}", s)),
+        }
+    }
+}
+
+impl<T> Renderer<T> for AnnotationRenderer
+where
+    T: Clone,
+{
+    /// Renders the annotations.
+    ///
+    /// Only visited rooms are considered.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the annotations.
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
+        let centers = maze.center_cache();
+        let dot = |pos| {
+            let center = centers[pos];
+            svg::node::element::Circle::new()
+                .set("cx", center.x)
+                .set("cy", center.y)
+                .set("r", 0.2)
+        };
+
+        let mut dead_ends = svg::node::element::Group::new()
+            .set("fill", self.dead_end.to_css());
+        let mut junctions = svg::node::element::Group::new()
+            .set("fill", self.junction.to_css());
+        for pos in maze.positions() {
+            if Self::is_dead_end(&maze[pos]) {
+                dead_ends.append(dot(pos));
+            } else if Self::is_junction(&maze[pos]) {
+                junctions.append(dot(pos));
+            }
+        }
+
+        group.append(dead_ends);
+        group.append(junctions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze::initialize;
+
+    use super::*;
+
+    #[test]
+    fn dots_match_structure() {
+        for shape in [maze::Shape::Hex, maze::Shape::Quad, maze::Shape::Tri] {
+            let maze = Maze::new(shape, 10, 8).initialize(
+                initialize::Method::Branching,
+                &mut initialize::LFSR::new(12345),
+            );
+            let renderer =
+                "#ff0000,#0000ff".parse::<AnnotationRenderer>().unwrap();
+            let mut group = svg::node::element::Group::new();
+            renderer.render(&maze, &mut group);
+
+            // Count the dots in each group by their colour
+            let output = group.to_string();
+            let mut fill = String::new();
+            let (mut dead_ends, mut junctions) = (0, 0);
+            for event in svg::read(&output).unwrap() {
+                match event {
+                    svg::parser::Event::Tag(
+                        "g",
+                        svg::node::element::tag::Type::Start,
+                        attributes,
+                    ) => {
+                        fill = attributes
+                            .get("fill")
+                            .map(ToString::to_string)
+                            .unwrap_or_default()
+                    }
+                    svg::parser::Event::Tag("circle", _, _) => {
+                        if fill == renderer.dead_end.to_css() {
+                            dead_ends += 1;
+                        } else if fill == renderer.junction.to_css() {
+                            junctions += 1;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            let count = |predicate: fn(usize) -> bool| {
+                maze.positions()
+                    .filter(|&pos| predicate(maze[pos].open_walls()))
+                    .count()
+            };
+            assert_eq!(count(|open| open == 1), dead_ends, "for {:?}", shape,);
+            assert_eq!(count(|open| open >= 3), junctions, "for {:?}", shape,);
+            assert!(dead_ends > 0 && junctions > 0);
+        }
+    }
+
+    #[test]
+    fn unvisited_ignored() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 5);
+        let mut group = svg::node::element::Group::new();
+        "red,blue"
+            .parse::<AnnotationRenderer>()
+            .unwrap()
+            .render(&maze, &mut group);
+
+        assert!(!group.to_string().contains("<circle"));
+    }
+
+    #[test]
+    fn invalid_annotation() {
+        assert!("red".parse::<AnnotationRenderer>().is_err());
+        assert!("red,blue,green".parse::<AnnotationRenderer>().is_err());
+        assert!("red,not a colour".parse::<AnnotationRenderer>().is_err());
+    }
+}
@@ -31,7 +31,10 @@ impl FromStr for BackgroundRenderer {
     }
 }
 
-impl Renderer for BackgroundRenderer {
+impl<T> Renderer<T> for BackgroundRenderer
+where
+    T: Clone,
+{
     /// Applies the background action.
     ///
     /// This action will use an image to sample the background colour of rooms.
@@ -39,7 +42,11 @@ impl Renderer for BackgroundRenderer {
     /// # Arguments
     /// *  `maze` - The maze.
     /// *  `group` - The group to which to add the rooms.
-    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
         let physical::ViewBox { width, height, .. } = maze.viewbox();
         let (cols, rows) = self.image.dimensions();
         let data = self
@@ -56,7 +63,7 @@ impl Renderer for BackgroundRenderer {
             })
             .split_by(&maze.shape(), maze.width(), maze.height());
 
-        group.append(draw_rooms(maze, |pos| data[pos]));
+        group.append(draw_rooms(maze, |pos| data[pos], 1.0));
     }
 }
 
@@ -0,0 +1,166 @@
+use std::str::FromStr;
+
+use svg::Node;
+
+use maze::analysis;
+use maze_tools::image::Color;
+
+use crate::types::*;
+
+/// The outer border of a maze.
+#[derive(Clone)]
+pub struct BorderRenderer {
+    /// The colour of the border.
+    pub color: Color,
+
+    /// The stroke width of the border.
+    pub width: f32,
+}
+
+impl FromStr for BorderRenderer {
+    type Err = String;
+
+    /// Converts a string to a border description.
+    ///
+    /// The string must be on the form `colour` or `colour,width`. The width
+    /// defaults to `0.8`, twice the width of interior walls.
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut parts = s.split(',').map(str::trim);
+        let color = parts.next().map(Color::from_str).unwrap()?;
+
+        if let Some(part1) = parts.next() {
+            if let Ok(width) = part1.parse() {
+                Ok(Self { color, width })
+            } else {
+                Err(format!("invalid width: {}", part1))
+            }
+        } else {
+            Ok(Self { color, width: 0.8 })
+        }
+    }
+}
+
+impl<T> Renderer<T> for BorderRenderer
+where
+    T: Clone,
+{
+    /// Renders the outer border.
+    ///
+    /// The border separates visited rooms from the rest, so for masked mazes
+    /// it follows the outline of the mask. Every closed outline is drawn as a
+    /// single continuous line.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the border.
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
+        let mut data = svg::node::element::path::Data::new();
+        for outline in analysis::outline(maze) {
+            data = outline.iter().enumerate().fold(data, |data, (i, pos)| {
+                if i == 0 {
+                    data.move_to((pos.x, pos.y))
+                } else {
+                    data.line_to((pos.x, pos.y))
+                }
+            });
+            data = data.close();
+        }
+
+        group.append(
+            svg::node::element::Path::new()
+                .set("fill", "none")
+                .set("stroke", self.color.to_css())
+                .set("stroke-linecap", "round")
+                .set("stroke-linejoin", "round")
+                .set("stroke-width", self.width)
+                .set("vector-effect", "non-scaling-stroke")
+                .set("d", data),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze::initialize;
+    use svg::node::element::path::{Command, Data};
+
+    use super::*;
+
+    /// Renders the border of a maze and returns the path commands.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    fn commands(maze: &Maze) -> Vec<Command> {
+        let mut group = svg::node::element::Group::new();
+        "black,2"
+            .parse::<BorderRenderer>()
+            .unwrap()
+            .render(maze, &mut group);
+
+        let output = group.to_string();
+        svg::read(&output)
+            .unwrap()
+            .find_map(|event| match event {
+                svg::parser::Event::Tag("path", _, attributes) => {
+                    Some(Data::parse(&attributes["d"]).unwrap().to_vec())
+                }
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn rectangular_perimeter() {
+        let (width, height) = (7, 4);
+        let maze = Maze::new(maze::Shape::Quad, width, height).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let commands = commands(&maze);
+
+        assert!(matches!(commands[0], Command::Move(..)));
+        assert!(matches!(commands.last(), Some(Command::Close)));
+        assert_eq!(
+            2 * (width + height),
+            commands
+                .iter()
+                .filter(|c| matches!(c, Command::Move(..) | Command::Line(..)))
+                .count(),
+        );
+        assert_eq!(
+            1,
+            commands
+                .iter()
+                .filter(|c| matches!(c, Command::Move(..)))
+                .count(),
+        );
+    }
+
+    #[test]
+    fn masked_perimeter() {
+        // A plus-shaped mask has twelve corners
+        let maze = Maze::new(maze::Shape::Quad, 3, 3).initialize_filter(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+            |pos| pos.col == 1 || pos.row == 1,
+        );
+        let commands = commands(&maze);
+
+        assert_eq!(
+            12,
+            commands
+                .iter()
+                .filter(|c| matches!(c, Command::Move(..) | Command::Line(..)))
+                .count(),
+        );
+    }
+
+    #[test]
+    fn invalid_width() {
+        assert!("black,wide".parse::<BorderRenderer>().is_err());
+    }
+}
@@ -51,9 +51,14 @@ where
     /// # Arguments
     /// *  `maze` - The maze.
     /// *  `rng` - A random number generator.
+    ///
+    /// # Panics
+    /// This method panics if the heat map type refers to rooms outside of the
+    /// maze; use [`HeatMapType::validate`] to check this beforehand.
     fn post_process(&self, mut maze: Maze, rng: &mut R) -> Maze {
         for _ in 0..self.count {
-            let heat_map = self.map_type.generate(&maze);
+            let heat_map =
+                self.map_type.generate(&maze).expect("invalid heat map");
             for pos in heat_map.positions() {
                 if 1.0 / (rng.random() * f64::from(heat_map[pos])) < 0.5 {
                     loop {
@@ -0,0 +1,147 @@
+use std::str::FromStr;
+
+use svg::Node;
+
+use maze_tools::image::Color;
+
+use crate::types::*;
+
+/// A debugging overlay showing the room lattice.
+///
+/// The centre of every room is marked with a dot, and lines connect the
+/// centres of neighbouring rooms.
+#[derive(Clone)]
+pub struct GridRenderer {
+    /// The colour of the lines and dots.
+    pub color: Color,
+}
+
+impl FromStr for GridRenderer {
+    type Err = String;
+
+    /// Converts a string to a grid description.
+    ///
+    /// The string must be a colour.
+    fn from_str(s: &str) -> Result<Self, String> {
+        Ok(Self {
+            color: s.trim().parse()?,
+        })
+    }
+}
+
+impl<T> Renderer<T> for GridRenderer
+where
+    T: Clone,
+{
+    /// Renders the lattice.
+    ///
+    /// All rooms are drawn, regardless of whether they have been visited.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the lattice.
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
+        let centers = maze.center_cache();
+
+        // Draw every line only once, from the lesser position
+        let mut data = svg::node::element::path::Data::new();
+        for pos in maze.positions() {
+            for next in
+                maze.adjacent_inside(pos).into_iter().filter(|&n| pos < n)
+            {
+                let (a, b) = (centers[pos], centers[next]);
+                data = data.move_to((a.x, a.y)).line_to((b.x, b.y));
+            }
+        }
+        let mut lattice = svg::node::element::Group::new()
+            .set("fill", self.color.to_css())
+            .add(
+                svg::node::element::Path::new()
+                    .set("fill", "none")
+                    .set("stroke", self.color.to_css())
+                    .set("stroke-width", 0.05)
+                    .set("d", data),
+            );
+
+        for pos in maze.positions() {
+            let center = centers[pos];
+            lattice.append(
+                svg::node::element::Circle::new()
+                    .set("cx", center.x)
+                    .set("cy", center.y)
+                    .set("r", 0.08),
+            );
+        }
+
+        group.append(lattice);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_dot_per_room() {
+        for shape in [maze::Shape::Hex, maze::Shape::Quad, maze::Shape::Tri] {
+            let maze = Maze::new(shape, 5, 4);
+            let mut group = svg::node::element::Group::new();
+            "red"
+                .parse::<GridRenderer>()
+                .unwrap()
+                .render(&maze, &mut group);
+
+            let output = group.to_string();
+            let dots = svg::read(&output)
+                .unwrap()
+                .filter(|event| {
+                    matches!(event, svg::parser::Event::Tag("circle", _, _))
+                })
+                .count();
+
+            assert_eq!(maze.positions().count(), dots, "for {:?}", shape);
+        }
+    }
+
+    #[test]
+    fn one_line_per_neighbour_pair() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 4);
+        let mut group = svg::node::element::Group::new();
+        "red"
+            .parse::<GridRenderer>()
+            .unwrap()
+            .render(&maze, &mut group);
+
+        let output = group.to_string();
+        let moves = svg::read(&output)
+            .unwrap()
+            .find_map(|event| match event {
+                svg::parser::Event::Tag("path", _, attributes) => Some(
+                    svg::node::element::path::Data::parse(&attributes["d"])
+                        .unwrap()
+                        .iter()
+                        .filter(|c| {
+                            matches!(
+                                c,
+                                svg::node::element::path::Command::Move(..)
+                            )
+                        })
+                        .count(),
+                ),
+                _ => None,
+            })
+            .unwrap();
+
+        // A 5 x 4 quad grid has 4 * 4 horizontal and 5 * 3 vertical pairs
+        assert_eq!(4 * 4 + 5 * 3, moves);
+    }
+
+    #[test]
+    fn invalid_color() {
+        assert!("not a colour".parse::<GridRenderer>().is_err());
+    }
+}
@@ -27,9 +27,10 @@ impl FromStr for HeatMapRenderer {
     /// The string can be on three forms:
     /// 1. `map_type`: If only a value that can be made into a
     ///    [`HeatMapType`](HeatMapType) is passed, the `from` and `to` values
-    ///    will be `#000000FF` and `#FFFF0000`.
+    ///    will be `#0000FF00` and `#FF0000FF`.
     /// 2. `map_type,colour`: If only one colour is passed, the `from` and `to`
-    ///    values will be `#00000000` and the colour passed.
+    ///    values will be the colour passed made fully transparent, and the
+    ///    colour passed.
     /// 3. `map_type,from,to`: If two colours are passed, they are used as
     ///    `from` and `to` values.
     fn from_str(s: &str) -> Result<Self, String> {
@@ -70,7 +71,10 @@ impl FromStr for HeatMapRenderer {
     }
 }
 
-impl Renderer for HeatMapRenderer {
+impl<T> Renderer<T> for HeatMapRenderer
+where
+    T: Clone + Sync,
+{
     /// Applies the heat map action.
     ///
     /// This action will calculate a heat map, and use the heat of each room to
@@ -79,11 +83,24 @@ impl Renderer for HeatMapRenderer {
     /// # Arguments
     /// *  `maze` - The maze.
     /// *  `group` - The group to which to add the rooms.
-    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
-        let matrix = self.map_type.generate(maze);
-        let max = *matrix.values().max().unwrap() as f32;
-        group.append(draw_rooms(maze, |pos| {
-            self.to.fade(self.from, matrix[pos] as f32 / max)
-        }));
+    ///
+    /// # Panics
+    /// This method panics if the heat map type refers to rooms outside of the
+    /// maze; use [`HeatMapType::validate`] to check this beforehand.
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
+        let matrix = self
+            .map_type
+            .generate(maze)
+            .expect("invalid heat map")
+            .normalized();
+        group.append(draw_rooms(
+            maze,
+            |pos| self.to.fade(self.from, matrix[pos]),
+            1.0,
+        ));
     }
 }
@@ -0,0 +1,134 @@
+use svg::Node;
+
+use maze::export::escape;
+use maze::matrix;
+
+use crate::types::*;
+
+/// Labels drawn at the centre of rooms.
+#[derive(Clone)]
+pub struct LabelRenderer {
+    /// The labels for every room. Empty strings are not drawn.
+    labels: matrix::Matrix<String>,
+
+    /// The font size, relative to the width of a room.
+    size: f32,
+}
+
+impl LabelRenderer {
+    /// Creates a label renderer with a default font size.
+    ///
+    /// # Arguments
+    /// *  `labels` - The labels for every room.
+    pub fn new(labels: matrix::Matrix<String>) -> Self {
+        Self { labels, size: 0.5 }
+    }
+
+    /// Sets the font size, relative to the width of a room.
+    ///
+    /// # Arguments
+    /// *  `size` - The font size.
+    pub fn with_size(self, size: f32) -> Self {
+        Self { size, ..self }
+    }
+}
+
+impl<T> Renderer<T> for LabelRenderer
+where
+    T: Clone,
+{
+    /// Renders the labels.
+    ///
+    /// Labels for rooms that have not been visited are not drawn.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the labels.
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
+        let room_width = maze.viewbox().width / maze.width() as f32;
+        let mut labels = svg::node::element::Group::new()
+            .set("font-size", self.size * room_width)
+            .set("text-anchor", "middle")
+            .set("dominant-baseline", "central");
+
+        for pos in maze.visited_positions() {
+            let label = match self.labels.get(pos) {
+                Some(label) if !label.is_empty() => label,
+                _ => continue,
+            };
+            let center = maze.center(pos);
+            labels.append(
+                svg::node::element::Text::new()
+                    .set("x", center.x)
+                    .set("y", center.y)
+                    .add(svg::node::Text::new(escape(label))),
+            );
+        }
+
+        group.append(labels);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze::initialize;
+
+    use super::*;
+
+    #[test]
+    fn one_text_per_label() {
+        let maze = Maze::new(maze::Shape::Hex, 5, 4).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let labels = matrix::Matrix::new_with_data(5, 4, |pos| {
+            if pos.col % 2 == 0 {
+                format!("{}<{}", pos.col, pos.row)
+            } else {
+                String::new()
+            }
+        });
+        let mut group = svg::node::element::Group::new();
+        LabelRenderer::new(labels.clone()).render(&maze, &mut group);
+
+        let output = group.to_string();
+        let texts = svg::read(&output)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag(
+                    "text",
+                    svg::node::element::tag::Type::Start,
+                    attributes,
+                ) => Some((
+                    attributes["x"].parse::<f32>().unwrap(),
+                    attributes["y"].parse::<f32>().unwrap(),
+                )),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let expected = maze
+            .positions()
+            .filter(|&pos| !labels[pos].is_empty())
+            .map(|pos| maze.center(pos))
+            .map(|center| (center.x, center.y))
+            .collect::<Vec<_>>();
+
+        assert_eq!(expected, texts);
+        assert!(output.contains("0&lt;1"));
+        assert!(!output.contains("0<1"));
+    }
+
+    #[test]
+    fn unvisited_rooms_skipped() {
+        let maze = Maze::new(maze::Shape::Quad, 3, 3);
+        let labels = matrix::Matrix::new_with_data(3, 3, |_| "x".to_string());
+        let mut group = svg::node::element::Group::new();
+        LabelRenderer::new(labels).render(&maze, &mut group);
+
+        assert!(!group.to_string().contains("<text"));
+    }
+}
@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use maze::initialize;
+
+use crate::types::*;
+
+/// A post processor opening random walls to add loops.
+#[derive(Clone)]
+pub struct LoopifyPostProcessor {
+    /// The fraction of closed internal walls to open, in the range _[0, 1]_.
+    pub density: f32,
+}
+
+impl FromStr for LoopifyPostProcessor {
+    type Err = String;
+
+    /// Converts a string to a loopify description.
+    ///
+    /// The string must be a number in the range _[0, 1]_, which is used as
+    /// `density`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.trim().parse::<f32>() {
+            Ok(density) if (0.0..=1.0).contains(&density) => {
+                Ok(Self { density })
+            }
+            _ => Err(format!("invalid density: {}", s)),
+        }
+    }
+}
+
+impl<R> PostProcessor<R> for LoopifyPostProcessor
+where
+    R: initialize::Randomizer + Sized + Send + Sync,
+{
+    /// Applies the loopify action.
+    ///
+    /// This action opens a fraction of the closed walls between visited rooms,
+    /// selected uniformly at random. Unlike braiding, walls anywhere in the
+    /// maze are considered, not only those of dead ends.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `rng` - A random number generator.
+    fn post_process(&self, mut maze: Maze, rng: &mut R) -> Maze {
+        // Every wall is considered from the room with the lowest position only
+        let mut wall_positions = maze
+            .visited_positions()
+            .flat_map(|pos| maze.internal_wall_positions(pos))
+            .filter(|&wall_pos| {
+                let (back, _) = maze.back(wall_pos);
+                back > wall_pos.0
+                    && maze[back].visited
+                    && !maze.is_open(wall_pos)
+            })
+            .collect::<Vec<_>>();
+
+        // The walls are selected by a partial shuffle to avoid duplicates
+        let count = ((wall_positions.len() as f32 * self.density).round()
+            as usize)
+            .min(wall_positions.len());
+        for i in 0..count {
+            let j = rng.range(i, wall_positions.len());
+            wall_positions.swap(i, j);
+            maze.open(wall_positions[i]);
+        }
+
+        maze
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Counts the open walls between rooms of a maze.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    fn open_walls(maze: &Maze) -> usize {
+        maze.positions()
+            .flat_map(|pos| maze.internal_wall_positions(pos))
+            .filter(|&wall_pos| maze.is_open(wall_pos))
+            .count()
+            / 2
+    }
+
+    /// Creates a perfect maze.
+    fn perfect() -> Maze {
+        Maze::new(maze::Shape::Hex, 12, 10).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        )
+    }
+
+    #[test]
+    fn post_process_density() {
+        let maze = perfect();
+        let open = open_walls(&maze);
+        let closed = maze
+            .positions()
+            .flat_map(|pos| maze.internal_wall_positions(pos))
+            .count()
+            / 2
+            - open;
+
+        for density in [0.0, 0.25, 0.5, 1.0] {
+            let loopified = LoopifyPostProcessor { density }
+                .post_process(maze.clone(), &mut initialize::LFSR::new(1));
+            let expected = (closed as f32 * density).round() as usize;
+            assert_eq!(
+                open + expected,
+                open_walls(&loopified),
+                "for density {}",
+                density,
+            );
+        }
+    }
+
+    #[test]
+    fn post_process_ignores_unvisited() {
+        let maze = Maze::new(maze::Shape::Quad, 6, 6).initialize_filter(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+            |pos| pos.col > 0,
+        );
+        let loopified = LoopifyPostProcessor { density: 1.0 }
+            .post_process(maze, &mut initialize::LFSR::new(1));
+
+        for row in 0..6 {
+            let pos = matrix::Pos { col: 0, row };
+            assert_eq!(0, loopified.doors(pos).count());
+        }
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            0.25,
+            "0.25".parse::<LoopifyPostProcessor>().unwrap().density
+        );
+        assert!("1.5".parse::<LoopifyPostProcessor>().is_err());
+        assert!("-0.1".parse::<LoopifyPostProcessor>().is_err());
+        assert!("many".parse::<LoopifyPostProcessor>().is_err());
+    }
+}
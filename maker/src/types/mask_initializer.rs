@@ -10,6 +10,9 @@ use super::*;
 /// intensity
 const D: f32 = 1.0 / 255.0 / 3.0;
 
+/// The number of pixels along each side of a character of an ASCII mask.
+const ASCII_RESOLUTION: u32 = 8;
+
 /// A masking image.
 #[derive(Clone)]
 pub struct MaskInitializer<R>
@@ -25,6 +28,109 @@ where
     _marker: ::std::marker::PhantomData<R>,
 }
 
+impl<R> MaskInitializer<R>
+where
+    R: initialize::Randomizer + Sized + Send + Sync,
+{
+    /// Creates a mask from an image.
+    ///
+    /// The image is stretched over the entire maze, and rooms where the
+    /// intensity of the image is above `threshold` are part of the maze.
+    ///
+    /// # Arguments
+    /// *  `image` - The mask image.
+    /// *  `threshold` - The intensity threshold, a value between 0 and 1.
+    pub fn from_image(image: image::RgbImage, threshold: f32) -> Self {
+        Self {
+            image,
+            threshold,
+            _marker: ::std::marker::PhantomData,
+        }
+    }
+
+    /// Creates a mask from an ASCII template.
+    ///
+    /// Every line of the template is a row, and every character a column. The
+    /// character `'#'` marks a room that is part of the maze, and `' '` marks
+    /// a hole. Lines shorter than the longest line are padded with holes.
+    ///
+    /// The template is stretched over the entire maze, so for a quad maze
+    /// with the same dimensions as the template, every character corresponds
+    /// to exactly one room.
+    ///
+    /// # Arguments
+    /// *  `template` - The template.
+    ///
+    /// # Errors
+    /// This function returns an error if the template is empty or contains
+    /// any other characters.
+    pub fn from_ascii(template: &str) -> Result<Self, String> {
+        let lines = template.lines().collect::<Vec<_>>();
+        let cols = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        if cols == 0 {
+            return Err("empty mask".to_owned());
+        }
+
+        let mut image = image::RgbImage::new(
+            cols as u32 * ASCII_RESOLUTION,
+            lines.len() as u32 * ASCII_RESOLUTION,
+        );
+        for (row, line) in lines.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let value = match c {
+                    '#' => 255,
+                    ' ' => 0,
+                    c => {
+                        return Err(format!(
+                            "invalid mask character {:?} at {}:{}",
+                            c,
+                            row + 1,
+                            col + 1,
+                        ))
+                    }
+                };
+                for dy in 0..ASCII_RESOLUTION {
+                    for dx in 0..ASCII_RESOLUTION {
+                        image.put_pixel(
+                            col as u32 * ASCII_RESOLUTION + dx,
+                            row as u32 * ASCII_RESOLUTION + dy,
+                            image::Rgb([value, value, value]),
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(Self::from_image(image, 0.5))
+    }
+
+    /// Calculates the rooms of a maze that are part of this mask.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to which to apply the mask.
+    pub fn candidates(&self, maze: &Maze) -> matrix::Matrix<bool> {
+        let physical::ViewBox { width, height, .. } = maze.viewbox();
+        let (cols, rows) = self.image.dimensions();
+        self.image
+            .enumerate_pixels()
+            .map(|(x, y, pixel)| {
+                (
+                    physical::Pos {
+                        x: width * (x as f32 / cols as f32),
+                        y: height * (y as f32 / rows as f32),
+                    },
+                    Intermediate::from(pixel),
+                )
+            })
+            .split_by(&maze.shape(), maze.width(), maze.height())
+            .map(|&v| v > self.threshold)
+    }
+}
+
 impl<R> FromStr for MaskInitializer<R>
 where
     R: initialize::Randomizer + Sized + Send + Sync,
@@ -44,13 +150,12 @@ where
 
         if let Some(part1) = parts.next() {
             if let Ok(threshold) = part1.parse() {
-                Ok(Self {
-                    image: image::open(path)
+                Ok(Self::from_image(
+                    image::open(path)
                         .map_err(|_| format!("failed to open {}", s))?
                         .to_rgb8(),
                     threshold,
-                    _marker: ::std::marker::PhantomData,
-                })
+                ))
             } else {
                 Err(format!("invalid threshold: {}", part1))
             }
@@ -74,22 +179,7 @@ where
     /// *  `rng` - A random number generator.
     /// *  `methods` - The initialisers to use to generate the maze.
     fn initialize(&self, maze: Maze, rng: &mut R, methods: Methods<R>) -> Maze {
-        let physical::ViewBox { width, height, .. } = maze.viewbox();
-        let (cols, rows) = self.image.dimensions();
-        let data = self
-            .image
-            .enumerate_pixels()
-            .map(|(x, y, pixel)| {
-                (
-                    physical::Pos {
-                        x: width * (x as f32 / cols as f32),
-                        y: height * (y as f32 / rows as f32),
-                    },
-                    Intermediate::from(pixel),
-                )
-            })
-            .split_by(&maze.shape(), maze.width(), maze.height())
-            .map(|&v| v > self.threshold);
+        let data = self.candidates(&maze);
 
         methods.initialize(maze, rng, |pos| data[pos])
     }
@@ -122,3 +212,50 @@ impl ops::Div<usize> for Intermediate {
         D * self.0 / divisor as f32
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A hand authored mask.
+    const TEMPLATE: &str = "\
+#### #
+#  # #
+######
+  ##";
+
+    #[test]
+    fn from_ascii_candidates() {
+        let mask =
+            MaskInitializer::<initialize::LFSR>::from_ascii(TEMPLATE).unwrap();
+        let maze = Maze::new(maze::Shape::Quad, 6, 4);
+        let candidates = mask.candidates(&maze);
+
+        for (row, line) in TEMPLATE.lines().enumerate() {
+            let line = format!("{:6}", line);
+            for (col, c) in line.chars().enumerate() {
+                assert_eq!(
+                    c == '#',
+                    candidates[matrix::Pos {
+                        col: col as isize,
+                        row: row as isize,
+                    }],
+                    "at {}:{}",
+                    col,
+                    row,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn from_ascii_invalid() {
+        assert!(MaskInitializer::<initialize::LFSR>::from_ascii("").is_err());
+        assert!(
+            MaskInitializer::<initialize::LFSR>::from_ascii("\n\n").is_err()
+        );
+        assert!(
+            MaskInitializer::<initialize::LFSR>::from_ascii("# x #").is_err()
+        );
+    }
+}
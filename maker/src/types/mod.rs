@@ -1,23 +1,41 @@
 use std::str::FromStr;
 
-use rayon::prelude::*;
 use svg::Node;
 
 use maze::initialize;
 use maze::matrix;
+use maze::physical;
 use maze_tools::image::Color;
 use maze_tools::voronoi;
 
+use crate::render::StyleMode;
+
 pub type Maze = maze::Maze<()>;
 
+pub mod annotation_renderer;
+pub use self::annotation_renderer::*;
 pub mod background_renderer;
 pub use self::background_renderer::*;
+pub mod border_renderer;
+pub use self::border_renderer::*;
 pub mod break_post_processor;
 pub use self::break_post_processor::*;
+pub mod grid_renderer;
+pub use self::grid_renderer::*;
 pub mod heatmap_renderer;
 pub use self::heatmap_renderer::*;
+pub mod label_renderer;
+pub use self::label_renderer::*;
+pub mod loopify_post_processor;
+pub use self::loopify_post_processor::*;
 pub mod mask_initializer;
 pub use self::mask_initializer::*;
+pub mod region_renderer;
+pub use self::region_renderer::*;
+pub mod ruler_renderer;
+pub use self::ruler_renderer::*;
+pub mod solution;
+pub use self::solution::*;
 pub mod solve_renderer;
 pub use solve_renderer::*;
 pub mod text_renderer;
@@ -96,7 +114,7 @@ impl<R> FromStr for Methods<R>
 where
     R: initialize::Randomizer + Sized + Send + Sync,
 {
-    type Err = String;
+    type Err = maze::ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut methods = vec![];
@@ -134,24 +152,98 @@ where
 }
 
 /// A trait for rendering a maze.
-pub trait Renderer {
+///
+/// The trait is generic over the room data of the maze, so renderers not
+/// relying on room data can be implemented for all mazes. It is object safe
+/// for every room data type.
+pub trait Renderer<T = ()>
+where
+    T: Clone,
+{
     /// Applies this action to a maze and SVG group.
     ///
     /// # Arguments
     /// *  `maze` - The maze.
     /// *  `group` - An SVG group.
-    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group);
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    );
+
+    /// Applies this action to a maze and SVG group using a specific style
+    /// mode.
+    ///
+    /// The default implementation ignores the style mode and calls
+    /// [`Renderer::render`], so the added elements keep their presentation
+    /// attributes. Only [`SolveRenderer`] supports [`StyleMode::Classes`].
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - An SVG group.
+    /// *  `_style` - How to style the added elements.
+    fn render_styled(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+        _style: StyleMode,
+    ) {
+        self.render(maze, group);
+    }
+
+    /// Applies this action to a maze and appends the output to a document.
+    ///
+    /// The output is added as a single group at the end of `document`, which
+    /// allows composing mazes into an existing scene. The walls of the maze
+    /// are not drawn.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `document` - The document to which to append the group.
+    fn render_into(&self, maze: &maze::Maze<T>, document: &mut svg::Document) {
+        let mut group = svg::node::element::Group::new();
+        self.render(maze, &mut group);
+        document.append(group);
+    }
+
+    /// Whether the walls of the maze should be drawn along with the output of
+    /// this renderer.
+    ///
+    /// If any renderer of a document returns `false`, the walls are omitted.
+    fn walls(&self) -> bool {
+        true
+    }
 }
 
-impl<T> Renderer for Option<T>
+impl<R, T> Renderer<T> for Option<R>
 where
-    T: Renderer,
+    R: Renderer<T>,
+    T: Clone,
 {
-    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
         if let Some(action) = self {
             action.render(maze, group);
         }
     }
+
+    fn render_styled(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+        style: StyleMode,
+    ) {
+        if let Some(action) = self {
+            action.render_styled(maze, group, style);
+        }
+    }
+
+    fn walls(&self) -> bool {
+        self.as_ref().is_none_or(|action| action.walls())
+    }
 }
 
 /// A type of heat map.
@@ -166,28 +258,128 @@ pub enum HeatMapType {
     /// The heat map is generated by travesing from every edge room to the one
     /// on the opposite side.
     Full,
+
+    /// The heat map is generated by traversing between explicit pairs of
+    /// rooms.
+    Custom(Vec<(matrix::Pos, matrix::Pos)>),
+
+    /// The heat map is generated by traversing from a centre room to every
+    /// room on the edge of the maze.
+    Radial {
+        /// The room from which to traverse.
+        center: matrix::Pos,
+    },
 }
 
 impl FromStr for HeatMapType {
-    type Err = String;
+    type Err = maze::ParseError;
 
+    /// Converts a string to a heat map type.
+    ///
+    /// Apart from the simple types `vertical`, `horizontal` and `full`, the
+    /// string may be on the form `radial:col:row`, or `custom:` followed by
+    /// pairs on the form `col:row:col:row` separated by `;`.
     fn from_str(s: &str) -> Result<HeatMapType, Self::Err> {
+        let error = || maze::ParseError::UnknownHeatMapType(s.to_owned());
         match s {
             "vertical" => Ok(HeatMapType::Vertical),
             "horizontal" => Ok(HeatMapType::Horizontal),
             "full" => Ok(HeatMapType::Full),
-            _ => Err(format!("unknown heat map type: {}", s)),
+            _ => {
+                if let Some(center) = s.strip_prefix("radial:") {
+                    match parse_positions(center).as_deref() {
+                        Some(&[center]) => Ok(HeatMapType::Radial { center }),
+                        _ => Err(error()),
+                    }
+                } else if let Some(pairs) = s.strip_prefix("custom:") {
+                    pairs
+                        .split(';')
+                        .map(|pair| match parse_positions(pair).as_deref() {
+                            Some(&[from, to]) => Ok((from, to)),
+                            _ => Err(error()),
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                        .map(HeatMapType::Custom)
+                } else {
+                    Err(error())
+                }
+            }
         }
     }
 }
 
+/// Parses a list of positions on the form `col:row:col:row...`.
+///
+/// # Arguments
+/// *  `s` - The string to parse.
+fn parse_positions(s: &str) -> Option<Vec<matrix::Pos>> {
+    let values = s
+        .split(':')
+        .map(|v| v.trim().parse::<isize>().ok())
+        .collect::<Option<Vec<_>>>()?;
+    if values.len() % 2 == 0 {
+        Some(
+            values
+                .chunks(2)
+                .map(|v| matrix::Pos {
+                    col: v[0],
+                    row: v[1],
+                })
+                .collect(),
+        )
+    } else {
+        None
+    }
+}
+
 impl HeatMapType {
+    /// Verifies that all rooms of this heat map type are inside of a maze.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze for which to generate a heat map.
+    ///
+    /// # Errors
+    /// An error is returned if a room of a custom pair, or the centre of a
+    /// radial heat map, is outside of the maze.
+    pub fn validate<T>(&self, maze: &maze::Maze<T>) -> Result<(), String>
+    where
+        T: Clone,
+    {
+        let outside = match self {
+            HeatMapType::Custom(pairs) => pairs
+                .iter()
+                .flat_map(|&(from, to)| [from, to])
+                .find(|&pos| !maze.is_inside(pos)),
+            HeatMapType::Radial { center } => {
+                Some(*center).filter(|&pos| !maze.is_inside(pos))
+            }
+            _ => None,
+        };
+        match outside {
+            Some(pos) => {
+                Err(format!("heat map room outside of maze: {:?}", pos))
+            }
+            None => Ok(()),
+        }
+    }
+
     /// Generates a heat map based on this heat map type.
     ///
     /// # Arguments
     /// *  `maze` - The maze for which to generate a heat map.
-    pub fn generate(&self, maze: &Maze) -> maze::matrix::Matrix<u32> {
-        match *self {
+    ///
+    /// # Errors
+    /// An error is returned if this heat map type refers to rooms outside of
+    /// the maze; see [`HeatMapType::validate`].
+    pub fn generate<T>(
+        &self,
+        maze: &maze::Maze<T>,
+    ) -> Result<maze::matrix::Matrix<u32>, String>
+    where
+        T: Clone + Sync,
+    {
+        self.validate(maze)?;
+        Ok(match self {
             HeatMapType::Vertical => self.create_heatmap(
                 maze,
                 (0..maze.width()).map(|col| {
@@ -232,33 +424,46 @@ impl HeatMapType {
                         )
                     }),
             ),
-        }
+            HeatMapType::Custom(pairs) => {
+                self.create_heatmap(maze, pairs.iter().cloned())
+            }
+            HeatMapType::Radial { center } => self.create_heatmap(
+                maze,
+                maze.positions()
+                    .filter(|&pos| {
+                        maze.walls(pos).iter().any(|wall| {
+                            !maze.is_inside(maze.back((pos, wall)).0)
+                        })
+                    })
+                    .map(|pos| (*center, pos)),
+            ),
+        })
     }
 
     /// Generates a heat map for a maze and an iteration of positions.
     ///
+    /// The positions are consumed in parallel as they are produced, without
+    /// collecting them first; see [`maze::heatmap_par`].
+    ///
     /// # Arguments
     /// *  `maze` - The maze for which to generate a heat map.
     /// *  `positions` - The positions for which to generate a heat map. These
-    ///   will be generated from the heat map type.
-    fn create_heatmap<I>(&self, maze: &Maze, positions: I) -> maze::HeatMap
+    ///    will be generated from the heat map type.
+    fn create_heatmap<I, T>(
+        &self,
+        maze: &maze::Maze<T>,
+        positions: I,
+    ) -> maze::HeatMap
     where
-        I: Iterator<Item = (maze::matrix::Pos, maze::matrix::Pos)>,
+        I: Iterator<Item = (maze::matrix::Pos, maze::matrix::Pos)> + Send,
+        T: Clone + Sync,
     {
-        let collected = positions.collect::<Vec<_>>();
-        collected
-            .chunks(collected.len() / rayon::current_num_threads())
-            .collect::<Vec<_>>()
-            .par_iter()
-            .map(|positions| maze::heatmap(maze, positions.iter().cloned()))
-            .reduce(
-                || maze::HeatMap::new(maze.width(), maze.height()),
-                std::ops::Add::add,
-            )
+        maze::heatmap_par(maze, positions)
     }
 }
 
 /// A source of random values.
+#[allow(clippy::upper_case_acronyms)]
 #[derive(Clone)]
 pub enum Random {
     /// A source of random values from the operating system.
@@ -276,8 +481,10 @@ impl Random {
 
     /// Creates a source of random values from an LFSR.
     ///
+    /// The sequence of values is fully determined by the seed.
+    ///
     /// # Arguments
-    /// *  `seed` The LFST seed.
+    /// *  `seed` - The LFSR seed.
     pub fn from_seed(seed: u64) -> Self {
         Self::LFSR(seed.into())
     }
@@ -303,44 +510,310 @@ impl initialize::Randomizer for Random {
 
 /// Draws all rooms of a maze.
 ///
+/// The outline of every room is shrunk towards its centre by `passage_ratio`;
+/// at `1.0` the rooms tile the maze, and lower values leave gaps between
+/// rooms, making the passages appear narrower than the walls.
+///
 /// # Arguments
 /// *  `maze` - The maze to draw.
 /// *  `colors` - A function determining the colour of a room.
-pub fn draw_rooms<F>(maze: &Maze, colors: F) -> svg::node::element::Group
+/// *  `passage_ratio` - The scale of each room outline relative to its centre.
+pub fn draw_rooms<F, T>(
+    maze: &maze::Maze<T>,
+    colors: F,
+    passage_ratio: f32,
+) -> svg::node::element::Group
 where
     F: Fn(maze::matrix::Pos) -> Color,
+    T: Clone,
 {
     let mut group = svg::node::element::Group::new();
-    for pos in maze.positions().filter(|&pos| maze[pos].visited) {
-        let color = colors(pos);
-        let mut commands = maze
-            .walls(pos)
-            .iter()
-            .enumerate()
-            .map(|(i, wall)| {
-                let (coords, _) = maze.corners((pos, wall));
-                if i == 0 {
-                    svg::node::element::path::Command::Move(
-                        svg::node::element::path::Position::Absolute,
-                        (coords.x, coords.y).into(),
-                    )
-                } else {
-                    svg::node::element::path::Command::Line(
-                        svg::node::element::path::Position::Absolute,
-                        (coords.x, coords.y).into(),
-                    )
-                }
+    for pos in maze.visited_positions() {
+        group.append(draw_room(maze, pos, colors(pos), passage_ratio));
+    }
+
+    group
+}
+
+/// Draws a single room of a maze.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `pos` - The room position.
+/// *  `color` - The colour of the room.
+/// *  `passage_ratio` - The scale of the room outline relative to its centre.
+pub fn draw_room<T>(
+    maze: &maze::Maze<T>,
+    pos: maze::matrix::Pos,
+    color: Color,
+    passage_ratio: f32,
+) -> svg::node::element::Path
+where
+    T: Clone,
+{
+    let mut commands = room_outline(maze, pos, passage_ratio)
+        .into_iter()
+        .enumerate()
+        .map(|(i, coords)| {
+            if i == 0 {
+                svg::node::element::path::Command::Move(
+                    svg::node::element::path::Position::Absolute,
+                    (coords.x, coords.y).into(),
+                )
+            } else {
+                svg::node::element::path::Command::Line(
+                    svg::node::element::path::Position::Absolute,
+                    (coords.x, coords.y).into(),
+                )
+            }
+        })
+        .collect::<Vec<_>>();
+    commands.push(svg::node::element::path::Command::Close);
+
+    svg::node::element::Path::new()
+        .set("fill", color.to_css())
+        .set("d", svg::node::element::path::Data::from(commands))
+}
+
+/// The corners of the outline of a room, shrunk towards its centre.
+///
+/// There is one corner for every wall of the room, in the order of the walls.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `pos` - The room position.
+/// *  `passage_ratio` - The scale of the room outline relative to its centre.
+pub fn room_outline<T>(
+    maze: &maze::Maze<T>,
+    pos: maze::matrix::Pos,
+    passage_ratio: f32,
+) -> Vec<physical::Pos>
+where
+    T: Clone,
+{
+    let center = maze.center(pos);
+    maze.walls(pos)
+        .iter()
+        .map(|wall| {
+            let (corner, _) = maze.corners((pos, wall));
+            center.lerp(corner, passage_ratio)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draw_rooms_visited() {
+        for shape in [maze::Shape::Hex, maze::Shape::Quad, maze::Shape::Tri] {
+            let maze = shape.create_visited(5, 3);
+            let group = draw_rooms(&maze, |_| Color::default(), 1.0);
+
+            let paths = svg::read(&group.to_string())
+                .unwrap()
+                .filter(|event| {
+                    matches!(event, svg::parser::Event::Tag("path", _, _))
+                })
+                .count();
+            assert_eq!(maze.positions().count(), paths, "for {:?}", shape);
+            assert_eq!(
+                0,
+                draw_rooms(
+                    &shape.create::<()>(5, 3),
+                    |_| Color::default(),
+                    1.0,
+                )
+                .get_children()
+                .len(),
+            );
+        }
+    }
+
+    #[test]
+    fn render_into_composes() {
+        let mut document = svg::Document::new()
+            .add(svg::node::element::Rectangle::new().set("id", "border"));
+        let solve = "red".parse::<SolveRenderer>().unwrap();
+        for seed in [1, 2] {
+            let maze = Maze::new(maze::Shape::Quad, 5, 3).initialize(
+                initialize::Method::Winding,
+                &mut initialize::LFSR::new(seed),
+            );
+            solve.render_into(&maze, &mut document);
+        }
+
+        let output = document.to_string();
+        let tags = svg::read(&output)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag(
+                    name,
+                    svg::node::element::tag::Type::Start
+                    | svg::node::element::tag::Type::Empty,
+                    _,
+                ) => Some(name),
+                _ => None,
             })
             .collect::<Vec<_>>();
-        commands.push(svg::node::element::path::Command::Close);
+        assert_eq!(vec!["svg", "rect", "g", "path", "g", "path"], tags);
+    }
 
-        group.append(
-            svg::node::element::Path::new()
-                .set("fill", color.to_string())
-                .set("fill-opacity", f32::from(color.alpha) / 255.0)
-                .set("d", svg::node::element::path::Data::from(commands)),
+    #[test]
+    fn room_outline_ratio() {
+        for shape in [maze::Shape::Hex, maze::Shape::Quad, maze::Shape::Tri] {
+            let maze = shape.create_visited(5, 3);
+            let pos = maze::matrix::Pos { col: 2, row: 1 };
+            let center = maze.center(pos);
+            let distances = |ratio| {
+                room_outline(&maze, pos, ratio)
+                    .into_iter()
+                    .map(|corner| corner.distance(center))
+                    .collect::<Vec<_>>()
+            };
+
+            let full = distances(1.0);
+            let half = distances(0.5);
+            assert_eq!(maze.walls(pos).len(), full.len());
+            for (full, half) in full.iter().zip(half.iter()) {
+                assert!(half < full, "for {:?}", shape);
+                assert!((full * 0.5 - half).abs() < 0.0001, "for {:?}", shape);
+            }
+            for (&wall, corner) in
+                maze.walls(pos).iter().zip(room_outline(&maze, pos, 1.0))
+            {
+                assert!((maze.corners((pos, wall)).0 - corner).value() < 1e-6);
+            }
+        }
+    }
+
+    #[test]
+    fn heatmap_type_from_str() {
+        assert!(matches!(
+            "radial:3:2".parse::<HeatMapType>(),
+            Ok(HeatMapType::Radial {
+                center: matrix::Pos { col: 3, row: 2 }
+            }),
+        ));
+        match "custom:0:0:4:3;1:2:3:0".parse::<HeatMapType>() {
+            Ok(HeatMapType::Custom(pairs)) => assert_eq!(
+                vec![
+                    (
+                        matrix::Pos { col: 0, row: 0 },
+                        matrix::Pos { col: 4, row: 3 },
+                    ),
+                    (
+                        matrix::Pos { col: 1, row: 2 },
+                        matrix::Pos { col: 3, row: 0 },
+                    ),
+                ],
+                pairs,
+            ),
+            _ => panic!("failed to parse custom heat map type"),
+        }
+        for s in [
+            "radial:3",
+            "radial:3:2:1:0",
+            "custom:0:0:4",
+            "custom:a:b:c:d",
+        ] {
+            assert!(matches!(
+                s.parse::<HeatMapType>(),
+                Err(maze::ParseError::UnknownHeatMapType(value)) if value == s,
+            ));
+        }
+    }
+
+    #[test]
+    fn methods_from_str_invalid() {
+        assert!(matches!(
+            "winding,invalid".parse::<Methods<initialize::LFSR>>(),
+            Err(maze::ParseError::UnknownMethod(value)) if value == "invalid",
+        ));
+    }
+
+    #[test]
+    fn heatmap_custom() {
+        let maze = Maze::new(maze::Shape::Quad, 9, 7).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let (from, to) = (
+            matrix::Pos { col: 0, row: 0 },
+            matrix::Pos { col: 8, row: 6 },
         );
+        let heatmap = HeatMapType::Custom(vec![(from, to)])
+            .generate(&maze)
+            .unwrap();
+        let path = maze.walk(from, to).unwrap().into_iter().collect::<Vec<_>>();
+
+        for pos in maze.positions() {
+            assert_eq!(u32::from(path.contains(&pos)), heatmap[pos]);
+        }
     }
 
-    group
+    #[test]
+    fn heatmap_outside() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 5).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        for s in ["custom:0:0:50:50", "custom:0:0:4:4;-1:0:4:4", "radial:5:2"] {
+            let map_type = s.parse::<HeatMapType>().unwrap();
+            assert!(map_type.validate(&maze).is_err(), "for {}", s);
+            assert!(map_type.generate(&maze).is_err(), "for {}", s);
+        }
+        for s in ["custom:0:0:4:4", "radial:2:2"] {
+            let map_type = s.parse::<HeatMapType>().unwrap();
+            assert!(map_type.generate(&maze).is_ok(), "for {}", s);
+        }
+    }
+
+    #[test]
+    fn heatmap_full_tiny() {
+        for size in [1, 2] {
+            let maze = Maze::new(maze::Shape::Quad, size, size).initialize(
+                initialize::Method::Winding,
+                &mut initialize::LFSR::new(12345),
+            );
+            let heatmap = HeatMapType::Full.generate(&maze).unwrap();
+
+            let expected = maze::heatmap(
+                &maze,
+                maze.positions()
+                    .filter(|&pos| pos.col == 0 || pos.row == 0)
+                    .map(|pos| {
+                        (
+                            pos,
+                            matrix::Pos {
+                                col: size as isize - 1 - pos.col,
+                                row: size as isize - 1 - pos.row,
+                            },
+                        )
+                    }),
+            );
+            assert_eq!(
+                expected.values().collect::<Vec<_>>(),
+                heatmap.values().collect::<Vec<_>>(),
+            );
+            assert!(heatmap.values().all(|&heat| heat > 0));
+        }
+    }
+
+    #[test]
+    fn heatmap_radial() {
+        let maze = Maze::new(maze::Shape::Quad, 9, 7).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let center = matrix::Pos { col: 4, row: 3 };
+        let heatmap = HeatMapType::Radial { center }.generate(&maze).unwrap();
+
+        // Every walk passes through the centre
+        let edge_rooms = 2 * (9 + 7) - 4;
+        assert_eq!(edge_rooms, heatmap[center]);
+        assert_eq!(Some(&heatmap[center]), heatmap.values().max());
+    }
 }
@@ -0,0 +1,148 @@
+use svg::Node;
+
+use maze::matrix;
+use maze_tools::image::Color;
+
+use crate::types::*;
+
+/// Colours the rooms of a maze by region.
+///
+/// Every room is assigned a region ID, for example a Voronoi segment or a
+/// connected component, and is filled with the colour from the palette at that
+/// index, wrapping around when there are more regions than colours.
+#[derive(Clone)]
+pub struct RegionRenderer {
+    /// The region ID of every room.
+    pub regions: matrix::Matrix<usize>,
+
+    /// The colours of the regions.
+    pub palette: Vec<Color>,
+
+    /// The region ID of rooms to leave uncoloured.
+    pub none: usize,
+}
+
+impl RegionRenderer {
+    /// Creates a region renderer where rooms with the region ID `usize::MAX`
+    /// are left uncoloured.
+    ///
+    /// # Arguments
+    /// *  `regions` - The region ID of every room.
+    /// *  `palette` - The colours of the regions.
+    pub fn new(regions: matrix::Matrix<usize>, palette: Vec<Color>) -> Self {
+        Self {
+            regions,
+            palette,
+            none: usize::MAX,
+        }
+    }
+
+    /// The colour of a room.
+    ///
+    /// If the room has no region, is outside of the region matrix or the
+    /// palette is empty, `None` is returned.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn color(&self, pos: matrix::Pos) -> Option<Color> {
+        self.regions
+            .get(pos)
+            .filter(|&&id| id != self.none && !self.palette.is_empty())
+            .map(|&id| self.palette[id % self.palette.len()])
+    }
+}
+
+impl<T> Renderer<T> for RegionRenderer
+where
+    T: Clone,
+{
+    /// Renders the regions.
+    ///
+    /// Only visited rooms are drawn.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the rooms.
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
+        let mut rooms = svg::node::element::Group::new();
+        for pos in maze.visited_positions() {
+            if let Some(color) = self.color(pos) {
+                rooms.append(draw_room(maze, pos, color, 1.0));
+            }
+        }
+
+        group.append(rooms);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Renders a maze and returns the fill colour of every drawn room.
+    fn fills(maze: &Maze, renderer: &RegionRenderer) -> Vec<String> {
+        let mut group = svg::node::element::Group::new();
+        renderer.render(maze, &mut group);
+        svg::read(&group.to_string())
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag("path", _, attributes) => {
+                    attributes.get("fill").map(ToString::to_string)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn regions_coloured() {
+        let maze = maze::Shape::Quad.create_visited(4, 2);
+        let regions =
+            matrix::Matrix::new_with_data(4, 2, |pos| (pos.col / 2) as usize);
+        let palette = vec![
+            "red".parse().unwrap(),
+            "green".parse().unwrap(),
+            "blue".parse().unwrap(),
+        ];
+        let renderer = RegionRenderer::new(regions.clone(), palette);
+
+        let css = |pos| renderer.color(pos).map(|color| color.to_css());
+        for pos in maze.positions() {
+            for other in maze.adjacent(pos).filter(|&p| maze.is_inside(p)) {
+                assert_eq!(
+                    regions[pos] == regions[other],
+                    css(pos) == css(other)
+                );
+            }
+        }
+        assert_eq!(maze.positions().count(), fills(&maze, &renderer).len());
+    }
+
+    #[test]
+    fn regions_none_skipped() {
+        let maze = maze::Shape::Quad.create_visited(4, 2);
+        let regions = matrix::Matrix::new_with_data(4, 2, |pos| {
+            if pos.col == 0 {
+                usize::MAX
+            } else {
+                0
+            }
+        });
+        let color: Color = "red".parse().unwrap();
+        let renderer = RegionRenderer::new(regions, vec![color]);
+
+        assert_eq!(vec![color.to_css(); 6], fills(&maze, &renderer));
+        assert!(fills(
+            &maze,
+            &RegionRenderer {
+                palette: vec![],
+                ..renderer
+            }
+        )
+        .is_empty());
+    }
+}
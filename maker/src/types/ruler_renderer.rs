@@ -0,0 +1,207 @@
+use std::str::FromStr;
+
+use svg::Node;
+
+use maze::matrix;
+use maze_tools::image::Color;
+
+use crate::types::*;
+
+/// The length of a tick mark, in maze units.
+const TICK_LENGTH: f32 = 0.3;
+
+/// The font size of the indices, in maze units.
+const FONT_SIZE: f32 = 0.3;
+
+/// A debugging overlay showing room indices along the edges of the maze.
+///
+/// A tick mark labelled with the column index is drawn above the view box of
+/// the maze for every column, aligned with the centre of its first room, and
+/// likewise for every row to the left of the view box. Since the ruler is
+/// drawn outside of the view box, a margin is required for it to be visible.
+#[derive(Clone)]
+pub struct RulerRenderer {
+    /// The colour of the ticks and indices.
+    pub color: Color,
+}
+
+impl FromStr for RulerRenderer {
+    type Err = String;
+
+    /// Converts a string to a ruler description.
+    ///
+    /// The string must be a colour.
+    fn from_str(s: &str) -> Result<Self, String> {
+        Ok(Self {
+            color: s.trim().parse()?,
+        })
+    }
+}
+
+impl<T> Renderer<T> for RulerRenderer
+where
+    T: Clone,
+{
+    /// Renders the ruler.
+    ///
+    /// The column ticks are added as one group, followed by the row ticks.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the ruler.
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
+        let viewbox = maze.viewbox();
+        let (left, top) = (viewbox.corner.x, viewbox.corner.y);
+        let axis = |anchor: &str, baseline: &str| {
+            svg::node::element::Group::new()
+                .set("fill", self.color.to_css())
+                .set("font-size", FONT_SIZE)
+                .set("text-anchor", anchor)
+                .set("dominant-baseline", baseline)
+        };
+        let label = |x: f32, y: f32, index: usize| {
+            svg::node::element::Text::new()
+                .set("x", x)
+                .set("y", y)
+                .add(svg::node::Text::new(index.to_string()))
+        };
+
+        let mut data = svg::node::element::path::Data::new();
+        let mut columns = axis("middle", "auto");
+        for col in 0..maze.width() {
+            let x = maze
+                .center(matrix::Pos {
+                    col: col as isize,
+                    row: 0,
+                })
+                .x;
+            data = data.move_to((x, top - TICK_LENGTH)).line_to((x, top));
+            columns.append(label(x, top - 1.5 * TICK_LENGTH, col));
+        }
+
+        let mut rows = axis("end", "central");
+        for row in 0..maze.height() {
+            let y = maze
+                .center(matrix::Pos {
+                    col: 0,
+                    row: row as isize,
+                })
+                .y;
+            data = data.move_to((left - TICK_LENGTH, y)).line_to((left, y));
+            rows.append(label(left - 1.5 * TICK_LENGTH, y, row));
+        }
+
+        group.append(
+            svg::node::element::Path::new()
+                .set("fill", "none")
+                .set("stroke", self.color.to_css())
+                .set("stroke-width", 0.05)
+                .set("d", data),
+        );
+        group.append(columns);
+        group.append(rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_tick_per_column_and_row() {
+        for shape in [maze::Shape::Hex, maze::Shape::Quad, maze::Shape::Tri] {
+            let maze = Maze::new(shape, 5, 3);
+            let mut group = svg::node::element::Group::new();
+            "red"
+                .parse::<RulerRenderer>()
+                .unwrap()
+                .render(&maze, &mut group);
+
+            // Collect the indices of each axis group by its text anchor
+            let output = group.to_string();
+            let mut anchor = String::new();
+            let (mut columns, mut rows) = (vec![], vec![]);
+            let mut in_text = false;
+            for event in svg::read(&output).unwrap() {
+                match event {
+                    svg::parser::Event::Tag(
+                        "g",
+                        svg::node::element::tag::Type::Start,
+                        attributes,
+                    ) => {
+                        anchor = attributes
+                            .get("text-anchor")
+                            .map(ToString::to_string)
+                            .unwrap_or_default()
+                    }
+                    svg::parser::Event::Tag("text", tag_type, _) => {
+                        in_text =
+                            tag_type == svg::node::element::tag::Type::Start
+                    }
+                    svg::parser::Event::Text(text) if in_text => {
+                        let index = text.trim().parse::<usize>().unwrap();
+                        if anchor == "middle" {
+                            columns.push(index);
+                        } else {
+                            rows.push(index);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            assert_eq!((0..5).collect::<Vec<_>>(), columns, "for {:?}", shape);
+            assert_eq!((0..3).collect::<Vec<_>>(), rows, "for {:?}", shape);
+        }
+    }
+
+    #[test]
+    fn ticks_aligned() {
+        let maze = Maze::new(maze::Shape::Quad, 4, 2);
+        let mut group = svg::node::element::Group::new();
+        "red"
+            .parse::<RulerRenderer>()
+            .unwrap()
+            .render(&maze, &mut group);
+
+        let output = group.to_string();
+        let data = svg::read(&output)
+            .unwrap()
+            .find_map(|event| match event {
+                svg::parser::Event::Tag("path", _, attributes) => Some(
+                    svg::node::element::path::Data::parse(&attributes["d"])
+                        .unwrap(),
+                ),
+                _ => None,
+            })
+            .unwrap();
+        let starts = data
+            .iter()
+            .filter_map(|command| match command {
+                svg::node::element::path::Command::Move(_, parameters) => {
+                    Some((parameters[0], parameters[1]))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(4 + 2, starts.len());
+        for col in 0..4 {
+            let center = maze.center(matrix::Pos { col, row: 0 });
+            assert_eq!(center.x, starts[col as usize].0);
+        }
+        for row in 0..2 {
+            let center = maze.center(matrix::Pos { col: 0, row });
+            assert_eq!(center.y, starts[4 + row as usize].1);
+        }
+    }
+
+    #[test]
+    fn invalid_color() {
+        assert!("not a colour".parse::<RulerRenderer>().is_err());
+    }
+}
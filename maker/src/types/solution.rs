@@ -0,0 +1,131 @@
+use std::str::FromStr;
+
+use maze::matrix;
+
+use crate::types::*;
+
+/// The endpoints of a maze solution.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Solution {
+    /// The starting room.
+    pub from: matrix::Pos,
+
+    /// The goal room.
+    pub to: matrix::Pos,
+}
+
+impl FromStr for Solution {
+    type Err = String;
+
+    /// Converts a string to solution endpoints.
+    ///
+    /// The string must be on the form `from_col:from_row:to_col:to_row`.
+    fn from_str(s: &str) -> Result<Self, String> {
+        match parse_positions(s).as_deref() {
+            Some(&[from, to]) => Ok(Self { from, to }),
+            _ => Err(format!("invalid endpoints: {}", s)),
+        }
+    }
+}
+
+impl Solution {
+    /// The solution from the top left room to the bottom right room.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    pub fn corners<T>(maze: &maze::Maze<T>) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            from: matrix::Pos { col: 0, row: 0 },
+            to: matrix::Pos {
+                col: maze.width() as isize - 1,
+                row: maze.height() as isize - 1,
+            },
+        }
+    }
+
+    /// Generates a JSON description of the shortest path between the
+    /// endpoints.
+    ///
+    /// The result is an array with one object per room on the path, in order
+    /// from `from` to `to`, on the form
+    /// `{"col": 0, "row": 0, "center": {"x": 0.7, "y": 0.7}}`, where `center`
+    /// is the physical centre of the room.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    ///
+    /// # Errors
+    /// An error is returned if an endpoint is outside of the maze, or if the
+    /// endpoints are not connected.
+    pub fn to_json<T>(&self, maze: &maze::Maze<T>) -> Result<String, String>
+    where
+        T: Clone,
+    {
+        if let Some(pos) = [self.from, self.to]
+            .into_iter()
+            .find(|&pos| !maze.is_inside(pos))
+        {
+            return Err(format!("endpoint outside of maze: {:?}", pos));
+        }
+
+        let path = maze
+            .walk(self.from, self.to)
+            .ok_or_else(|| "the endpoints are not connected".to_string())?;
+        let rooms = path
+            .into_iter()
+            .map(|pos| {
+                let center = maze.center(pos);
+                serde_json::json!({
+                    "col": pos.col,
+                    "row": pos.row,
+                    "center": {
+                        "x": center.x,
+                        "y": center.y,
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::to_string(&rooms).map_err(|e| e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze::initialize;
+
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        assert_eq!(
+            Ok(Solution {
+                from: matrix::Pos { col: 1, row: 2 },
+                to: matrix::Pos { col: 3, row: 4 },
+            }),
+            "1:2:3:4".parse::<Solution>(),
+        );
+        assert!("1:2:3".parse::<Solution>().is_err());
+        assert!("1:2:3:x".parse::<Solution>().is_err());
+    }
+
+    #[test]
+    fn to_json_errors() {
+        let maze = Maze::new(maze::Shape::Quad, 5, 5);
+        assert!(Solution::corners(&maze).to_json(&maze).is_err());
+
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        assert!(Solution::corners(&maze).to_json(&maze).is_ok());
+        assert!("0:0:5:0"
+            .parse::<Solution>()
+            .unwrap()
+            .to_json(&maze)
+            .is_err());
+    }
+}
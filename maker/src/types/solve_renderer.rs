@@ -2,6 +2,7 @@ use maze::render::svg::ToPath;
 
 use svg::Node;
 
+use crate::render::SOLUTION_CLASS;
 use crate::types::*;
 
 /// The maze solution.
@@ -9,46 +10,203 @@ use crate::types::*;
 pub struct SolveRenderer {
     /// The colour of the solution marker.
     color: String,
+
+    /// Whether to draw the solution as a smooth curve.
+    smooth: bool,
+
+    /// Whether to draw the walls of the maze.
+    ///
+    /// Disabling this yields a document containing only the solution, which
+    /// may be overlaid on a rendering of the maze.
+    walls: bool,
 }
 
 impl FromStr for SolveRenderer {
     type Err = String;
 
-    /// Converts a string to a string to render.
+    /// Converts a string to a solution renderer.
     ///
-    /// The string must be a path.
+    /// The string must be on the form `"colour"`, optionally followed by any
+    /// of the styles `",smooth"`, to draw the solution as a smooth curve, and
+    /// `",nowalls"`, to omit the walls of the maze.
     fn from_str(s: &str) -> Result<Self, String> {
-        Ok(Self { color: s.into() })
+        let mut parts = s.split(',').map(str::trim);
+        let mut result = Self {
+            color: parts.next().unwrap().into(),
+            smooth: false,
+            walls: true,
+        };
+
+        for part in parts {
+            match part {
+                "smooth" => result.smooth = true,
+                "nowalls" => result.walls = false,
+                part => {
+                    return Err(format!("invalid solution style: {}", part))
+                }
+            }
+        }
+
+        Ok(result)
     }
 }
 
-impl Renderer for SolveRenderer {
+impl<T> Renderer<T> for SolveRenderer
+where
+    T: Clone,
+{
     /// Renders the maze solution.
     ///
     /// # Arguments
     /// *  `maze` - The maze.
     /// *  `group` - The group to which to add the solution.
-    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
-        group.append(
-            svg::node::element::Path::new()
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
+        self.render_styled(maze, group, StyleMode::Inline);
+    }
+
+    /// Renders the maze solution using a specific style mode.
+    ///
+    /// With [`StyleMode::Classes`], the solution is given the class
+    /// [`SOLUTION_CLASS`] and its colour is ignored.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `group` - The group to which to add the solution.
+    /// *  `style` - How to style the solution.
+    fn render_styled(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+        style: StyleMode,
+    ) {
+        let path = maze
+            .walk(
+                maze::matrix::Pos { col: 0, row: 0 },
+                maze::matrix::Pos {
+                    col: maze.width() as isize - 1,
+                    row: maze.height() as isize - 1,
+                },
+            )
+            .unwrap();
+        let data = if self.smooth {
+            path.to_smooth_path_d()
+        } else {
+            path.to_path_d()
+        };
+
+        group.append(match style {
+            StyleMode::Inline => svg::node::element::Path::new()
                 .set("fill", "none")
                 .set("stroke", self.color.as_str())
                 .set("stroke-linecap", "round")
                 .set("stroke-linejoin", "round")
                 .set("stroke-width", 0.4)
                 .set("vector-effect", "non-scaling-stroke")
-                .set(
-                    "d",
-                    maze.walk(
-                        maze::matrix::Pos { col: 0, row: 0 },
-                        maze::matrix::Pos {
-                            col: maze.width() as isize - 1,
-                            row: maze.height() as isize - 1,
-                        },
-                    )
-                    .unwrap()
-                    .to_path_d(),
+                .set("d", data),
+            StyleMode::Classes => svg::node::element::Path::new()
+                .set("class", SOLUTION_CLASS)
+                .set("d", data),
+        });
+    }
+
+    fn walls(&self) -> bool {
+        self.walls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze::initialize;
+    use svg::node::element::path::Command;
+
+    use super::*;
+
+    /// Renders the solution of a maze and returns the path commands.
+    ///
+    /// # Arguments
+    /// *  `renderer` - The renderer description.
+    fn commands(renderer: &str) -> Vec<Command> {
+        let maze = Maze::new(maze::Shape::Quad, 6, 6).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let mut group = svg::node::element::Group::new();
+        renderer
+            .parse::<SolveRenderer>()
+            .unwrap()
+            .render(&maze, &mut group);
+
+        let output = group.to_string();
+        svg::read(&output)
+            .unwrap()
+            .find_map(|event| match event {
+                svg::parser::Event::Tag("path", _, attributes) => Some(
+                    svg::node::element::path::Data::parse(&attributes["d"])
+                        .unwrap()
+                        .to_vec(),
                 ),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn smooth_uses_curves() {
+        let commands = commands("black,smooth");
+        assert!(matches!(commands[0], Command::Move(..)));
+        assert!(commands[1..]
+            .iter()
+            .all(|command| matches!(command, Command::CubicCurve(..))));
+    }
+
+    #[test]
+    fn straight_uses_lines() {
+        let commands = commands("black");
+        assert!(matches!(commands[0], Command::Move(..)));
+        assert!(commands[1..]
+            .iter()
+            .all(|command| matches!(command, Command::Line(..))));
+    }
+
+    #[test]
+    fn invalid_style() {
+        assert!("black,wobbly".parse::<SolveRenderer>().is_err());
+        assert!("black,smooth,wobbly".parse::<SolveRenderer>().is_err());
+    }
+
+    #[test]
+    fn without_walls() {
+        let maze = Maze::new(maze::Shape::Hex, 6, 6).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let renderer = "blue,smooth,nowalls".parse::<SolveRenderer>().unwrap();
+        let mut buffer = Vec::new();
+        crate::render::write_svg(&maze, &[&renderer], &mut buffer).unwrap();
+        let output = String::from_utf8(buffer).unwrap();
+
+        let (x, y, width, height) = maze.viewbox().tuple();
+        let tags = svg::read(&output)
+            .unwrap()
+            .filter_map(|event| match event {
+                svg::parser::Event::Tag(name, _, attributes) => {
+                    Some((name, attributes))
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(3, tags.len());
+        assert_eq!("svg", tags[0].0);
+        assert_eq!(
+            format!("{} {} {} {}", x, y, width, height),
+            tags[0].1["viewBox"].to_string(),
         );
+        assert_eq!("path", tags[1].0);
+        assert_eq!("blue", tags[1].1["stroke"].to_string());
+        assert_eq!("svg", tags[2].0);
     }
 }
@@ -28,7 +28,10 @@ impl FromStr for TextRenderer {
     }
 }
 
-impl Renderer for TextRenderer {
+impl<T> Renderer<T> for TextRenderer
+where
+    T: Clone,
+{
     /// Applies the text action.
     ///
     /// This action will render a string as background.
@@ -36,7 +39,11 @@ impl Renderer for TextRenderer {
     /// # Arguments
     /// *  `maze` - The maze.
     /// *  `group` - The group to which to add the rooms.
-    fn render(&self, maze: &Maze, group: &mut svg::node::element::Group) {
+    fn render(
+        &self,
+        maze: &maze::Maze<T>,
+        group: &mut svg::node::element::Group,
+    ) {
         let physical::ViewBox { width, height, .. } = maze.viewbox();
         let columns = (self.text.len() as f32).sqrt().ceil() as usize;
         let rows = (self.text.len() as f32 / columns as f32).ceil() as usize;
@@ -53,7 +60,7 @@ impl Renderer for TextRenderer {
             })
             .split_by(&maze.shape(), maze.width(), maze.height());
 
-        group.append(draw_rooms(maze, |pos| data[pos]));
+        group.append(draw_rooms(maze, |pos| data[pos], 1.0));
     }
 }
 
@@ -0,0 +1,40 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Runs the maze maker and returns the generated SVG.
+///
+/// # Arguments
+/// *  `name` - A name unique to the invocation, used for the output file.
+/// *  `seed` - The seed to pass.
+fn generate(name: &str, seed: u64) -> String {
+    let output: PathBuf = env::temp_dir().join(format!(
+        "maze-maker-{}-{}.svg",
+        std::process::id(),
+        name
+    ));
+    let status = Command::new(env!("CARGO_BIN_EXE_maze-maker"))
+        .args(["--walls", "6", "--width", "20", "--height", "10"])
+        .args(["--method", "branching,winding,braid"])
+        .args(["--break", "full,2", "--heat-map", "full"])
+        .args(["--seed", &seed.to_string()])
+        .arg(&output)
+        .status()
+        .expect("failed to run maze maker");
+    assert!(status.success());
+
+    let result = fs::read_to_string(&output).expect("failed to read SVG");
+    fs::remove_file(&output).ok();
+    result
+}
+
+#[test]
+fn same_seed_same_output() {
+    assert_eq!(generate("first", 12345), generate("second", 12345));
+}
+
+#[test]
+fn different_seed_different_output() {
+    assert_ne!(generate("third", 12345), generate("fourth", 54321));
+}
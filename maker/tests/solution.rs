@@ -0,0 +1,76 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Runs the maze maker and returns the solution written as JSON.
+///
+/// # Arguments
+/// *  `name` - A name unique to the invocation, used for the output files.
+/// *  `args` - Additional arguments to pass.
+fn solve(name: &str, args: &[&str]) -> serde_json::Value {
+    let output = |extension: &str| -> PathBuf {
+        env::temp_dir().join(format!(
+            "maze-maker-{}-{}.{}",
+            std::process::id(),
+            name,
+            extension,
+        ))
+    };
+    let (svg, json) = (output("svg"), output("json"));
+    let status = Command::new(env!("CARGO_BIN_EXE_maze-maker"))
+        .args(["--walls", "4", "--width", "12", "--height", "8"])
+        .args(["--method", "braid", "--seed", "12345"])
+        .arg("--solution-json")
+        .arg(&json)
+        .args(args)
+        .arg(&svg)
+        .status()
+        .expect("failed to run maze maker");
+    assert!(status.success());
+
+    let result = fs::read_to_string(&json).expect("failed to read solution");
+    fs::remove_file(&svg).ok();
+    fs::remove_file(&json).ok();
+    serde_json::from_str(&result).expect("failed to parse solution")
+}
+
+/// Extracts the room positions of a solution.
+///
+/// # Arguments
+/// *  `solution` - The parsed solution.
+fn rooms(solution: &serde_json::Value) -> Vec<(i64, i64)> {
+    solution
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|room| {
+            assert!(room["center"]["x"].is_number());
+            assert!(room["center"]["y"].is_number());
+            (room["col"].as_i64().unwrap(), room["row"].as_i64().unwrap())
+        })
+        .collect()
+}
+
+#[test]
+fn solution_contiguous() {
+    let rooms = rooms(&solve("corners", &[]));
+
+    assert_eq!(Some(&(0, 0)), rooms.first());
+    assert_eq!(Some(&(11, 7)), rooms.last());
+    assert!(rooms
+        .windows(2)
+        .all(|w| (w[0].0 - w[1].0).abs() + (w[0].1 - w[1].1).abs() == 1));
+}
+
+#[test]
+fn solution_endpoints() {
+    let rooms =
+        rooms(&solve("endpoints", &["--solution-endpoints", "3:1:5:6"]));
+
+    assert_eq!(Some(&(3, 1)), rooms.first());
+    assert_eq!(Some(&(5, 6)), rooms.last());
+    assert!(rooms
+        .windows(2)
+        .all(|w| (w[0].0 - w[1].0).abs() + (w[0].1 - w[1].1).abs() == 1));
+}
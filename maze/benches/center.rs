@@ -0,0 +1,38 @@
+use criterion::{
+    black_box, criterion_group, criterion_main, BenchmarkId, Criterion,
+};
+use maze::{Maze, Shape};
+
+pub fn center(c: &mut Criterion) {
+    let mut group = c.benchmark_group("center");
+    for shape in [Shape::Tri, Shape::Quad, Shape::Hex].iter() {
+        let maze = Maze::<()>::new(black_box(*shape), 100, 100);
+        group.bench_with_input(
+            BenchmarkId::new("direct", shape),
+            shape,
+            |b, _| {
+                b.iter(|| {
+                    for pos in maze.positions() {
+                        black_box(maze.center(pos));
+                    }
+                });
+            },
+        );
+        let cache = maze.center_cache();
+        group.bench_with_input(
+            BenchmarkId::new("cached", shape),
+            shape,
+            |b, _| {
+                b.iter(|| {
+                    for pos in maze.positions() {
+                        black_box(cache[pos]);
+                    }
+                });
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, center);
+criterion_main!(benches);
@@ -0,0 +1,636 @@
+//! # Analysis of initialised mazes
+//!
+//! This module contains functions measuring properties of mazes, such as how
+//! hard they are to solve.
+
+use std::collections::{BTreeMap, HashSet, VecDeque};
+
+use crate::matrix;
+use crate::physical;
+use crate::Maze;
+
+/// Weights for the components of a difficulty score.
+///
+/// See [`difficulty_weighted`] for a description of the components.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Weights {
+    /// The weight of the detour component.
+    pub detour: f32,
+
+    /// The weight of the false branch component.
+    pub branches: f32,
+
+    /// The weight of the branch depth component.
+    pub depth: f32,
+}
+
+impl Default for Weights {
+    /// All components are weighted equally by default.
+    fn default() -> Self {
+        Self {
+            detour: 1.0,
+            branches: 1.0,
+            depth: 1.0,
+        }
+    }
+}
+
+/// Calculates a difficulty score in the range _[0, 1]_ for walking between two
+/// rooms.
+///
+/// This is equivalent to calling [`difficulty_weighted`] with the default
+/// weights.
+///
+/// # Arguments
+/// *  `maze` - The maze to analyse.
+/// *  `from` - The starting position.
+/// *  `to` - The desired goal.
+pub fn difficulty<T>(maze: &Maze<T>, from: matrix::Pos, to: matrix::Pos) -> f32
+where
+    T: Clone,
+{
+    difficulty_weighted(maze, from, to, Weights::default())
+}
+
+/// Calculates a weighted difficulty score in the range _[0, 1]_ for walking
+/// between two rooms.
+///
+/// The score is the weighted average of three components, each in the range
+/// _[0, 1]_:
+/// 1. _detour_: `1 - d / l`, where `d` is the physical distance between the
+///    centres of `from` and `to`, and `l` the physical length of the
+///    solution. A straight corridor yields `0`.
+/// 2. _branches_: the fraction of rooms on the solution from which a false
+///    branch, an opening not leading along the solution, leaves.
+/// 3. _depth_: `a / (a + n)`, where `a` is the average depth, in rooms, of
+///    the false branches and `n` the number of rooms on the solution.
+///
+/// If the rooms are not connected, or if all weights are zero, `0.0` is
+/// returned.
+///
+/// # Arguments
+/// *  `maze` - The maze to analyse.
+/// *  `from` - The starting position.
+/// *  `to` - The desired goal.
+/// *  `weights` - The weights of the components.
+pub fn difficulty_weighted<T>(
+    maze: &Maze<T>,
+    from: matrix::Pos,
+    to: matrix::Pos,
+    weights: Weights,
+) -> f32
+where
+    T: Clone,
+{
+    let total = weights.detour + weights.branches + weights.depth;
+    let path = match maze.walk(from, to) {
+        Some(path) if total > 0.0 => path.into_iter().collect::<Vec<_>>(),
+        _ => return 0.0,
+    };
+
+    // Rooms on the solution are considered visited when exploring branches
+    let mut visited = matrix::Matrix::<bool>::new(maze.width(), maze.height());
+    for &pos in path.iter() {
+        visited[pos] = true;
+    }
+
+    let length = path
+        .windows(2)
+        .map(|w| maze.center(w[0]).distance(maze.center(w[1])))
+        .sum::<f32>();
+    let direct = maze.center(from).distance(maze.center(to));
+    let detour = if length > 0.0 {
+        1.0 - (direct / length).min(1.0)
+    } else {
+        0.0
+    };
+
+    let mut forks = 0;
+    let mut depths = Vec::new();
+    for &pos in path.iter() {
+        let before = depths.len();
+        let branches = maze
+            .neighbors(pos)
+            .filter(|&next| !*visited.get(next).unwrap_or(&true))
+            .collect::<Vec<_>>();
+        for next in branches {
+            if !visited[next] {
+                depths.push(explore(maze, &mut visited, next));
+            }
+        }
+        if depths.len() > before {
+            forks += 1;
+        }
+    }
+
+    let branches = forks as f32 / path.len() as f32;
+    let depth = if depths.is_empty() {
+        0.0
+    } else {
+        let average = depths.iter().sum::<usize>() as f32 / depths.len() as f32;
+        average / (average + path.len() as f32)
+    };
+
+    (weights.detour * detour
+        + weights.branches * branches
+        + weights.depth * depth)
+        / total
+}
+
+/// Determines whether there is exactly one simple path between two rooms.
+///
+/// A simple path never visits a room twice. The path is unique if every
+/// passage along the shortest path is a bridge, that is, a passage whose
+/// removal would disconnect its rooms. Loops elsewhere in the maze do not
+/// affect the result.
+///
+/// # Arguments
+/// *  `maze` - The maze to analyse.
+/// *  `from` - The starting position.
+/// *  `to` - The desired goal.
+pub fn has_unique_solution<T>(
+    maze: &Maze<T>,
+    from: matrix::Pos,
+    to: matrix::Pos,
+) -> bool
+where
+    T: Clone,
+{
+    let path = match maze.walk(from, to) {
+        Some(path) => path.into_iter().collect::<Vec<_>>(),
+        None => return false,
+    };
+
+    let bridges = bridges(maze, from);
+    path.windows(2).all(|w| bridges.contains(&edge(w[0], w[1])))
+}
+
+/// Finds all bridges in the part of a maze reachable from a room.
+///
+/// This is an iterative implementation of Tarjan's bridge finding algorithm.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `start` - The room from which to search.
+fn bridges<T>(
+    maze: &Maze<T>,
+    start: matrix::Pos,
+) -> HashSet<(matrix::Pos, matrix::Pos)>
+where
+    T: Clone,
+{
+    let mut result = HashSet::new();
+    let mut discovered =
+        matrix::Matrix::<usize>::new(maze.width(), maze.height());
+    let mut low = matrix::Matrix::<usize>::new(maze.width(), maze.height());
+    let mut time = 1;
+
+    let neighbors = |pos| {
+        maze.neighbors(pos)
+            .filter(|&next| maze.is_inside(next))
+            .collect::<Vec<_>>()
+    };
+
+    discovered[start] = time;
+    low[start] = time;
+    let mut stack = vec![(start, None, neighbors(start), 0)];
+    while let Some((pos, parent, next, index)) = stack.last_mut() {
+        let (pos, parent) = (*pos, *parent);
+        if let Some(&next) = next.get(*index) {
+            *index += 1;
+            if Some(next) == parent {
+                continue;
+            } else if discovered[next] == 0 {
+                time += 1;
+                discovered[next] = time;
+                low[next] = time;
+                stack.push((next, Some(pos), neighbors(next), 0));
+            } else {
+                low[pos] = low[pos].min(discovered[next]);
+            }
+        } else {
+            stack.pop();
+            if let Some(parent) = parent {
+                low[parent] = low[parent].min(low[pos]);
+                if low[pos] > discovered[parent] {
+                    result.insert(edge(parent, pos));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// An undirected edge between two rooms.
+///
+/// # Arguments
+/// *  `a` - One room.
+/// *  `b` - Another room.
+fn edge(a: matrix::Pos, b: matrix::Pos) -> (matrix::Pos, matrix::Pos) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+/// Determines whether the visited rooms of a maze form a perfect maze.
+///
+/// A perfect maze has exactly one path between any two rooms; its passages
+/// form a spanning tree. This holds if the visited rooms are connected and
+/// there is exactly one passage less than there are visited rooms.
+///
+/// Only visited rooms are considered, so this works for masked mazes. Open
+/// walls leading to rooms that are not visited or outside of the maze are
+/// ignored. A maze without visited rooms is not perfect.
+///
+/// # Arguments
+/// *  `maze` - The maze to analyse.
+pub fn is_perfect<T>(maze: &Maze<T>) -> bool
+where
+    T: Clone,
+{
+    let carved = |pos| maze.is_inside(pos) && maze[pos].visited;
+
+    let rooms = maze.visited_positions().count();
+    let passages = maze
+        .visited_positions()
+        .flat_map(|pos| maze.neighbors(pos))
+        .filter(|&next| carved(next))
+        .count()
+        / 2;
+    if rooms == 0 || passages != rooms - 1 {
+        return false;
+    }
+
+    // With the passage count correct, the maze is a tree if it is connected
+    let start = maze.visited_positions().next().unwrap();
+    let mut seen = matrix::Matrix::<bool>::new(maze.width(), maze.height());
+    let mut queue = VecDeque::from([start]);
+    let mut count = 1;
+    seen[start] = true;
+    while let Some(pos) = queue.pop_front() {
+        for next in maze.neighbors(pos) {
+            if carved(next) && !seen[next] {
+                seen[next] = true;
+                count += 1;
+                queue.push_back(next);
+            }
+        }
+    }
+
+    count == rooms
+}
+
+/// Traces the outline of the visited rooms of a maze.
+///
+/// The outline consists of all walls separating a visited room from a room
+/// that is either not visited or outside of the maze. For a masked maze this
+/// is the outline of the mask.
+///
+/// Every loop is a list of wall corners, one for each wall; the last corner
+/// connects to the first. A region with holes yields one loop for its outer
+/// boundary and one for every hole, and disjoint regions yield separate loops.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+pub fn outline<T>(maze: &Maze<T>) -> Vec<Vec<physical::Pos>>
+where
+    T: Clone,
+{
+    let member = |pos| maze.is_inside(pos) && maze[pos].visited;
+
+    // Map the start corner of every border wall to its end corner; corners
+    // are keyed by their rounded coordinates, since shared corners computed
+    // from different rooms may differ slightly
+    let key = |pos: physical::Pos| {
+        (
+            (pos.x * 1000.0).round() as i64,
+            (pos.y * 1000.0).round() as i64,
+        )
+    };
+    let mut edges = BTreeMap::new();
+    for pos in maze.visited_positions() {
+        for &wall in maze.walls(pos) {
+            if !member(maze.back((pos, wall)).0) {
+                let (start, end) = maze.corners((pos, wall));
+                edges
+                    .entry(key(start))
+                    .or_insert_with(Vec::new)
+                    .push((start, end));
+            }
+        }
+    }
+
+    let mut result = Vec::new();
+    while let Some(&first) = edges.keys().next() {
+        let mut outline = Vec::new();
+        let mut current = first;
+        while let Some((start, end)) =
+            edges.get_mut(&current).and_then(|ends| ends.pop())
+        {
+            if edges.get(&current).map(Vec::is_empty).unwrap_or(false) {
+                edges.remove(&current);
+            }
+            outline.push(start);
+            current = key(end);
+        }
+        result.push(outline);
+    }
+
+    result
+}
+
+/// Explores a branch breadth first and returns its depth.
+///
+/// All rooms reached are marked as visited, and already visited rooms are not
+/// entered.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `visited` - The visited rooms.
+/// *  `start` - The first room of the branch.
+fn explore<T>(
+    maze: &Maze<T>,
+    visited: &mut matrix::Matrix<bool>,
+    start: matrix::Pos,
+) -> usize
+where
+    T: Clone,
+{
+    let mut result = 0;
+    let mut queue = VecDeque::new();
+    visited[start] = true;
+    queue.push_back((start, 1));
+
+    while let Some((pos, depth)) = queue.pop_front() {
+        result = result.max(depth);
+        for next in maze.neighbors(pos) {
+            if let Some(v) = visited.get_mut(next) {
+                if !*v {
+                    *v = true;
+                    queue.push_back((next, depth + 1));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn difficulty_corridor(mut maze: TestMaze) {
+        let width = maze.width();
+        let mut navigator = Navigator::new(&mut maze).from(matrix_pos(0, 0));
+        for _ in 1..width {
+            navigator = navigator.right(true);
+        }
+        let log = navigator.stop();
+
+        let score = difficulty(&maze, log[0], *log.last().unwrap());
+        assert!(score < 0.1, "{} was not near 0", score);
+    }
+
+    #[maze_test]
+    fn difficulty_winding(mut maze: TestMaze) {
+        let width = maze.width();
+        let from = matrix_pos(0, 0);
+        let to = matrix_pos(width as isize - 1, maze.height() as isize - 1);
+        let winding = maze.clone().initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        let mut navigator = Navigator::new(&mut maze).from(from);
+        for _ in 1..width {
+            navigator = navigator.right(true);
+        }
+        let log = navigator.stop();
+        let corridor = difficulty(&maze, log[0], *log.last().unwrap());
+
+        let score = difficulty(&winding, from, to);
+        assert!(score > corridor, "{} <= {}", score, corridor);
+        assert!((0.0..=1.0).contains(&score));
+    }
+
+    #[maze_test]
+    fn unique_solution_perfect(maze: TestMaze) {
+        let (from, to) = corners(&maze);
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        assert!(has_unique_solution(&maze, from, to));
+        assert!(has_unique_solution(&maze, from, from));
+    }
+
+    #[maze_test]
+    fn unique_solution_loops(maze: TestMaze) {
+        let (from, to) = corners(&maze);
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        let mut flipped = 0;
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                let (back, _) = maze.back((pos, wall));
+                if maze.is_open((pos, wall)) || !maze.is_inside(back) {
+                    continue;
+                }
+                let mut looped = maze.clone();
+                looped.open((pos, wall));
+
+                let unique = count_paths(&looped, from, to) == 1;
+                assert_eq!(unique, has_unique_solution(&looped, from, to));
+                if !unique {
+                    flipped += 1;
+                }
+            }
+        }
+        assert!(flipped > 0);
+    }
+
+    #[maze_test]
+    fn unique_solution_braided(maze: TestMaze) {
+        let (from, to) = corners(&maze);
+        let maze = maze.initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+        assert!(!has_unique_solution(&maze, from, to));
+    }
+
+    #[maze_test]
+    fn unique_solution_disconnected(maze: TestMaze) {
+        let (from, to) = corners(&maze);
+        assert!(!has_unique_solution(&maze, from, to));
+    }
+
+    #[maze_test]
+    fn is_perfect_winding(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        assert!(is_perfect(&maze));
+    }
+
+    #[maze_test]
+    fn is_perfect_braided(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+        assert!(!is_perfect(&maze));
+    }
+
+    #[maze_test]
+    fn is_perfect_disconnected(maze: TestMaze) {
+        let width = maze.width();
+        let maze = maze.initialize_filter(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+            |pos| pos.col as usize != width / 2,
+        );
+        assert!(!is_perfect(&maze));
+        assert!(!is_perfect(&crate::Shape::Quad.create::<()>(5, 5)));
+    }
+
+    #[maze_test]
+    fn is_perfect_masked(maze: TestMaze) {
+        let template = maze.clone();
+        let col = maze.width() as isize / 2;
+        let center = template.center(matrix_pos(col, 2));
+        let radius = template.center(matrix_pos(col + 2, 2)).distance(center);
+        let filter = initialize::filters::disc(&template, center, radius);
+        let maze = maze.initialize_filter(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+            filter,
+        );
+        assert!(maze.positions().any(|pos| !maze[pos].visited));
+        assert!(is_perfect(&maze));
+    }
+
+    /// The top left and bottom right rooms of a maze.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    fn corners(maze: &TestMaze) -> (matrix::Pos, matrix::Pos) {
+        (
+            matrix_pos(0, 0),
+            matrix_pos(maze.width() as isize - 1, maze.height() as isize - 1),
+        )
+    }
+
+    /// Counts the simple paths between two rooms by exhaustive search.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze.
+    /// *  `from` - The starting position.
+    /// *  `to` - The desired goal.
+    fn count_paths(
+        maze: &TestMaze,
+        from: matrix::Pos,
+        to: matrix::Pos,
+    ) -> usize {
+        fn visit(
+            maze: &TestMaze,
+            visited: &mut matrix::Matrix<bool>,
+            pos: matrix::Pos,
+            to: matrix::Pos,
+        ) -> usize {
+            if pos == to {
+                return 1;
+            }
+            visited[pos] = true;
+            let mut count = 0;
+            for next in maze.neighbors(pos) {
+                if !*visited.get(next).unwrap_or(&true) {
+                    count += visit(maze, visited, next, to);
+                }
+            }
+            visited[pos] = false;
+            count
+        }
+
+        let mut visited = matrix::Matrix::new(maze.width(), maze.height());
+        visit(maze, &mut visited, from, to)
+    }
+
+    #[test]
+    fn outline_l_shape() {
+        // An L-shape has the same perimeter as its bounding box
+        let (width, height) = (4, 3);
+        let maze = TestMaze::new(crate::Shape::Quad, width, height)
+            .initialize_filter(
+                initialize::Method::Winding,
+                &mut initialize::LFSR::new(12345),
+                |pos| pos.col == 0 || pos.row as usize == height - 1,
+            );
+        let outline = outline(&maze);
+
+        assert_eq!(1, outline.len());
+        assert_eq!(2 * (width + height), outline[0].len());
+    }
+
+    #[test]
+    fn outline_hole() {
+        let maze = TestMaze::new(crate::Shape::Quad, 3, 3).initialize_filter(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+            |pos| pos != matrix_pos(1, 1),
+        );
+        let mut lengths =
+            outline(&maze).iter().map(Vec::len).collect::<Vec<_>>();
+        lengths.sort_unstable();
+
+        assert_eq!(vec![4, 12], lengths);
+    }
+
+    #[maze_test]
+    fn outline_closed(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let outline = outline(&maze);
+
+        assert_eq!(1, outline.len());
+        for (i, &pos) in outline[0].iter().enumerate() {
+            let next = outline[0][(i + 1) % outline[0].len()];
+            let walls = maze
+                .positions()
+                .flat_map(|pos| {
+                    maze.walls(pos).iter().map(move |&wall| (pos, wall))
+                })
+                .filter(|&wall_pos| !maze.is_inside(maze.back(wall_pos).0))
+                .map(|wall_pos| maze.corners(wall_pos))
+                .collect::<Vec<_>>();
+            assert!(walls.iter().any(
+                |&(start, end)| is_close(pos, start) && is_close(next, end)
+            ));
+        }
+    }
+
+    #[maze_test]
+    fn outline_empty(maze: TestMaze) {
+        assert!(outline(&maze).is_empty());
+    }
+
+    #[maze_test]
+    fn difficulty_disconnected(maze: TestMaze) {
+        assert_eq!(0.0, difficulty(&maze, matrix_pos(0, 0), matrix_pos(1, 1)));
+    }
+}
@@ -0,0 +1,58 @@
+//! # Errors
+//!
+//! This module contains the error returned when parsing the textual
+//! representations of the types of this crate and the crates building on it.
+
+use std::fmt;
+
+/// An error parsing a value from a string.
+///
+/// Every variant contains the string that failed to parse.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+    /// The string is not the name of a shape.
+    UnknownShape(String),
+
+    /// The string is not the name of an initialisation method.
+    UnknownMethod(String),
+
+    /// The string is not a valid colour.
+    InvalidColor {
+        /// The string that failed to parse.
+        value: String,
+
+        /// A description of the problem.
+        reason: String,
+    },
+
+    /// The string is not a valid heat map type.
+    UnknownHeatMapType(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use ParseError::*;
+        match self {
+            UnknownShape(value) => write!(f, "unknown shape: {}", value),
+            UnknownMethod(value) => write!(f, "unknown method: {}", value),
+            InvalidColor { value, reason } => {
+                write!(f, "invalid colour {}: {}", value, reason)
+            }
+            UnknownHeatMapType(value) => {
+                write!(f, "unknown heat map type: {}", value)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for String {
+    /// Converts the error to its message.
+    ///
+    /// This allows using `?` on parse results in functions returning string
+    /// errors.
+    fn from(source: ParseError) -> Self {
+        source.to_string()
+    }
+}
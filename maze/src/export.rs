@@ -0,0 +1,535 @@
+//! # Export of mazes to other representations
+//!
+//! This module contains functions converting mazes to and from compact
+//! representations, such as bit masks suitable for tile based game engines,
+//! _Tiled_ tile maps and a binary format suitable for storage.
+
+use crate::matrix;
+use crate::wall;
+use crate::Maze;
+use crate::Shape;
+use crate::Topology;
+use crate::WallPos;
+
+/// Converts a maze to a matrix of bit masks.
+///
+/// Each value is the union of [`wall::Wall::mask`] for all open walls of the
+/// corresponding room.
+///
+/// # Arguments
+/// *  `maze` - The maze to export.
+pub fn to_bitmask_matrix<T>(maze: &Maze<T>) -> matrix::Matrix<wall::Mask>
+where
+    T: Clone,
+{
+    maze.rooms.map_with_pos(|pos, _| {
+        maze.doors(pos).fold(0, |acc, wall| acc | wall.mask())
+    })
+}
+
+impl Shape {
+    /// Creates a maze of this type from a matrix of bit masks.
+    ///
+    /// This is the inverse of [`to_bitmask_matrix`].
+    ///
+    /// The bit masks do not record the topology of the maze, so it must be
+    /// passed separately.
+    ///
+    /// # Arguments
+    /// *  `masks` - The bit masks of open walls for every room.
+    /// *  `topology` - The topology of the maze.
+    ///
+    /// # Errors
+    /// An error is returned if the topology is not supported for this shape
+    /// and the dimensions of `masks`, if a mask contains bits not
+    /// corresponding to a wall of its room, or if a wall is open while its
+    /// back, inside of the maze, is not.
+    pub fn create_from_bitmasks(
+        self,
+        masks: matrix::Matrix<wall::Mask>,
+        topology: Topology,
+    ) -> Result<Maze<()>, String> {
+        let mut maze = self
+            .create(masks.width, masks.height)
+            .with_topology(topology)?;
+        for pos in masks.positions() {
+            let mask = masks[pos];
+            let walls = maze.walls(pos);
+            let valid = walls.iter().fold(0, |acc, wall| acc | wall.mask());
+            if mask & !valid != 0 {
+                return Err(format!(
+                    "invalid walls for room {:?}: {:#x}",
+                    pos,
+                    mask & !valid,
+                ));
+            }
+
+            for &wall in walls.iter().filter(|wall| mask & wall.mask() != 0) {
+                let (back_pos, back) = maze.back((pos, wall));
+                if masks
+                    .get(back_pos)
+                    .map(|&m| m & back.mask() == 0)
+                    .unwrap_or(false)
+                {
+                    return Err(format!(
+                        "wall {} of room {:?} is open only on one side",
+                        wall.name, pos,
+                    ));
+                }
+            }
+            maze.set_walls_mask(pos, mask);
+        }
+
+        Ok(maze)
+    }
+}
+
+/// The magic bytes starting the binary representation of a maze.
+const MAGIC: &[u8; 4] = b"MAZE";
+
+/// The version of the binary representation of a maze.
+const VERSION: u8 = 2;
+
+/// The length of the header of the binary representation of a maze.
+const HEADER_LENGTH: usize = MAGIC.len() + 3 + 2 * 4;
+
+/// Converts a maze to a compact binary representation.
+///
+/// The representation starts with a header: the magic bytes `MAZE`, a version
+/// byte, currently `2`, a byte containing the number of walls of each room
+/// identifying the shape, a byte identifying the topology, `0` for
+/// [`Topology::Bounded`] and `1` for [`Topology::Torus`], and the width and
+/// height of the maze as unsigned 32 bit little endian integers.
+///
+/// The header is followed by one bit per wall, describing whether it is open,
+/// for every room in row major order. Only walls leading out of the maze, or
+/// to a room at a greater position, are included, since the back of a wall
+/// always has the same state. Bits are packed starting with the least
+/// significant bit of every byte, and the last byte is padded with zeroes.
+///
+/// Room data and whether rooms are visited is not included.
+///
+/// # Arguments
+/// *  `maze` - The maze to export.
+///
+/// # Panics
+/// This function panics if the width or height of the maze do not fit in 32
+/// bits.
+pub fn to_bytes<T>(maze: &Maze<T>) -> Vec<u8>
+where
+    T: Clone,
+{
+    let mut result = Vec::with_capacity(
+        HEADER_LENGTH + (maze.width() * maze.height()).div_ceil(4),
+    );
+    result.extend_from_slice(MAGIC);
+    result.push(VERSION);
+    result.push(maze.shape() as u8);
+    result.push(match maze.topology() {
+        Topology::Bounded => 0,
+        Topology::Torus => 1,
+    });
+    for dimension in [maze.width(), maze.height()] {
+        result.extend_from_slice(
+            &u32::try_from(dimension)
+                .expect("dimension too large")
+                .to_le_bytes(),
+        );
+    }
+
+    for (i, open) in stored_walls(maze)
+        .map(|wall_pos| maze.is_open(wall_pos))
+        .enumerate()
+    {
+        if i % 8 == 0 {
+            result.push(0);
+        }
+        if open {
+            *result.last_mut().unwrap() |= 1 << (i % 8);
+        }
+    }
+
+    result
+}
+
+/// Creates a maze from its compact binary representation.
+///
+/// This is the inverse of [`to_bytes`]. Rooms with at least one open wall are
+/// marked as visited.
+///
+/// # Arguments
+/// *  `bytes` - The binary representation.
+///
+/// # Errors
+/// An error is returned if the header is invalid or has an unsupported
+/// version, if the topology is not supported for the shape and dimensions, or
+/// if the length of the data does not match the dimensions of the maze.
+pub fn from_bytes(bytes: &[u8]) -> Result<Maze<()>, String> {
+    if bytes.len() < HEADER_LENGTH {
+        return Err(format!("truncated header: {} bytes", bytes.len()));
+    }
+    let (header, data) = bytes.split_at(HEADER_LENGTH);
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err("invalid magic bytes".to_owned());
+    }
+    let header = &header[MAGIC.len()..];
+    if header[0] != VERSION {
+        return Err(format!("unsupported version: {}", header[0]));
+    }
+    let shape = Shape::try_from(u32::from(header[1]))
+        .map_err(|s| format!("invalid shape: {}", s))?;
+    let topology = match header[2] {
+        0 => Topology::Bounded,
+        1 => Topology::Torus,
+        t => return Err(format!("invalid topology: {}", t)),
+    };
+    let dimension = |offset: usize| {
+        u32::from_le_bytes(header[offset..offset + 4].try_into().unwrap())
+            as usize
+    };
+    let (width, height) = (dimension(3), dimension(7));
+
+    // Every room stores at least one wall, so we can reject overly large
+    // dimensions before allocating the maze
+    if width
+        .checked_mul(height)
+        .is_none_or(|rooms| rooms > data.len().saturating_mul(8))
+    {
+        return Err(format!(
+            "{} bytes of data is too short for a {}x{} maze",
+            data.len(),
+            width,
+            height,
+        ));
+    }
+
+    let mut maze = shape.create(width, height).with_topology(topology)?;
+    let walls = stored_walls(&maze).collect::<Vec<_>>();
+    if data.len() != walls.len().div_ceil(8) {
+        return Err(format!(
+            "expected {} bytes of data for a {}x{} maze, found {}",
+            walls.len().div_ceil(8),
+            width,
+            height,
+            data.len(),
+        ));
+    }
+    for (i, wall_pos) in walls.into_iter().enumerate() {
+        if data[i / 8] & (1 << (i % 8)) != 0 {
+            maze.open(wall_pos);
+        }
+    }
+
+    Ok(maze)
+}
+
+/// A tile set referenced by an exported _Tiled_ map.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tileset {
+    /// The path of the external `.tsx` tile set file.
+    pub source: String,
+
+    /// The width of a tile, in pixels.
+    pub tile_width: u32,
+
+    /// The height of a tile, in pixels.
+    pub tile_height: u32,
+}
+
+/// Converts a quad maze to a _Tiled_ TMX map.
+///
+/// The map has a single CSV encoded layer with one tile per room. The tile
+/// set is expected to contain 16 tiles, where the tile with the local ID `n`
+/// has the open walls described by the bit mask `n`, as returned by
+/// [`to_bitmask_matrix`]: bit 0 for left, 1 for up, 2 for right and 3 for
+/// down. Since global tile IDs start at `1`, the tile ID of every room is its
+/// bit mask plus one.
+///
+/// # Arguments
+/// *  `maze` - The maze to export.
+/// *  `tileset` - The tile set to reference.
+///
+/// # Errors
+/// An error is returned if the maze is not a quad maze.
+pub fn to_tmx<T>(maze: &Maze<T>, tileset: &Tileset) -> Result<String, String>
+where
+    T: Clone,
+{
+    if maze.shape() != Shape::Quad {
+        return Err(format!("unsupported shape: {}", maze.shape()));
+    }
+
+    let masks = to_bitmask_matrix(maze);
+    let data = (0..maze.height())
+        .map(|row| {
+            (0..maze.width())
+                .map(|col| {
+                    let pos = matrix::Pos {
+                        col: col as isize,
+                        row: row as isize,
+                    };
+                    (masks[pos] + 1).to_string()
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        })
+        .collect::<Vec<_>>()
+        .join(",\n");
+
+    Ok(format!(
+        concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<map version=\"1.10\" orientation=\"orthogonal\" ",
+            "renderorder=\"right-down\" width=\"{width}\" ",
+            "height=\"{height}\" tilewidth=\"{tile_width}\" ",
+            "tileheight=\"{tile_height}\" infinite=\"0\">\n",
+            " <tileset firstgid=\"1\" source=\"{source}\"/>\n",
+            " <layer id=\"1\" name=\"maze\" width=\"{width}\" ",
+            "height=\"{height}\">\n",
+            "  <data encoding=\"csv\">\n{data}\n</data>\n",
+            " </layer>\n",
+            "</map>\n",
+        ),
+        width = maze.width(),
+        height = maze.height(),
+        tile_width = tileset.tile_width,
+        tile_height = tileset.tile_height,
+        source = escape(&tileset.source),
+        data = data,
+    ))
+}
+
+/// Escapes the characters of a string with special meaning in XML.
+///
+/// The result may be used both as text and as an attribute value.
+///
+/// # Example
+///
+/// ```
+/// assert_eq!(
+///     maze::export::escape("<a & 'b'>"),
+///     "&lt;a &amp; &apos;b&apos;&gt;",
+/// );
+/// ```
+///
+/// # Arguments
+/// *  `s` - The string to escape.
+pub fn escape(s: &str) -> String {
+    s.chars()
+        .fold(String::with_capacity(s.len()), |mut acc, c| {
+            match c {
+                '&' => acc.push_str("&amp;"),
+                '<' => acc.push_str("&lt;"),
+                '>' => acc.push_str("&gt;"),
+                '"' => acc.push_str("&quot;"),
+                '\'' => acc.push_str("&apos;"),
+                c => acc.push(c),
+            }
+            acc
+        })
+}
+
+/// Iterates over the walls stored in the binary representation of a maze.
+///
+/// See [`to_bytes`].
+///
+/// # Arguments
+/// *  `maze` - The maze.
+fn stored_walls<T>(maze: &Maze<T>) -> impl Iterator<Item = WallPos> + '_
+where
+    T: Clone,
+{
+    maze.positions()
+        .flat_map(|pos| maze.walls(pos).iter().map(move |&wall| (pos, wall)))
+        .filter(|&wall_pos| {
+            let (back, _) = maze.back(wall_pos);
+            !maze.is_inside(back) || wall_pos.0 < back
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize;
+    use crate::test_utils::*;
+
+    /// Initialises a maze with every supported topology.
+    ///
+    /// The entrance of bounded mazes is opened as well.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise.
+    fn topologies(maze: TestMaze) -> impl Iterator<Item = TestMaze> {
+        [Topology::Bounded, Topology::Torus]
+            .into_iter()
+            .filter_map(move |topology| {
+                maze.clone().with_topology(topology).ok()
+            })
+            .map(|maze| {
+                let mut maze = maze.initialize(
+                    initialize::Method::Winding,
+                    &mut initialize::LFSR::new(12345),
+                );
+                let entrance = matrix_pos(0, 0);
+                maze.open((entrance, maze.walls(entrance)[0]));
+                maze
+            })
+    }
+
+    #[maze_test]
+    fn bitmask_round_trip(maze: TestMaze) {
+        for maze in topologies(maze) {
+            let imported = maze
+                .shape()
+                .create_from_bitmasks(to_bitmask_matrix(&maze), maze.topology())
+                .unwrap();
+
+            assert_eq!(maze.topology(), imported.topology());
+            for pos in maze.positions() {
+                for &wall in maze.walls(pos) {
+                    assert_eq!(
+                        maze.is_open((pos, wall)),
+                        imported.is_open((pos, wall)),
+                    );
+                }
+            }
+        }
+    }
+
+    #[maze_test]
+    fn bytes_round_trip(maze: TestMaze) {
+        for maze in topologies(maze) {
+            let imported = from_bytes(&to_bytes(&maze)).unwrap();
+
+            assert_eq!(maze.shape(), imported.shape());
+            assert_eq!(maze.topology(), imported.topology());
+            assert_eq!(to_bitmask_matrix(&maze), to_bitmask_matrix(&imported));
+        }
+    }
+
+    #[test]
+    fn bytes_torus() {
+        for (width, height) in [(7, 5), (8, 8)] {
+            let maze = Shape::Quad
+                .create::<()>(width, height)
+                .with_topology(Topology::Torus)
+                .unwrap()
+                .initialize(
+                    initialize::Method::Winding,
+                    &mut initialize::LFSR::new(12345),
+                );
+            let imported = from_bytes(&to_bytes(&maze)).unwrap();
+
+            assert_eq!(Topology::Torus, imported.topology());
+            assert_eq!(to_bitmask_matrix(&maze), to_bitmask_matrix(&imported));
+        }
+    }
+
+    #[test]
+    fn bytes_compact() {
+        let maze = Shape::Quad.create::<()>(1000, 1000);
+
+        assert!(to_bytes(&maze).len() < 251_000);
+    }
+
+    #[maze_test]
+    fn bytes_invalid(maze: TestMaze) {
+        let bytes = to_bytes(&maze);
+        for length in 0..bytes.len() {
+            assert!(from_bytes(&bytes[..length]).is_err());
+        }
+        for i in 0..HEADER_LENGTH {
+            let mut garbage = bytes.clone();
+            garbage[i] ^= 0xff;
+            assert!(from_bytes(&garbage).is_err());
+        }
+        let mut extended = bytes.clone();
+        extended.push(0);
+        assert!(from_bytes(&extended).is_err());
+        assert!(from_bytes(&[0xff; 64]).is_err());
+    }
+
+    #[maze_test]
+    fn bitmask_one_sided(maze: TestMaze) {
+        let pos = matrix_pos(1, 1);
+        let mut masks = to_bitmask_matrix(&maze);
+        masks[pos] = maze.walls(pos)[0].mask();
+        assert!(maze
+            .shape()
+            .create_from_bitmasks(masks, Topology::Bounded)
+            .is_err());
+    }
+
+    #[maze_test]
+    fn bitmask_invalid_wall(maze: TestMaze) {
+        let mut masks = to_bitmask_matrix(&maze);
+        masks[matrix_pos(1, 1)] = 1 << 31;
+        assert!(maze
+            .shape()
+            .create_from_bitmasks(masks, Topology::Bounded)
+            .is_err());
+    }
+
+    /// The tile set used by TMX tests.
+    fn tileset() -> Tileset {
+        Tileset {
+            source: "walls & doors.tsx".to_owned(),
+            tile_width: 16,
+            tile_height: 16,
+        }
+    }
+
+    /// Extracts the value of an attribute from the first matching tag.
+    fn attribute<'a>(xml: &'a str, tag: &str, name: &str) -> &'a str {
+        let start = xml.find(&format!("<{} ", tag)).unwrap();
+        let rest = &xml[start..];
+        let rest = &rest[..rest.find('>').unwrap()];
+        let value = &rest
+            [rest.find(&format!(" {}=\"", name)).unwrap() + name.len() + 3..];
+        &value[..value.find('"').unwrap()]
+    }
+
+    #[maze_test(quad)]
+    fn tmx_layer(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let tmx = to_tmx(&maze, &tileset()).unwrap();
+
+        assert_eq!(maze.width().to_string(), attribute(&tmx, "layer", "width"));
+        assert_eq!(
+            maze.height().to_string(),
+            attribute(&tmx, "layer", "height"),
+        );
+        assert_eq!("16", attribute(&tmx, "map", "tilewidth"));
+        assert_eq!(
+            "walls &amp; doors.tsx",
+            attribute(&tmx, "tileset", "source"),
+        );
+
+        let start = tmx.find("<data encoding=\"csv\">").unwrap();
+        let data = &tmx[start..tmx.find("</data>").unwrap()];
+        let tiles = data[data.find('>').unwrap() + 1..]
+            .split(',')
+            .map(|tile| tile.trim().parse::<u32>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(maze.width() * maze.height(), tiles.len());
+        let masks = to_bitmask_matrix(&maze);
+        assert_eq!(
+            maze.positions()
+                .map(|pos| masks[pos] + 1)
+                .collect::<Vec<_>>(),
+            tiles,
+        );
+    }
+
+    #[test]
+    fn tmx_unsupported() {
+        for shape in [Shape::Hex, Shape::Tri] {
+            assert!(to_tmx(&shape.create::<()>(5, 5), &tileset()).is_err());
+        }
+    }
+}
@@ -0,0 +1,426 @@
+//! # Generation of many mazes
+//!
+//! This module contains functions generating several mazes at once. With the
+//! `parallel` feature enabled, the mazes are generated in parallel.
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::initialize;
+use crate::matrix;
+use crate::Maze;
+use crate::Shape;
+
+/// The parameters required to regenerate a maze.
+///
+/// A descriptor is a small, fully reproducible alternative to exchanging the
+/// maze itself.
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub struct MazeDescriptor {
+    /// The shape of the maze.
+    pub shape: Shape,
+
+    /// The width, in rooms, of the maze.
+    pub width: usize,
+
+    /// The height, in rooms, of the maze.
+    pub height: usize,
+
+    /// The seed for the random number generator.
+    pub seed: u64,
+
+    /// The initialisation method to use.
+    pub method: initialize::Method,
+}
+
+impl MazeDescriptor {
+    /// Generates the maze described.
+    ///
+    /// The maze is initialised using an [`initialize::LFSR`] with the seed as
+    /// source of random values, so building the same descriptor always
+    /// yields the same maze.
+    pub fn build(&self) -> Maze<()> {
+        self.shape
+            .create(self.width, self.height)
+            .initialize(self.method, &mut initialize::LFSR::new(self.seed))
+    }
+}
+
+/// The default width and height of mazes created by [`MazeBuilder`].
+const DEFAULT_SIZE: usize = 10;
+
+/// A builder for configuring and generating a maze in one expression.
+///
+/// # Example
+///
+/// ```
+/// # use maze::generate::MazeBuilder;
+/// # use maze::initialize::Method;
+/// let maze = MazeBuilder::new(maze::Shape::Hex)
+///     .size(8, 5)
+///     .seed(42)
+///     .method(Method::Winding)
+///     .build();
+///
+/// assert_eq!((8, 5), (maze.width(), maze.height()));
+/// assert!(maze.positions().all(|pos| maze[pos].visited));
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MazeBuilder(MazeDescriptor);
+
+impl MazeBuilder {
+    /// Creates a builder for a maze with a specific shape.
+    ///
+    /// The maze is initially `10` by `10` rooms, initialised using the
+    /// default method. With the `rand` feature enabled, the seed is random;
+    /// otherwise it is `0`.
+    ///
+    /// # Arguments
+    /// *  `shape` - The shape of the maze.
+    pub fn new(shape: Shape) -> Self {
+        Self(MazeDescriptor {
+            shape,
+            width: DEFAULT_SIZE,
+            height: DEFAULT_SIZE,
+            seed: default_seed(),
+            method: initialize::Method::default(),
+        })
+    }
+
+    /// Sets the size of the maze.
+    ///
+    /// # Arguments
+    /// *  `width` - The width, in rooms, of the maze.
+    /// *  `height` - The height, in rooms, of the maze.
+    pub fn size(self, width: usize, height: usize) -> Self {
+        Self(MazeDescriptor {
+            width,
+            height,
+            ..self.0
+        })
+    }
+
+    /// Sets the seed for the random number generator.
+    ///
+    /// # Arguments
+    /// *  `seed` - The seed.
+    pub fn seed(self, seed: u64) -> Self {
+        Self(MazeDescriptor { seed, ..self.0 })
+    }
+
+    /// Sets the initialisation method.
+    ///
+    /// # Arguments
+    /// *  `method` - The initialisation method to use.
+    pub fn method(self, method: initialize::Method) -> Self {
+        Self(MazeDescriptor { method, ..self.0 })
+    }
+
+    /// The descriptor of the maze being built.
+    ///
+    /// This can be used to regenerate the maze later, also when the seed was
+    /// not set explicitly.
+    pub fn descriptor(&self) -> MazeDescriptor {
+        self.0
+    }
+
+    /// Generates the maze.
+    ///
+    /// See [`MazeDescriptor::build`].
+    pub fn build(&self) -> Maze<()> {
+        self.0.build()
+    }
+}
+
+impl From<MazeDescriptor> for MazeBuilder {
+    fn from(source: MazeDescriptor) -> Self {
+        Self(source)
+    }
+}
+
+/// The seed used by a new [`MazeBuilder`].
+#[cfg(feature = "rand")]
+fn default_seed() -> u64 {
+    rand::random()
+}
+
+/// The seed used by a new [`MazeBuilder`].
+#[cfg(not(feature = "rand"))]
+fn default_seed() -> u64 {
+    0
+}
+
+/// Generates one maze for every seed.
+///
+/// Every maze is initialised using an [`initialize::LFSR`] with the seed as
+/// source of random values, so the result for a seed is the same as for a
+/// single maze generated with the same seed. The order of the generated mazes
+/// corresponds to the order of the seeds.
+///
+/// # Arguments
+/// *  `shape` - The shape of the mazes.
+/// *  `width` - The width, in rooms, of the mazes.
+/// *  `height` - The height, in rooms, of the mazes.
+/// *  `method` - The initialisation method to use.
+/// *  `seeds` - The seeds for the random number generators.
+pub fn batch(
+    shape: Shape,
+    width: usize,
+    height: usize,
+    method: initialize::Method,
+    seeds: &[u64],
+) -> Vec<Maze<()>> {
+    let generate = |&seed: &u64| {
+        MazeDescriptor {
+            shape,
+            width,
+            height,
+            seed,
+            method,
+        }
+        .build()
+    };
+
+    #[cfg(feature = "parallel")]
+    {
+        seeds.par_iter().map(generate).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    {
+        seeds.iter().map(generate).collect()
+    }
+}
+
+/// Generates a maze with a solution of at least a minimum length.
+///
+/// Mazes are generated until the number of steps from `from` to `to`, as
+/// calculated by [`Maze::distance`], is at least `min_len`, or `attempts`
+/// mazes have been generated. At least one maze is always generated.
+///
+/// The return value is the tuple `(maze, length)`. If the minimum length was
+/// not reached within the number of attempts, the maze with the longest
+/// solution found is returned, so callers must check `length` if the minimum
+/// is a hard requirement. Rooms that are not connected have a length of `0`.
+///
+/// # Arguments
+/// *  `shape` - The shape of the maze.
+/// *  `width` - The width, in rooms, of the maze.
+/// *  `height` - The height, in rooms, of the maze.
+/// *  `method` - The initialisation method to use.
+/// *  `from` - The starting position.
+/// *  `to` - The goal.
+/// *  `min_len` - The minimum number of steps from `from` to `to`.
+/// *  `attempts` - The maximum number of mazes to generate.
+/// *  `rng` - A random number generator.
+#[allow(clippy::too_many_arguments)]
+pub fn with_min_solution<R>(
+    shape: Shape,
+    width: usize,
+    height: usize,
+    method: initialize::Method,
+    from: matrix::Pos,
+    to: matrix::Pos,
+    min_len: u32,
+    attempts: usize,
+    rng: &mut R,
+) -> (Maze<()>, u32)
+where
+    R: initialize::Randomizer + Sized,
+{
+    let mut generate = || {
+        let maze = shape.create(width, height).initialize(method, rng);
+        let length = maze.distance(from, to).unwrap_or(0);
+        (maze, length)
+    };
+
+    let mut best = generate();
+    for _ in 1..attempts {
+        if best.1 >= min_len {
+            break;
+        }
+        let candidate = generate();
+        if candidate.1 > best.1 {
+            best = candidate;
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::export;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn batch_matches_sequential(maze: TestMaze) {
+        let seeds = [1, 12345, 2, 54321, 3];
+        let mazes = batch(
+            maze.shape(),
+            maze.width(),
+            maze.height(),
+            initialize::Method::Branching,
+            &seeds,
+        );
+
+        assert_eq!(seeds.len(), mazes.len());
+        for (seed, generated) in seeds.iter().zip(mazes.iter()) {
+            let expected = maze.clone().initialize(
+                initialize::Method::Branching,
+                &mut initialize::LFSR::new(*seed),
+            );
+            assert_eq!(
+                export::to_bitmask_matrix(&expected),
+                export::to_bitmask_matrix(generated),
+            );
+        }
+    }
+
+    #[maze_test]
+    fn descriptor_build_stable(maze: TestMaze) {
+        let descriptor = MazeDescriptor {
+            shape: maze.shape(),
+            width: maze.width(),
+            height: maze.height(),
+            seed: 12345,
+            method: initialize::Method::Branching,
+        };
+
+        assert_eq!(
+            export::to_bitmask_matrix(&descriptor.build()),
+            export::to_bitmask_matrix(&descriptor.build()),
+        );
+    }
+
+    #[maze_test]
+    fn builder_matches_pipeline(maze: TestMaze) {
+        let built = MazeBuilder::new(maze.shape())
+            .size(maze.width(), maze.height())
+            .seed(12345)
+            .method(initialize::Method::Winding)
+            .build();
+        let expected = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        assert_eq!(
+            export::to_bitmask_matrix(&expected),
+            export::to_bitmask_matrix(&built),
+        );
+        assert!(built.positions().all(|pos| built[pos].visited));
+    }
+
+    #[test]
+    fn builder_descriptor() {
+        let builder = MazeBuilder::new(Shape::Tri).size(3, 4);
+        let descriptor = builder.descriptor();
+
+        assert_eq!(
+            (Shape::Tri, 3, 4),
+            (descriptor.shape, descriptor.width, descriptor.height),
+        );
+        assert_eq!(builder, MazeBuilder::from(descriptor));
+        assert_eq!(initialize::Method::default(), descriptor.method);
+    }
+
+    #[cfg(feature = "serde")]
+    #[maze_test]
+    fn descriptor_round_trip(maze: TestMaze) {
+        let descriptor = MazeDescriptor {
+            shape: maze.shape(),
+            width: maze.width(),
+            height: maze.height(),
+            seed: 12345,
+            method: initialize::Method::GrowingTree(
+                initialize::growing_tree::Policy::MixNewestRandom(0.25),
+            ),
+        };
+        let deserialized = serde_json::from_str::<MazeDescriptor>(
+            &serde_json::to_string(&descriptor).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(descriptor, deserialized);
+        assert_eq!(
+            export::to_bitmask_matrix(&descriptor.build()),
+            export::to_bitmask_matrix(&deserialized.build()),
+        );
+    }
+
+    #[maze_test]
+    fn with_min_solution_reached(maze: TestMaze) {
+        let (from, to) = (
+            matrix_pos(0, 0),
+            matrix_pos(maze.width() as isize - 1, maze.height() as isize - 1),
+        );
+        let min_len = 20;
+        let (generated, length) = with_min_solution(
+            maze.shape(),
+            maze.width(),
+            maze.height(),
+            initialize::Method::Winding,
+            from,
+            to,
+            min_len,
+            100,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        assert!(length >= min_len);
+        assert_eq!(Some(length), generated.distance(from, to));
+    }
+
+    #[maze_test]
+    fn with_min_solution_cap(maze: TestMaze) {
+        // The minimum is unreachable, so the longest solution found is used
+        let (from, to) = (matrix_pos(0, 0), matrix_pos(1, 0));
+        let min_len = (maze.width() * maze.height()) as u32;
+        let (generated, length) = with_min_solution(
+            maze.shape(),
+            maze.width(),
+            maze.height(),
+            initialize::Method::Branching,
+            from,
+            to,
+            min_len,
+            10,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        assert!(length < min_len);
+        assert_eq!(Some(length), generated.distance(from, to));
+
+        let mut rng = initialize::LFSR::new(12345);
+        let longest = (0..10)
+            .map(|_| {
+                maze.clone()
+                    .initialize(initialize::Method::Branching, &mut rng)
+                    .distance(from, to)
+                    .unwrap()
+            })
+            .max()
+            .unwrap();
+        assert_eq!(longest, length);
+    }
+
+    #[maze_test]
+    fn batch_empty(maze: TestMaze) {
+        assert!(batch(
+            maze.shape(),
+            maze.width(),
+            maze.height(),
+            initialize::Method::Winding,
+            &[],
+        )
+        .is_empty());
+    }
+}
@@ -0,0 +1,202 @@
+//! # Mazes as graphs
+//!
+//! This module contains a representation of the rooms of a maze as an
+//! undirected graph, suitable for general graph algorithms.
+
+use crate::matrix;
+use crate::Maze;
+
+/// An undirected graph of the rooms of a maze.
+///
+/// Every room inside of the maze is a node, identified by a flattened index
+/// in the range `[0, len())`, and every open wall between two rooms inside of
+/// the maze is an edge. Open walls leading out of the maze are ignored.
+///
+/// Use [`Graph::position`] and [`Graph::index`] to map between node indices
+/// and room positions.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Graph {
+    /// The width of the maze.
+    width: usize,
+
+    /// The neighbours of each node, in the order of the walls of the room.
+    adjacency: Vec<Vec<usize>>,
+}
+
+impl Graph {
+    /// The number of nodes.
+    pub fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+
+    /// Whether this graph has no nodes.
+    pub fn is_empty(&self) -> bool {
+        self.adjacency.is_empty()
+    }
+
+    /// The number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.adjacency.iter().map(Vec::len).sum::<usize>() / 2
+    }
+
+    /// Iterates over all edges.
+    ///
+    /// Every edge is yielded once, as the tuple `(a, b)` where `a < b`.
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.adjacency
+            .iter()
+            .enumerate()
+            .flat_map(|(a, neighbours)| {
+                neighbours
+                    .iter()
+                    .filter(move |&&b| a < b)
+                    .map(move |&b| (a, b))
+            })
+    }
+
+    /// The neighbours of a node.
+    ///
+    /// # Arguments
+    /// *  `index` - The node index.
+    ///
+    /// # Panics
+    /// This method panics if `index` is out of bounds.
+    pub fn neighbours(&self, index: usize) -> &[usize] {
+        &self.adjacency[index]
+    }
+
+    /// The number of neighbours of a node.
+    ///
+    /// # Arguments
+    /// *  `index` - The node index.
+    ///
+    /// # Panics
+    /// This method panics if `index` is out of bounds.
+    pub fn degree(&self, index: usize) -> usize {
+        self.adjacency[index].len()
+    }
+
+    /// The room position of a node.
+    ///
+    /// # Arguments
+    /// *  `index` - The node index.
+    pub fn position(&self, index: usize) -> matrix::Pos {
+        matrix::Pos {
+            col: (index % self.width) as isize,
+            row: (index / self.width) as isize,
+        }
+    }
+
+    /// The node index of a room position.
+    ///
+    /// If the position is outside of the maze, `None` is returned.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn index(&self, pos: matrix::Pos) -> Option<usize> {
+        if pos.col >= 0 && pos.row >= 0 && (pos.col as usize) < self.width {
+            let index = pos.row as usize * self.width + pos.col as usize;
+            Some(index).filter(|&index| index < self.len())
+        } else {
+            None
+        }
+    }
+}
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Creates an undirected graph of the rooms of this maze.
+    ///
+    /// See [`Graph`] for a description of the nodes and edges.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let maze = maze::Shape::Quad.create::<()>(5, 5).initialize(
+    ///     maze::initialize::Method::Winding,
+    ///     &mut maze::initialize::LFSR::new(12345),
+    /// );
+    /// let graph = maze.to_graph();
+    ///
+    /// // A perfect maze is a tree
+    /// assert_eq!(graph.len() - 1, graph.edge_count());
+    /// assert_eq!(
+    ///     Some(matrix::Pos { col: 3, row: 2 }),
+    ///     graph
+    ///         .index(matrix::Pos { col: 3, row: 2 })
+    ///         .map(|index| graph.position(index)),
+    /// );
+    /// ```
+    pub fn to_graph(&self) -> Graph {
+        let width = self.width();
+        let adjacency = self
+            .positions()
+            .map(|pos| {
+                self.doors(pos)
+                    .map(|wall| self.back((pos, wall)).0)
+                    .filter(|&next| self.is_inside(next))
+                    .map(|next| next.row as usize * width + next.col as usize)
+                    .collect()
+            })
+            .collect();
+
+        Graph { width, adjacency }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn to_graph_edges(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Braid,
+            &mut rand::thread_rng(),
+        );
+        let graph = maze.to_graph();
+
+        let open = maze
+            .positions()
+            .flat_map(|pos| maze.doors(pos).map(move |wall| (pos, wall)))
+            .filter(|&(pos, wall)| maze.is_inside(maze.back((pos, wall)).0))
+            .count();
+        assert_eq!(maze.positions().count(), graph.len());
+        assert_eq!(open / 2, graph.edge_count());
+        assert_eq!(graph.edge_count(), graph.edges().count());
+    }
+
+    #[maze_test]
+    fn to_graph_degree(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Branching,
+            &mut rand::thread_rng(),
+        );
+        let graph = maze.to_graph();
+
+        for pos in maze.positions() {
+            let index = graph.index(pos).unwrap();
+            assert_eq!(pos, graph.position(index));
+            assert_eq!(maze[pos].open_walls(), graph.degree(index));
+            for &neighbour in graph.neighbours(index) {
+                assert!(maze.connected(pos, graph.position(neighbour)));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn to_graph_index_outside(maze: TestMaze) {
+        let graph = maze.to_graph();
+
+        assert_eq!(None, graph.index(matrix_pos(-1, 0)));
+        assert_eq!(None, graph.index(matrix_pos(0, -1)));
+        assert_eq!(None, graph.index(matrix_pos(maze.width() as isize, 0)));
+        assert_eq!(None, graph.index(matrix_pos(0, maze.height() as isize)));
+        assert_eq!(0, graph.edge_count());
+    }
+}
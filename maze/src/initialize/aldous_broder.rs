@@ -0,0 +1,83 @@
+//! # The _Aldous-Broder_ algorithm
+//!
+//! This algorithm performs a random walk through the maze, opening the wall to
+//! every room the first time it is entered, until all rooms have been
+//! visited. Every spanning tree of the maze is generated with the same
+//! probability.
+//!
+//! Since the walk keeps revisiting rooms, this is considerably slower than the
+//! other initialisers, especially for large mazes; see [`wilson`](super::wilson)
+//! for a faster algorithm generating the same distribution.
+
+use crate::Maze;
+
+use crate::matrix;
+
+/// Initialises a maze using the _Aldous-Broder_ algorithm.
+///
+/// The maze should be fully closed; any already open walls will be ignored and
+/// kept.
+///
+/// This method will ignore rooms for which `filter` returns `false`.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `filter` - A filter function used to ignore rooms.
+pub fn initialize<F, R, T>(maze: Maze<T>, rng: &mut R, filter: F) -> Maze<T>
+where
+    F: Fn(matrix::Pos) -> bool,
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    match matrix::filter(maze.width(), maze.height(), filter) {
+        (count, candidates) if count > 0 => {
+            initialize_candidates(maze, rng, candidates)
+        }
+        _ => maze,
+    }
+}
+
+/// Initialises a maze using the _Aldous-Broder_ algorithm.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `candidates` - A filter for the rooms to modify.
+pub(crate) fn initialize_candidates<R, T>(
+    mut maze: Maze<T>,
+    rng: &mut R,
+    mut candidates: matrix::Matrix<bool>,
+) -> Maze<T>
+where
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    // The rooms that are part of the maze; unlike candidates, this is never
+    // modified
+    let filter = candidates.clone();
+
+    // Since the maze may be segmented, we walk each segment separately
+    while let Some(mut current) = super::random_room(rng, &candidates) {
+        let mut remaining = super::segment(&maze, &filter, current).len() - 1;
+        candidates[current] = false;
+
+        while remaining > 0 {
+            let neighbors = maze
+                .walls(current)
+                .iter()
+                .map(|wall| (wall, maze.back((current, wall)).0))
+                .filter(|&(_, next)| *filter.get(next).unwrap_or(&false))
+                .collect::<Vec<_>>();
+            let (wall, next) = neighbors[rng.range(0, neighbors.len())];
+            if candidates[next] {
+                maze.open((current, wall));
+                candidates[next] = false;
+                remaining -= 1;
+            }
+            current = next;
+        }
+    }
+
+    maze
+}
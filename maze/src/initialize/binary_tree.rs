@@ -0,0 +1,96 @@
+//! # The _Binary Tree_ algorithm
+//!
+//! This algorithm visits every room and opens either the wall in the
+//! vertical or the wall in the horizontal direction of the [bias](Bias). The
+//! result is a maze with two fully open corridors along the sides of the
+//! bias, and with a diagonal texture pointing towards the biased corner.
+//!
+//! The algorithm is only supported for [quad](crate::Shape::Quad) mazes.
+
+use crate::matrix;
+use crate::Maze;
+use crate::Shape;
+
+use super::Bias;
+
+/// Initialises a maze using the _Binary Tree_ algorithm.
+///
+/// The maze should be fully closed; any already open walls will be ignored and
+/// kept.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `bias` - The corner towards which passages lead.
+///
+/// # Errors
+/// If the maze is not a quad maze, an error is returned.
+pub fn initialize<R, T>(
+    mut maze: Maze<T>,
+    rng: &mut R,
+    bias: Bias,
+) -> Result<Maze<T>, String>
+where
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    if maze.shape() != Shape::Quad {
+        return Err(format!("unsupported shape: {:?}", maze.shape()));
+    }
+
+    let positions = maze.positions().collect::<Vec<matrix::Pos>>();
+    for pos in positions {
+        let walls = [bias.vertical(), bias.horizontal()]
+            .into_iter()
+            .filter(|&wall| maze.is_inside(maze.back((pos, wall)).0))
+            .collect::<Vec<_>>();
+        if !walls.is_empty() {
+            maze.open((pos, walls[rng.range(0, walls.len())]));
+        }
+    }
+
+    Ok(maze)
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::{Bias, LFSR};
+    use crate::shape::quad::walls;
+    use crate::test_utils::*;
+
+    #[maze_test(quad)]
+    fn initialize_open_sides(maze: TestMaze) {
+        let (last_col, last_row) =
+            (maze.width() as isize - 1, maze.height() as isize - 1);
+
+        let maze_ne =
+            initialize(maze.clone(), &mut LFSR::new(12345), Bias::NorthEast)
+                .unwrap();
+        for col in 0..last_col {
+            assert!(maze_ne.is_open((matrix_pos(col, 0), &walls::RIGHT)));
+        }
+        for row in 1..=last_row {
+            assert!(maze_ne.is_open((matrix_pos(last_col, row), &walls::UP)));
+        }
+
+        let maze_sw =
+            initialize(maze.clone(), &mut LFSR::new(12345), Bias::SouthWest)
+                .unwrap();
+        for col in 1..=last_col {
+            assert!(maze_sw.is_open((matrix_pos(col, last_row), &walls::LEFT)));
+        }
+        for row in 0..last_row {
+            assert!(maze_sw.is_open((matrix_pos(0, row), &walls::DOWN)));
+        }
+    }
+
+    #[maze_test(hex, tri)]
+    fn initialize_unsupported(maze: TestMaze) {
+        assert!(
+            initialize(maze, &mut LFSR::new(12345), Bias::NorthEast).is_err()
+        );
+    }
+}
@@ -30,7 +30,7 @@ where
                     .map(|wall| (pos, *wall))
                     .collect::<Vec<_>>()
             })
-            .unwrap_or_else(Vec::<_>::new);
+            .unwrap_or_default();
 
         while !walls.is_empty() {
             // Get a random wall
@@ -0,0 +1,149 @@
+//! # _Eller's_ algorithm
+//!
+//! This algorithm generates a maze one row at a time, keeping track only of
+//! which set every room of the current row belongs to; two rooms are in the
+//! same set if they are connected through previous rows. Adjacent rooms in
+//! different sets are randomly joined, and every set is extended into the
+//! next row through at least one room. The last row joins all remaining
+//! sets.
+//!
+//! Since only one row is kept in memory, this is suitable for very tall
+//! mazes.
+//!
+//! The algorithm is only supported for [quad](crate::Shape::Quad) mazes.
+
+use std::collections::BTreeMap;
+
+use crate::matrix;
+use crate::shape::quad::walls;
+use crate::Maze;
+use crate::Shape;
+
+/// Initialises a maze using _Eller's_ algorithm.
+///
+/// The maze should be fully closed; any already open walls will be ignored and
+/// kept.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+///
+/// # Errors
+/// If the maze is not a quad maze, an error is returned.
+pub fn initialize<R, T>(
+    mut maze: Maze<T>,
+    rng: &mut R,
+) -> Result<Maze<T>, String>
+where
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    if maze.shape() != Shape::Quad {
+        return Err(format!("unsupported shape: {:?}", maze.shape()));
+    }
+
+    let (width, height) = (maze.width(), maze.height());
+
+    // The set of every room on the current row; 0 means no set
+    let mut sets = vec![0usize; width];
+    let mut next_set = 1;
+
+    for row in 0..height {
+        let last = row + 1 == height;
+        let pos = |col: usize| matrix::Pos {
+            col: col as isize,
+            row: row as isize,
+        };
+
+        // Rooms not connected from the previous row get new sets
+        for set in sets.iter_mut().filter(|set| **set == 0) {
+            *set = next_set;
+            next_set += 1;
+        }
+
+        // Randomly join adjacent rooms in different sets; on the last row all
+        // sets must be joined
+        for col in 0..width.saturating_sub(1) {
+            let (a, b) = (sets[col], sets[col + 1]);
+            if a != b && (last || rng.range(0, 2) == 0) {
+                maze.open((pos(col), &walls::RIGHT));
+                for set in sets.iter_mut().filter(|set| **set == b) {
+                    *set = a;
+                }
+            }
+        }
+
+        if last {
+            break;
+        }
+
+        // Extend every set into the next row through at least one room
+        let mut members = BTreeMap::new();
+        for (col, &set) in sets.iter().enumerate() {
+            members.entry(set).or_insert_with(Vec::new).push(col);
+        }
+        let mut next = vec![0usize; width];
+        for (set, cols) in members {
+            let required = cols[rng.range(0, cols.len())];
+            for col in cols {
+                if col == required || rng.range(0, 2) == 0 {
+                    maze.open((pos(col), &walls::DOWN));
+                    next[col] = set;
+                }
+            }
+        }
+        sets = next;
+    }
+
+    Ok(maze)
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::LFSR;
+    use crate::test_utils::*;
+
+    #[test]
+    fn initialize_perfect() {
+        for (width, height) in [(1, 1), (1, 6), (6, 1), (7, 3), (20, 20)] {
+            for seed in 0..10 {
+                let maze = initialize(
+                    TestMaze::new(Shape::Quad, width, height),
+                    &mut LFSR::new(seed),
+                )
+                .unwrap();
+
+                // A connected maze with one fewer passage than rooms has no
+                // loops
+                let rooms = width * height;
+                let passages = maze
+                    .positions()
+                    .map(|pos| maze.doors(pos).count())
+                    .sum::<usize>()
+                    / 2;
+                assert_eq!(rooms - 1, passages, "for {}x{}", width, height);
+                for pos in maze.positions() {
+                    assert!(maze.walk(matrix_pos(0, 0), pos).is_some());
+                }
+            }
+        }
+    }
+
+    #[maze_test(quad)]
+    fn initialize_inside(maze: TestMaze) {
+        let maze = initialize(maze, &mut LFSR::new(12345)).unwrap();
+        for pos in maze.positions() {
+            for wall in maze.doors(pos) {
+                assert!(maze.is_inside(maze.back((pos, wall)).0));
+            }
+        }
+    }
+
+    #[maze_test(hex, tri)]
+    fn initialize_unsupported(maze: TestMaze) {
+        assert!(initialize(maze, &mut LFSR::new(12345)).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! # Room filters
+//!
+//! This module contains helpers to create and combine filters for
+//! [`Maze::initialize_filter`] and the `initialize` functions of the various
+//! methods.
+//!
+//! # Example
+//!
+//! ```
+//! # use maze::initialize::filters;
+//! # use maze::matrix;
+//! let template = maze::Shape::Quad.create::<()>(10, 10);
+//! let center = template.center(matrix::Pos { col: 5, row: 5 });
+//! let maze = template.clone().initialize_filter(
+//!     maze::initialize::Method::Winding,
+//!     &mut maze::initialize::LFSR::new(12345),
+//!     filters::and(
+//!         filters::disc(&template, center, 4.0),
+//!         filters::rect(matrix::Pos { col: 0, row: 0 }, 10, 5),
+//!     ),
+//! );
+//!
+//! // Only the upper half of the disc is carved
+//! assert!(maze[matrix::Pos { col: 5, row: 4 }].visited);
+//! assert!(!maze[matrix::Pos { col: 5, row: 6 }].visited);
+//! ```
+
+use crate::matrix;
+use crate::physical;
+use crate::Maze;
+
+/// A filter selecting the rooms of a rectangle.
+///
+/// # Arguments
+/// *  `corner` - The top left room of the rectangle.
+/// *  `width` - The number of columns.
+/// *  `height` - The number of rows.
+pub fn rect(
+    corner: matrix::Pos,
+    width: usize,
+    height: usize,
+) -> impl Fn(matrix::Pos) -> bool {
+    move |pos| {
+        pos.col >= corner.col
+            && pos.row >= corner.row
+            && pos.col < corner.col + width as isize
+            && pos.row < corner.row + height as isize
+    }
+}
+
+/// A filter selecting the rooms whose centre lies within a circle.
+///
+/// # Arguments
+/// *  `maze` - The maze, used to find the centres of rooms.
+/// *  `center` - The centre of the circle.
+/// *  `radius` - The radius of the circle.
+pub fn disc<T>(
+    maze: &Maze<T>,
+    center: physical::Pos,
+    radius: f32,
+) -> impl Fn(matrix::Pos) -> bool + '_
+where
+    T: Clone,
+{
+    move |pos| (maze.center(pos) - center).value() <= radius * radius
+}
+
+/// A filter selecting the rooms selected by both of two filters.
+///
+/// # Arguments
+/// *  `a` - A filter.
+/// *  `b` - Another filter.
+pub fn and<A, B>(a: A, b: B) -> impl Fn(matrix::Pos) -> bool
+where
+    A: Fn(matrix::Pos) -> bool,
+    B: Fn(matrix::Pos) -> bool,
+{
+    move |pos| a(pos) && b(pos)
+}
+
+/// A filter selecting the rooms selected by either of two filters.
+///
+/// # Arguments
+/// *  `a` - A filter.
+/// *  `b` - Another filter.
+pub fn or<A, B>(a: A, b: B) -> impl Fn(matrix::Pos) -> bool
+where
+    A: Fn(matrix::Pos) -> bool,
+    B: Fn(matrix::Pos) -> bool,
+{
+    move |pos| a(pos) || b(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[test]
+    fn disc_area() {
+        let maze = crate::Shape::Quad.create::<()>(41, 41);
+        let center = maze.center(matrix_pos(20, 20));
+        let spacing = maze.center(matrix_pos(21, 20)).distance(center);
+        let radius = 15.0 * spacing;
+        let filter = disc(&maze, center, radius);
+
+        let count = maze.positions().filter(|&pos| filter(pos)).count();
+        let expected = std::f32::consts::PI * 15.0 * 15.0;
+        assert!(
+            (count as f32 - expected).abs() < expected * 0.05,
+            "{} rooms instead of about {}",
+            count,
+            expected,
+        );
+        assert!(filter(matrix_pos(20, 20)));
+        assert!(!filter(matrix_pos(0, 0)));
+    }
+
+    #[maze_test]
+    fn and_intersection(maze: TestMaze) {
+        let a = rect(matrix_pos(1, 1), 4, 3);
+        let b = rect(matrix_pos(3, 0), 5, 2);
+        let expected = rect(matrix_pos(3, 1), 2, 1);
+        let filter = and(&a, &b);
+
+        for pos in maze.positions() {
+            assert_eq!(expected(pos), filter(pos));
+            assert_eq!(a(pos) || b(pos), or(&a, &b)(pos));
+        }
+    }
+
+    #[test]
+    fn rect_bounds() {
+        let filter = rect(matrix_pos(2, 3), 2, 1);
+
+        assert!(filter(matrix_pos(2, 3)));
+        assert!(filter(matrix_pos(3, 3)));
+        assert!(!filter(matrix_pos(4, 3)));
+        assert!(!filter(matrix_pos(2, 4)));
+        assert!(!filter(matrix_pos(1, 3)));
+        assert!(!rect(matrix_pos(0, 0), 0, 5)(matrix_pos(0, 0)));
+    }
+}
@@ -0,0 +1,341 @@
+//! # The _Growing Tree_ algorithm
+//!
+//! This algorithm maintains a set of active rooms. In each step one room is
+//! picked from this set according to a [policy](Policy), and a random
+//! unvisited neighbour of that room is opened and added to the set. Rooms
+//! without unvisited neighbours are removed from the set.
+//!
+//! Depending on the policy, the resulting maze ranges from long winding
+//! corridors, as yielded by [`Winding`](super::Method::Winding), to short
+//! branching ones.
+
+use std::hash;
+use std::str;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::Maze;
+
+use crate::matrix;
+
+/// The policy used to select the next room from the active set.
+///
+/// Mixed policies are compared by the bit patterns of their probabilities,
+/// so `MixNewestRandom(0.0)` and `MixNewestRandom(-0.0)` differ, while a
+/// `NaN` probability equals itself. This keeps equality consistent with
+/// hashing.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Policy {
+    /// Always selects the most recently added room.
+    ///
+    /// This yields the same maze as [`Winding`](super::Method::Winding) for
+    /// the same random number generator.
+    Newest,
+
+    /// Selects a random room.
+    ///
+    /// This yields mazes similar to those generated by
+    /// [`Branching`](super::Method::Branching).
+    Random,
+
+    /// Always selects the least recently added room.
+    Oldest,
+
+    /// Selects the most recently added room with the given probability, and
+    /// a random room otherwise.
+    MixNewestRandom(f32),
+}
+
+impl Policy {
+    /// Selects the index of the next room from an active set.
+    ///
+    /// # Arguments
+    /// *  `rng` - A random number generator.
+    /// *  `count` - The number of active rooms. This must not be `0`.
+    fn select<R>(self, rng: &mut R, count: usize) -> usize
+    where
+        R: super::Randomizer + Sized,
+    {
+        match self {
+            Policy::Newest => count - 1,
+            Policy::Random => rng.range(0, count),
+            Policy::Oldest => 0,
+            Policy::MixNewestRandom(newest) => {
+                if rng.random() < newest as f64 {
+                    count - 1
+                } else {
+                    rng.range(0, count)
+                }
+            }
+        }
+    }
+}
+
+impl PartialEq for Policy {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Policy::MixNewestRandom(a), Policy::MixNewestRandom(b)) => {
+                a.to_bits() == b.to_bits()
+            }
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
+}
+
+impl Eq for Policy {}
+
+impl hash::Hash for Policy {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        if let Policy::MixNewestRandom(newest) = self {
+            newest.to_bits().hash(state);
+        }
+    }
+}
+
+impl std::fmt::Display for Policy {
+    /// The opposite of [std::str::FromStr].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use maze::initialize::growing_tree::*;
+    ///
+    /// assert_eq!(
+    ///     Policy::Newest.to_string().parse::<Policy>(),
+    ///     Ok(Policy::Newest),
+    /// );
+    /// assert_eq!(
+    ///     Policy::MixNewestRandom(0.25).to_string().parse::<Policy>(),
+    ///     Ok(Policy::MixNewestRandom(0.25)),
+    /// );
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use Policy::*;
+        match self {
+            Newest => write!(f, "newest"),
+            Random => write!(f, "random"),
+            Oldest => write!(f, "oldest"),
+            MixNewestRandom(newest) => write!(f, "mix:{}", newest),
+        }
+    }
+}
+
+impl str::FromStr for Policy {
+    type Err = String;
+
+    /// Converts a string to a policy.
+    ///
+    /// The source strings are `"newest"`, `"random"`, `"oldest"` and
+    /// `"mix:<probability>"`, where _probability_ is the probability of
+    /// selecting the newest room, in the range `[0, 1]`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use maze::initialize::growing_tree::*;
+    ///
+    /// assert_eq!(
+    ///     "oldest".parse::<Policy>(),
+    ///     Ok(Policy::Oldest),
+    /// );
+    /// assert_eq!(
+    ///     "mix:0.5".parse::<Policy>(),
+    ///     Ok(Policy::MixNewestRandom(0.5)),
+    /// );
+    /// assert!("mix:2".parse::<Policy>().is_err());
+    /// ```
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        match source {
+            "newest" => Ok(Policy::Newest),
+            "random" => Ok(Policy::Random),
+            "oldest" => Ok(Policy::Oldest),
+            e => e
+                .strip_prefix("mix:")
+                .and_then(|newest| newest.parse::<f32>().ok())
+                .filter(|newest| (0.0..=1.0).contains(newest))
+                .map(Policy::MixNewestRandom)
+                .ok_or_else(|| e.to_owned()),
+        }
+    }
+}
+
+/// Initialises a maze using the _Growing Tree_ algorithm.
+///
+/// The maze should be fully closed; any already open walls will be ignored and
+/// kept.
+///
+/// This method will ignore rooms for which `filter` returns `false`.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `filter` - A filter function used to ignore rooms.
+/// *  `policy` - The policy used to select rooms from the active set.
+pub fn initialize<F, R, T>(
+    maze: Maze<T>,
+    rng: &mut R,
+    filter: F,
+    policy: Policy,
+) -> Maze<T>
+where
+    F: Fn(matrix::Pos) -> bool,
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    match matrix::filter(maze.width(), maze.height(), filter) {
+        (count, candidates) if count > 0 => {
+            initialize_candidates(maze, rng, candidates, policy)
+        }
+        _ => maze,
+    }
+}
+
+/// Initialises a maze using the _Growing Tree_ algorithm.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `candidates` - A filter for the rooms to modify.
+/// *  `policy` - The policy used to select rooms from the active set.
+pub(crate) fn initialize_candidates<R, T>(
+    mut maze: Maze<T>,
+    rng: &mut R,
+    mut candidates: matrix::Matrix<bool>,
+    policy: Policy,
+) -> Maze<T>
+where
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    let mut active = Vec::new();
+
+    loop {
+        // Since the maze may be segmented, we must attempt to find a new
+        // random room once the active set is exhausted
+        if active.is_empty() {
+            match super::random_room(rng, &candidates) {
+                Some(pos) => {
+                    candidates[pos] = false;
+                    active.push(pos);
+                }
+                None => break,
+            }
+        }
+
+        let index = policy.select(rng, active.len());
+        let current = active[index];
+
+        // Find all non-visited neighbours as the tuple (neighbour-position,
+        // wall-from-current)
+        let neighbors = maze
+            .walls(current)
+            .iter()
+            .map(|wall| maze.back((current, wall)))
+            .filter(|&(pos, _)| *candidates.get(pos).unwrap_or(&false))
+            .map(|(pos, wall)| (pos, maze.back((pos, wall)).1))
+            .collect::<Vec<_>>();
+
+        if neighbors.is_empty() {
+            active.remove(index);
+        } else {
+            let (next, wall) = neighbors[rng.range(0, neighbors.len())];
+            maze.open((current, wall));
+            candidates[next] = false;
+            active.push(next);
+        }
+    }
+
+    maze
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::export;
+    use crate::initialize::{Method, LFSR};
+    use crate::test_utils::*;
+
+    /// The various policies tested.
+    const POLICIES: &[Policy] = &[
+        Policy::Newest,
+        Policy::Random,
+        Policy::Oldest,
+        Policy::MixNewestRandom(0.5),
+    ];
+
+    #[maze_test]
+    fn newest_is_winding(maze: TestMaze) {
+        for seed in 0..10 {
+            let expected = maze
+                .clone()
+                .initialize(Method::Winding, &mut LFSR::new(seed));
+            let actual = initialize(
+                maze.clone(),
+                &mut LFSR::new(seed),
+                |_| true,
+                Policy::Newest,
+            );
+
+            assert_eq!(
+                export::to_bitmask_matrix(&expected),
+                export::to_bitmask_matrix(&actual),
+                "for seed {}",
+                seed,
+            );
+        }
+    }
+
+    #[maze_test]
+    fn initialize_perfect(maze: TestMaze) {
+        for &policy in POLICIES {
+            let maze = initialize(
+                maze.clone(),
+                &mut rand::thread_rng(),
+                |_| true,
+                policy,
+            );
+
+            // A spanning tree has exactly one fewer passage than rooms
+            let rooms = maze.positions().count();
+            let passages = maze
+                .positions()
+                .flat_map(|pos| maze.doors(pos).map(move |wall| (pos, wall)))
+                .count()
+                / 2;
+            assert_eq!(rooms - 1, passages, "for policy {:?}", policy);
+            assert!(maze.positions().all(|pos| maze[pos].visited));
+        }
+    }
+
+    #[test]
+    fn eq_consistent_with_hash() {
+        let hash = |policy: Policy| {
+            let mut hasher = std::collections::hash_map::DefaultHasher::new();
+            hash::Hash::hash(&policy, &mut hasher);
+            hash::Hasher::finish(&hasher)
+        };
+
+        let negative_zero = "mix:-0".parse::<Policy>().unwrap();
+        assert_ne!(Policy::MixNewestRandom(0.0), negative_zero);
+        assert_eq!(hash(Policy::MixNewestRandom(-0.0)), hash(negative_zero));
+
+        let nan = Policy::MixNewestRandom(f32::NAN);
+        assert_eq!(nan, nan);
+        assert_eq!(hash(nan), hash(nan));
+        assert_ne!(Policy::Newest, Policy::Oldest);
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!("".parse::<Policy>().is_err());
+        assert!("mix".parse::<Policy>().is_err());
+        assert!("mix:".parse::<Policy>().is_err());
+        assert!("mix:-0.5".parse::<Policy>().is_err());
+        assert!("mixed:0.5".parse::<Policy>().is_err());
+    }
+}
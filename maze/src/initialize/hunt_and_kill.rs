@@ -0,0 +1,195 @@
+//! # The _Hunt-and-Kill_ algorithm
+//!
+//! This algorithm performs a random walk, opening walls to unvisited rooms,
+//! until it reaches a room without unvisited neighbours. It then _hunts_ for
+//! an unvisited room adjacent to a visited one by scanning the maze row by
+//! row, connects that room to the visited area and resumes the walk from it.
+//!
+//! Like [`Winding`](super::Method::Winding), this yields mazes with long
+//! winding corridors, but no backtracking path is maintained, so the memory
+//! used does not grow with the length of the corridors.
+
+use crate::Maze;
+
+use crate::matrix;
+
+/// Initialises a maze using the _Hunt-and-Kill_ algorithm.
+///
+/// The maze should be fully closed; any already open walls will be ignored and
+/// kept.
+///
+/// This method will ignore rooms for which `filter` returns `false`.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `filter` - A filter function used to ignore rooms.
+pub fn initialize<F, R, T>(maze: Maze<T>, rng: &mut R, filter: F) -> Maze<T>
+where
+    F: Fn(matrix::Pos) -> bool,
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    match matrix::filter(maze.width(), maze.height(), filter) {
+        (count, candidates) if count > 0 => {
+            initialize_candidates(maze, rng, candidates)
+        }
+        _ => maze,
+    }
+}
+
+/// Initialises a maze using the _Hunt-and-Kill_ algorithm.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `candidates` - A filter for the rooms to modify.
+pub(crate) fn initialize_candidates<R, T>(
+    mut maze: Maze<T>,
+    rng: &mut R,
+    mut candidates: matrix::Matrix<bool>,
+) -> Maze<T>
+where
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    // The rooms that are part of the maze; unlike candidates, this is never
+    // modified
+    let filter = candidates.clone();
+
+    // All rows before this one are known to contain no candidates
+    let mut first_row = 0;
+
+    // Start in a random room; we know that at least one candidate exists
+    let mut current = super::random_room(rng, &candidates);
+
+    while let Some(mut pos) = current {
+        candidates[pos] = false;
+
+        // Walk until no unvisited neighbours remain
+        loop {
+            let neighbors = maze
+                .walls(pos)
+                .iter()
+                .map(|wall| maze.back((pos, wall)))
+                .filter(|&(next, _)| *candidates.get(next).unwrap_or(&false))
+                .map(|(next, wall)| (next, maze.back((next, wall)).1))
+                .collect::<Vec<_>>();
+            if neighbors.is_empty() {
+                break;
+            }
+
+            let (next, wall) = neighbors[rng.range(0, neighbors.len())];
+            maze.open((pos, wall));
+            candidates[next] = false;
+            pos = next;
+        }
+
+        // Hunt for an unvisited room next to a visited one; since the maze may
+        // be segmented, we must fall back on a new random room
+        while first_row < maze.height()
+            && (0..maze.width()).all(|col| {
+                !candidates[matrix::Pos {
+                    col: col as isize,
+                    row: first_row as isize,
+                }]
+            })
+        {
+            first_row += 1;
+        }
+        current = hunt(&maze, &candidates, &filter, first_row)
+            .map(|(next, wall)| {
+                maze.open((next, wall));
+                next
+            })
+            .or_else(|| super::random_room(rng, &candidates));
+    }
+
+    maze
+}
+
+/// Finds the first unvisited room in row-major order that is adjacent to a
+/// visited room.
+///
+/// The return value is the unvisited room and the wall leading to the first
+/// visited neighbour.
+///
+/// # Arguments
+/// *  `maze` - The maze being initialised.
+/// *  `candidates` - The rooms that have not yet been visited.
+/// *  `filter` - The rooms that are part of the maze.
+/// *  `first_row` - The first row to scan.
+fn hunt<T>(
+    maze: &Maze<T>,
+    candidates: &matrix::Matrix<bool>,
+    filter: &matrix::Matrix<bool>,
+    first_row: usize,
+) -> Option<crate::WallPos>
+where
+    T: Clone,
+{
+    (first_row..maze.height())
+        .flat_map(|row| {
+            (0..maze.width()).map(move |col| matrix::Pos {
+                col: col as isize,
+                row: row as isize,
+            })
+        })
+        .filter(|&pos| candidates[pos])
+        .find_map(|pos| {
+            maze.walls(pos)
+                .iter()
+                .find(|&wall| {
+                    let (next, _) = maze.back((pos, wall));
+                    *filter.get(next).unwrap_or(&false) && !candidates[next]
+                })
+                .map(|&wall| (pos, wall))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::{Method, LFSR};
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn initialize_perfect(maze: TestMaze) {
+        let maze = initialize(maze, &mut rand::thread_rng(), |_| true);
+
+        let rooms = maze.positions().count();
+        let passages = maze
+            .positions()
+            .flat_map(|pos| maze.doors(pos).map(move |wall| (pos, wall)))
+            .count()
+            / 2;
+        assert_eq!(rooms - 1, passages);
+        assert!(maze.positions().all(|pos| maze[pos].visited));
+        let start = matrix_pos(0, 0);
+        assert!(maze.positions().all(|pos| maze.walk(start, pos).is_some()));
+    }
+
+    #[maze_test]
+    fn initialize_method(maze: TestMaze) {
+        let expected =
+            initialize(maze.clone(), &mut LFSR::new(12345), |_| true);
+        let actual =
+            maze.initialize(Method::HuntAndKill, &mut LFSR::new(12345));
+
+        assert_eq!(
+            crate::export::to_bitmask_matrix(&expected),
+            crate::export::to_bitmask_matrix(&actual),
+        );
+    }
+
+    #[test]
+    fn initialize_large() {
+        let maze = crate::Shape::Quad
+            .create::<()>(300, 300)
+            .initialize(Method::HuntAndKill, &mut LFSR::new(12345));
+
+        assert!(maze.positions().all(|pos| maze[pos].visited));
+    }
+}
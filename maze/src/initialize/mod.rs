@@ -5,24 +5,44 @@
 
 use std::iter;
 use std::str;
-use std::u64;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 use crate::Maze;
+use crate::WallPos;
 
+use crate::export;
 use crate::matrix;
+use crate::shape::quad;
+use crate::wall;
 
+pub mod aldous_broder;
+pub mod binary_tree;
 mod braid;
 mod branching;
 mod clear;
+pub mod ellers;
+pub mod filters;
+pub mod growing_tree;
+pub mod hunt_and_kill;
+pub mod sidewinder;
+pub mod wilson;
 mod winding;
 
 /// The various supported initialisation method.
 #[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
 pub enum Method {
+    /// Initialises a maze using the _Aldous-Broder_ algorithm.
+    ///
+    /// This method generates every possible maze with the same probability,
+    /// but is slow for large mazes. A maze initialised with this method will
+    /// not contain loops.
+    ///
+    /// See [`aldous_broder`] for a description of the algorithm.
+    AldousBroder,
+
     /// Initialises a maze with no dead ends.
     ///
     /// A dead end is a room with only one open wall.
@@ -46,12 +66,44 @@ pub enum Method {
     /// [Wikipedia]: https://en.wikipedia.org/wiki/Maze_generation_algorithm#Randomized_Prim's_algorithm
     Branching,
 
+    /// Initialises a maze using the _Growing Tree_ algorithm.
+    ///
+    /// This method generalises [`Branching`](Method::Branching) and
+    /// [`Winding`](Method::Winding); the policy determines which room from
+    /// the set of active rooms is extended in each step. A maze initialised
+    /// with this method will not contain loops.
+    ///
+    /// See [`growing_tree`] for a description of the algorithm.
+    GrowingTree(growing_tree::Policy),
+
+    /// Initialises a maze using the _Hunt-and-Kill_ algorithm.
+    ///
+    /// This method yields mazes with long winding corridors, similar to
+    /// [`Winding`](Method::Winding). A maze initialised with this method will
+    /// not contain loops.
+    ///
+    /// See [`hunt_and_kill`] for a description of the algorithm.
+    HuntAndKill,
+
+    /// Initialises a maze using _Wilson's_ algorithm.
+    ///
+    /// This method generates every possible maze with the same probability,
+    /// like [`AldousBroder`](Method::AldousBroder), but considerably faster.
+    /// A maze initialised with this method will not contain loops.
+    ///
+    /// See [`wilson`] for a description of the algorithm.
+    Wilson,
+
     /// Initialises a maze using a winding algorithm.
     ///
     /// This method uses a simple _Depth First_ algorithm to generate a maze,
     /// which yields mazes with long winding corridors. A maze initialised with
     /// this method will not contain loops.
     ///
+    /// The next room is picked uniformly among the unvisited neighbours, so
+    /// the order of the walls of a shape does not bias the direction of the
+    /// corridors.
+    ///
     /// See [Wikipedia] for a description of the algorithm.
     ///
     /// [Wikipedia]: https://en.wikipedia.org/wiki/Maze_generation_algorithm#Depth-first_search
@@ -65,6 +117,60 @@ impl Default for Method {
     }
 }
 
+impl Method {
+    /// Initialises a maze using this method and reports the opened walls.
+    ///
+    /// This is equivalent to [`Maze::initialize_filter`], but the walls that
+    /// were closed before and are open after are returned as well. Every
+    /// passage is listed only once, from one of its sides, so opening all
+    /// returned walls in a copy of the original maze yields the initialised
+    /// maze. The walls are listed in row-major order of their rooms, not in
+    /// the order in which they were opened.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize::{Method, LFSR};
+    /// let maze = maze::Shape::Quad.create::<()>(5, 5);
+    /// let (initialized, walls) =
+    ///     Method::Winding.apply(maze.clone(), &mut LFSR::new(12345), |_| true);
+    ///
+    /// // A perfect maze has one passage less than it has rooms
+    /// assert_eq!(initialized.positions().count() - 1, walls.len());
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise.
+    /// *  `rng` - A random number generator.
+    /// *  `filter` - A filter function used to ignore rooms.
+    pub fn apply<F, R, T>(
+        self,
+        maze: Maze<T>,
+        rng: &mut R,
+        filter: F,
+    ) -> (Maze<T>, Vec<WallPos>)
+    where
+        F: Fn(matrix::Pos) -> bool,
+        R: Randomizer + Sized,
+        T: Clone,
+    {
+        let before = export::to_bitmask_matrix(&maze);
+        let maze = maze.initialize_filter(self, rng, filter);
+
+        let opened = maze
+            .positions()
+            .flat_map(|pos| maze.doors(pos).map(move |wall| (pos, wall)))
+            .filter(|&(pos, wall)| before[pos] & wall.mask() == 0)
+            .filter(|&(pos, wall)| {
+                let (next, back) = maze.back((pos, wall));
+                !maze.is_inside(next) || (pos, wall.index) <= (next, back.index)
+            })
+            .collect();
+
+        (maze, opened)
+    }
+}
+
 impl std::fmt::Display for Method {
     /// The opposite of [std::str::FromStr].
     ///
@@ -74,6 +180,10 @@ impl std::fmt::Display for Method {
     /// # use maze::initialize::*;
     ///
     /// assert_eq!(
+    ///     Method::AldousBroder.to_string().parse::<Method>(),
+    ///     Ok(Method::AldousBroder),
+    /// );
+    /// assert_eq!(
     ///     Method::Braid.to_string().parse::<Method>(),
     ///     Ok(Method::Braid),
     /// );
@@ -86,6 +196,20 @@ impl std::fmt::Display for Method {
     ///     Ok(Method::Clear),
     /// );
     /// assert_eq!(
+    ///     Method::GrowingTree(growing_tree::Policy::MixNewestRandom(0.5))
+    ///         .to_string()
+    ///         .parse::<Method>(),
+    ///     Ok(Method::GrowingTree(growing_tree::Policy::MixNewestRandom(0.5))),
+    /// );
+    /// assert_eq!(
+    ///     Method::HuntAndKill.to_string().parse::<Method>(),
+    ///     Ok(Method::HuntAndKill),
+    /// );
+    /// assert_eq!(
+    ///     Method::Wilson.to_string().parse::<Method>(),
+    ///     Ok(Method::Wilson),
+    /// );
+    /// assert_eq!(
     ///     Method::Winding.to_string().parse::<Method>(),
     ///     Ok(Method::Winding),
     /// );
@@ -93,21 +217,28 @@ impl std::fmt::Display for Method {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use Method::*;
         match self {
+            AldousBroder => write!(f, "aldous-broder"),
             Braid => write!(f, "braid"),
             Clear => write!(f, "clear"),
             Branching => write!(f, "branching"),
+            GrowingTree(policy) => write!(f, "growing:{}", policy),
+            HuntAndKill => write!(f, "hunt-and-kill"),
+            Wilson => write!(f, "wilson"),
             Winding => write!(f, "winding"),
         }
     }
 }
 
 impl str::FromStr for Method {
-    type Err = String;
+    type Err = crate::ParseError;
 
     /// Converts a string to an initialiser.
     ///
     /// The source strings are the lower case names of the initialisation
-    /// methods.
+    /// methods. The _Growing Tree_ method is named `"growing:<policy>"`,
+    /// where _policy_ is a [policy string](growing_tree::Policy), the
+    /// _Hunt-and-Kill_ method is named `"hunt-and-kill"` and the
+    /// _Aldous-Broder_ method is named `"aldous-broder"`.
     ///
     /// # Examples
     ///
@@ -115,6 +246,10 @@ impl str::FromStr for Method {
     /// # use maze::initialize::*;
     ///
     /// assert_eq!(
+    ///     "aldous-broder".parse::<Method>(),
+    ///     Ok(Method::AldousBroder),
+    /// );
+    /// assert_eq!(
     ///     "braid".parse::<Method>(),
     ///     Ok(Method::Braid),
     /// );
@@ -127,17 +262,82 @@ impl str::FromStr for Method {
     ///     Ok(Method::Clear),
     /// );
     /// assert_eq!(
+    ///     "growing:mix:0.5".parse::<Method>(),
+    ///     Ok(Method::GrowingTree(growing_tree::Policy::MixNewestRandom(0.5))),
+    /// );
+    /// assert_eq!(
+    ///     "hunt-and-kill".parse::<Method>(),
+    ///     Ok(Method::HuntAndKill),
+    /// );
+    /// assert_eq!(
+    ///     "wilson".parse::<Method>(),
+    ///     Ok(Method::Wilson),
+    /// );
+    /// assert_eq!(
     ///     "winding".parse::<Method>(),
     ///     Ok(Method::Winding),
     /// );
     /// ```
     fn from_str(source: &str) -> Result<Self, Self::Err> {
         match source {
+            "aldous-broder" => Ok(Method::AldousBroder),
             "braid" => Ok(Method::Braid),
             "clear" => Ok(Method::Clear),
             "branching" => Ok(Method::Branching),
+            "hunt-and-kill" => Ok(Method::HuntAndKill),
+            "wilson" => Ok(Method::Wilson),
             "winding" => Ok(Method::Winding),
-            e => Err(e.to_owned()),
+            e => e
+                .strip_prefix("growing:")
+                .and_then(|policy| policy.parse().ok())
+                .map(Method::GrowingTree)
+                .ok_or_else(|| crate::ParseError::UnknownMethod(e.to_owned())),
+        }
+    }
+}
+
+/// The corner of a quad maze towards which the passages of a biased
+/// initialiser lead.
+///
+/// See [`binary_tree`] and [`sidewinder`].
+#[derive(Copy, Clone, Debug, Eq, Hash, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Deserialize, Serialize))]
+pub enum Bias {
+    /// Towards the top right corner.
+    NorthEast,
+
+    /// Towards the top left corner.
+    NorthWest,
+
+    /// Towards the bottom right corner.
+    SouthEast,
+
+    /// Towards the bottom left corner.
+    SouthWest,
+}
+
+impl Bias {
+    /// All biases.
+    pub const ALL: [Self; 4] = [
+        Bias::NorthEast,
+        Bias::NorthWest,
+        Bias::SouthEast,
+        Bias::SouthWest,
+    ];
+
+    /// The quad wall in the horizontal direction of this bias.
+    pub fn horizontal(self) -> &'static wall::Wall {
+        match self {
+            Bias::NorthEast | Bias::SouthEast => &quad::walls::RIGHT,
+            Bias::NorthWest | Bias::SouthWest => &quad::walls::LEFT,
+        }
+    }
+
+    /// The quad wall in the vertical direction of this bias.
+    pub fn vertical(self) -> &'static wall::Wall {
+        match self {
+            Bias::NorthEast | Bias::NorthWest => &quad::walls::UP,
+            Bias::SouthEast | Bias::SouthWest => &quad::walls::DOWN,
         }
     }
 }
@@ -146,6 +346,10 @@ pub trait Randomizer {
     /// Generates a random value in the range `[low, high)`, where `low` and
     /// `high` are the low and high values of `a` and `b`.
     ///
+    /// If `a == b` the range is empty, and `a` is returned without panicking.
+    /// Callers picking an element from a collection must therefore check that
+    /// it is not empty first, since `range(0, 0)` returns `0`.
+    ///
     /// # Arguments
     /// *  `a` - A number.
     /// *  `b` - A number.
@@ -161,10 +365,10 @@ where
     T: rand::Rng,
 {
     fn range(&mut self, a: usize, b: usize) -> usize {
-        if a < b {
-            self.gen_range(a..b)
-        } else {
-            self.gen_range(b..a)
+        match a.cmp(&b) {
+            std::cmp::Ordering::Less => self.gen_range(a..b),
+            std::cmp::Ordering::Equal => a,
+            std::cmp::Ordering::Greater => self.gen_range(b..a),
         }
     }
 
@@ -283,15 +487,71 @@ where
         F: Fn(matrix::Pos) -> bool,
         R: Randomizer + Sized,
     {
-        match matrix::filter(self.width(), self.height(), filter) {
+        self.initialize_filter_progress(method, rng, filter, None)
+    }
+
+    /// Initialises a maze using the selected algorithm while reporting
+    /// progress.
+    ///
+    /// This is equivalent to [`Maze::initialize_filter`], but `progress` is
+    /// called with the fraction of completion, in the range _[0, 1]_. The
+    /// reported values never decrease, and the last one is always `1.0`.
+    ///
+    /// [`Winding`](Method::Winding) reports the fraction of visited rooms
+    /// periodically; the other methods only report completion.
+    ///
+    /// # Arguments
+    /// *  `method` - The initialisation method to use.
+    /// *  `rng` - A random number generator.
+    /// *  `filter` - A filter function used to ignore rooms.
+    /// *  `progress` - A function receiving the progress, if any.
+    pub fn initialize_filter_progress<R, F>(
+        self,
+        method: Method,
+        rng: &mut R,
+        filter: F,
+        mut progress: Option<&mut dyn FnMut(f32)>,
+    ) -> Self
+    where
+        F: Fn(matrix::Pos) -> bool,
+        R: Randomizer + Sized,
+    {
+        let maze = match matrix::filter(self.width(), self.height(), filter) {
             (count, filter) if count > 0 => match method {
+                Method::AldousBroder => {
+                    aldous_broder::initialize_candidates(self, rng, filter)
+                }
                 Method::Braid => braid::initialize(self, rng, filter),
                 Method::Clear => clear::initialize(self, rng, filter),
                 Method::Branching => branching::initialize(self, rng, filter),
-                Method::Winding => winding::initialize(self, rng, filter),
+                Method::GrowingTree(policy) => {
+                    growing_tree::initialize_candidates(
+                        self, rng, filter, policy,
+                    )
+                }
+                Method::HuntAndKill => {
+                    hunt_and_kill::initialize_candidates(self, rng, filter)
+                }
+                Method::Wilson => {
+                    wilson::initialize_candidates(self, rng, filter)
+                }
+                Method::Winding => winding::initialize(
+                    self,
+                    rng,
+                    filter,
+                    progress
+                        .as_mut()
+                        .map(|progress| &mut **progress as &mut dyn FnMut(f32)),
+                ),
             },
             _ => self,
+        };
+
+        if let Some(progress) = progress {
+            progress(1.0);
         }
+
+        maze
     }
 }
 
@@ -318,6 +578,42 @@ fn random_room(
     }
 }
 
+/// Finds all rooms in the same segment as a room.
+///
+/// A segment is a set of rooms in `filter_matrix` that are adjacent to each
+/// other, regardless of whether the walls between them are open. The rooms are
+/// returned in the order they are found, starting with `pos`.
+///
+/// # Arguments
+/// *  `maze` - The maze.
+/// *  `filter_matrix` - A matrix containing the rooms to consider.
+/// *  `pos` - A room in the segment.
+fn segment<T>(
+    maze: &Maze<T>,
+    filter_matrix: &matrix::Matrix<bool>,
+    pos: matrix::Pos,
+) -> Vec<matrix::Pos>
+where
+    T: Clone,
+{
+    let mut found = matrix::Matrix::<bool>::new(maze.width(), maze.height());
+    found[pos] = true;
+    let mut result = vec![pos];
+    let mut index = 0;
+    while let Some(&current) = result.get(index) {
+        for wall in maze.walls(current) {
+            let (next, _) = maze.back((current, wall));
+            if *filter_matrix.get(next).unwrap_or(&false) && !found[next] {
+                found[next] = true;
+                result.push(next);
+            }
+        }
+        index += 1;
+    }
+
+    result
+}
+
 /// Ensures all rooms are connected
 ///
 /// This function will find all closed areas and ensure they have one exit to
@@ -331,6 +627,32 @@ where
     F: Fn(matrix::Pos) -> bool,
     R: Randomizer + Sized,
     T: Clone,
+{
+    connect_all_with(maze, rng, filter, 1);
+}
+
+/// Ensures all rooms are connected, using several connectors between areas.
+///
+/// This function will find all closed areas and open `connectors` random
+/// walls along the boundary between each pair of neighbouring areas. If a
+/// boundary has fewer walls, all of them are opened. More connectors yield
+/// looser mazes with several routes between areas.
+///
+/// Passing `1` for `connectors` is equivalent to calling [`connect_all`].
+///
+/// # Arguments
+/// *  `maze` - The maze to modify.
+/// *  `filter` - A filter for rooms to consider.
+/// *  `connectors` - The number of walls to open between neighbouring areas.
+pub fn connect_all_with<F, R, T>(
+    maze: &mut Maze<T>,
+    rng: &mut R,
+    filter: F,
+    connectors: usize,
+) where
+    F: Fn(matrix::Pos) -> bool,
+    R: Randomizer + Sized,
+    T: Clone,
 {
     // First find all non-connected areas by visiting all rooms and filling for
     // each filtered, non-filled room and then incrementing the area index
@@ -348,17 +670,22 @@ where
         }
     }
 
-    // Then find all edges between separate areas and open a random wall
+    // Then find all edges between separate areas and open random walls; the
+    // walls are selected by a partial shuffle to avoid duplicates
     for (_, edge) in areas
         .edges(|pos| maze.adjacent(pos))
         .iter()
         .filter(|&((source, _), _)| *source > 0)
     {
-        let wall_positions = edge
+        let mut wall_positions = edge
             .iter()
             .flat_map(|&(pos1, pos2)| maze.connecting_wall(pos1, pos2))
             .collect::<Vec<_>>();
-        maze.open(wall_positions[rng.range(0, wall_positions.len())])
+        for i in 0..connectors.min(wall_positions.len()) {
+            let j = rng.range(i, wall_positions.len());
+            wall_positions.swap(i, j);
+            maze.open(wall_positions[i]);
+        }
     }
 }
 
@@ -370,8 +697,122 @@ mod tests {
     use crate::test_utils::*;
 
     /// The various initialisation methods tested.
-    const INITIALIZERS: &[Method] =
-        &[Method::Braid, Method::Branching, Method::Winding];
+    const INITIALIZERS: &[Method] = &[
+        Method::AldousBroder,
+        Method::Braid,
+        Method::Branching,
+        Method::GrowingTree(growing_tree::Policy::MixNewestRandom(0.5)),
+        Method::HuntAndKill,
+        Method::Wilson,
+        Method::Winding,
+    ];
+
+    #[maze_test(quad)]
+    fn connect_all_with_limited(maze: TestMaze) {
+        let mut maze = maze;
+        let (left, right) = (matrix_pos(0, 0), matrix_pos(1, 0));
+        connect_all_with(
+            &mut maze,
+            &mut LFSR::new(12345),
+            |pos| pos == left || pos == right,
+            3,
+        );
+
+        assert!(maze.connected(left, right));
+        assert_eq!(1, maze[left].open_walls());
+        assert_eq!(1, maze[right].open_walls());
+    }
+
+    /// Calculates the distribution of the degree of the centre room of a 3x3
+    /// quad maze over a number of mazes.
+    ///
+    /// # Arguments
+    /// *  `mazes` - The mazes.
+    fn centre_degrees<I>(mazes: I) -> [f64; 5]
+    where
+        I: Iterator<Item = TestMaze>,
+    {
+        let mut result = [0.0; 5];
+        let mut count = 0;
+        for maze in mazes {
+            result[maze[matrix_pos(1, 1)].open_walls()] += 1.0;
+            count += 1;
+        }
+        result.map(|v| v / count as f64)
+    }
+
+    /// Calculates the exact distribution of the degree of the centre room of a
+    /// 3x3 quad maze over all its spanning trees.
+    fn spanning_tree_centre_degrees() -> [f64; 5] {
+        let maze = crate::Shape::Quad.create::<()>(3, 3);
+        let edges = maze
+            .positions()
+            .flat_map(|pos| {
+                maze.walls(pos).iter().map(move |&wall| (pos, wall))
+            })
+            .filter(|&(pos, wall)| {
+                let (next, _) = maze.back((pos, wall));
+                maze.is_inside(next) && pos < next
+            })
+            .collect::<Vec<_>>();
+
+        // Every subset of 8 edges leaving all rooms connected is a tree
+        let trees = (0u32..1 << edges.len())
+            .filter(|subset| subset.count_ones() == 8)
+            .map(|subset| {
+                let mut maze = maze.clone();
+                for (i, &wall_pos) in edges.iter().enumerate() {
+                    maze.set_open(wall_pos, subset & (1 << i) != 0);
+                }
+                maze
+            })
+            .filter(|maze| {
+                maze.positions()
+                    .all(|pos| maze.walk(matrix_pos(0, 0), pos).is_some())
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(192, trees.len());
+
+        centre_degrees(trees.into_iter())
+    }
+
+    #[test]
+    fn uniform_spanning_trees() {
+        let expected = spanning_tree_centre_degrees();
+        let distance =
+            |initializer: &dyn Fn(TestMaze, &mut LFSR) -> TestMaze| {
+                let mut rng = LFSR::new(12345);
+                let actual = centre_degrees((0..2000).map(|_| {
+                    initializer(crate::Shape::Quad.create(3, 3), &mut rng)
+                }));
+                expected
+                    .iter()
+                    .zip(actual.iter())
+                    .map(|(e, a)| (e - a).abs())
+                    .sum::<f64>()
+                    / 2.0
+            };
+
+        let winding =
+            distance(&|maze, rng| maze.initialize(Method::Winding, rng));
+        let wilson =
+            distance(&|maze, rng| maze.initialize(Method::Wilson, rng));
+        let aldous_broder =
+            distance(&|maze, rng| maze.initialize(Method::AldousBroder, rng));
+        assert!(wilson < 0.1, "{} for Wilson", wilson);
+        assert!(aldous_broder < 0.1, "{} for Aldous-Broder", aldous_broder);
+        assert!(wilson < winding && aldous_broder < winding);
+    }
+
+    #[test]
+    fn method_from_str_invalid() {
+        for s in ["invalid", "growing:", "growing:mix:2"] {
+            assert_eq!(
+                Err(crate::ParseError::UnknownMethod(s.to_owned())),
+                s.parse::<Method>(),
+            );
+        }
+    }
 
     /// Tests that range works as advertised.
     #[test]
@@ -394,6 +835,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn range_empty() {
+        assert_eq!(3, LFSR::new(12345).range(3, 3));
+        assert_eq!(0, LFSR::new(12345).range(0, 0));
+        assert_eq!(3, rand::thread_rng().range(3, 3));
+        assert_eq!(0, rand::thread_rng().range(0, 0));
+    }
+
     /// Tests that random gives a rectangular distribution.
     #[test]
     fn lfsr_random() {
@@ -468,6 +917,26 @@ mod tests {
         }
     }
 
+    #[maze_test]
+    fn apply_replay(maze: TestMaze) {
+        for method in INITIALIZERS {
+            let (initialized, walls) =
+                method.apply(maze.clone(), &mut LFSR::new(12345), |_| true);
+
+            let mut replayed = maze.clone();
+            for &wall_pos in &walls {
+                assert!(!replayed.is_open(wall_pos), "for method {:?}", method);
+                replayed.open(wall_pos);
+            }
+            assert_eq!(
+                crate::export::to_bitmask_matrix(&initialized),
+                crate::export::to_bitmask_matrix(&replayed),
+                "for method {:?}",
+                method,
+            );
+        }
+    }
+
     #[maze_test]
     fn initialize_lfsr_stable(maze: TestMaze) {
         for method in INITIALIZERS {
@@ -529,7 +998,7 @@ mod tests {
                 let maze = maze.clone().initialize_filter(
                     *method,
                     &mut rand::thread_rng(),
-                    &filter,
+                    filter,
                 );
 
                 for pos in maze.positions() {
@@ -551,7 +1020,7 @@ mod tests {
                 let maze = maze.clone().initialize_filter(
                     *method,
                     &mut rand::thread_rng(),
-                    &filter,
+                    filter,
                 );
 
                 for pos in maze.positions() {
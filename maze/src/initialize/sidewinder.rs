@@ -0,0 +1,124 @@
+//! # The _Sidewinder_ algorithm
+//!
+//! This algorithm processes the maze row by row. The row on the vertical side
+//! of the [bias](Bias) is a single open corridor. In every other row, runs of
+//! rooms are opened in the horizontal direction of the bias, and every run is
+//! connected to the previous row through one random room of the run.
+//!
+//! The algorithm is only supported for [quad](crate::Shape::Quad) mazes.
+
+use crate::matrix;
+use crate::Maze;
+use crate::Shape;
+
+use super::Bias;
+
+/// Initialises a maze using the _Sidewinder_ algorithm.
+///
+/// The maze should be fully closed; any already open walls will be ignored and
+/// kept.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `bias` - The run direction and the side of the open corridor. Runs are
+///    opened in the horizontal direction, and connected in the vertical
+///    direction.
+///
+/// # Errors
+/// If the maze is not a quad maze, an error is returned.
+pub fn initialize<R, T>(
+    mut maze: Maze<T>,
+    rng: &mut R,
+    bias: Bias,
+) -> Result<Maze<T>, String>
+where
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    if maze.shape() != Shape::Quad {
+        return Err(format!("unsupported shape: {:?}", maze.shape()));
+    }
+
+    let (horizontal, vertical) = (bias.horizontal(), bias.vertical());
+    let (width, height) = (maze.width() as isize, maze.height() as isize);
+
+    // Iterate over the rooms in the run direction
+    let cols = (0..width)
+        .map(|col| {
+            if horizontal.dir.0 > 0 {
+                col
+            } else {
+                width - 1 - col
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let mut run = Vec::new();
+    for row in 0..height {
+        for &col in cols.iter() {
+            let pos = matrix::Pos { col, row };
+            run.push(pos);
+
+            let at_end = !maze.is_inside(maze.back((pos, horizontal)).0);
+            let at_side = !maze.is_inside(maze.back((pos, vertical)).0);
+            if at_side {
+                // The row on the side of the bias is a single corridor
+                if !at_end {
+                    maze.open((pos, horizontal));
+                }
+            } else if at_end || rng.range(0, 2) == 0 {
+                // Close the run by connecting a random room of it
+                let index = rng.range(0, run.len());
+                maze.open((run[index], vertical));
+                run.clear();
+                continue;
+            } else {
+                maze.open((pos, horizontal));
+            }
+
+            if at_end {
+                run.clear();
+            }
+        }
+    }
+
+    Ok(maze)
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::initialize::{Bias, LFSR};
+    use crate::shape::quad::walls;
+    use crate::test_utils::*;
+
+    #[maze_test(quad)]
+    fn initialize_open_side(maze: TestMaze) {
+        let (last_col, last_row) =
+            (maze.width() as isize - 1, maze.height() as isize - 1);
+
+        let maze_ne =
+            initialize(maze.clone(), &mut LFSR::new(12345), Bias::NorthEast)
+                .unwrap();
+        for col in 0..last_col {
+            assert!(maze_ne.is_open((matrix_pos(col, 0), &walls::RIGHT)));
+        }
+
+        let maze_sw =
+            initialize(maze.clone(), &mut LFSR::new(12345), Bias::SouthWest)
+                .unwrap();
+        for col in 1..=last_col {
+            assert!(maze_sw.is_open((matrix_pos(col, last_row), &walls::LEFT)));
+        }
+    }
+
+    #[maze_test(hex, tri)]
+    fn initialize_unsupported(maze: TestMaze) {
+        assert!(
+            initialize(maze, &mut LFSR::new(12345), Bias::NorthEast).is_err()
+        );
+    }
+}
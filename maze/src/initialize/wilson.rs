@@ -0,0 +1,98 @@
+//! # _Wilson's_ algorithm
+//!
+//! This algorithm grows a tree from a single room. For every room not yet part
+//! of the tree, a random walk is performed until it hits the tree, and the
+//! walk with all loops erased is added to the tree. Every spanning tree of the
+//! maze is generated with the same probability.
+//!
+//! This is slower than the biased initialisers, since the first walks may be
+//! long, but usually much faster than [`aldous_broder`](super::aldous_broder).
+
+use crate::Maze;
+
+use crate::matrix;
+use crate::wall;
+
+/// Initialises a maze using _Wilson's_ algorithm.
+///
+/// The maze should be fully closed; any already open walls will be ignored and
+/// kept.
+///
+/// This method will ignore rooms for which `filter` returns `false`.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `filter` - A filter function used to ignore rooms.
+pub fn initialize<F, R, T>(maze: Maze<T>, rng: &mut R, filter: F) -> Maze<T>
+where
+    F: Fn(matrix::Pos) -> bool,
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    match matrix::filter(maze.width(), maze.height(), filter) {
+        (count, candidates) if count > 0 => {
+            initialize_candidates(maze, rng, candidates)
+        }
+        _ => maze,
+    }
+}
+
+/// Initialises a maze using _Wilson's_ algorithm.
+///
+/// # Arguments
+/// *  `maze` - The maze to initialise.
+/// *  `rng` - A random number generator.
+/// *  `candidates` - A filter for the rooms to modify.
+pub(crate) fn initialize_candidates<R, T>(
+    mut maze: Maze<T>,
+    rng: &mut R,
+    mut candidates: matrix::Matrix<bool>,
+) -> Maze<T>
+where
+    R: super::Randomizer + Sized,
+    T: Clone,
+{
+    // The rooms that are part of the maze; unlike candidates, this is never
+    // modified
+    let filter = candidates.clone();
+
+    // The wall through which a walk last left each room; following these from
+    // the start of a walk yields the walk with all loops erased
+    let mut exits: matrix::Matrix<Option<&'static wall::Wall>> =
+        matrix::Matrix::new(maze.width(), maze.height());
+
+    // Since the maze may be segmented, we grow a tree in each segment
+    while let Some(root) = super::random_room(rng, &candidates) {
+        candidates[root] = false;
+
+        for start in super::segment(&maze, &filter, root) {
+            // Walk randomly until the tree is reached
+            let mut current = start;
+            while candidates[current] {
+                let neighbors = maze
+                    .walls(current)
+                    .iter()
+                    .filter(|&wall| {
+                        let (next, _) = maze.back((current, wall));
+                        *filter.get(next).unwrap_or(&false)
+                    })
+                    .collect::<Vec<_>>();
+                let wall = neighbors[rng.range(0, neighbors.len())];
+                exits[current] = Some(wall);
+                current = maze.back((current, wall)).0;
+            }
+
+            // Add the loop erased walk to the tree
+            let mut current = start;
+            while candidates[current] {
+                let wall = exits[current].unwrap();
+                maze.open((current, wall));
+                candidates[current] = false;
+                current = maze.back((current, wall)).0;
+            }
+        }
+    }
+
+    maze
+}
@@ -12,14 +12,21 @@ use crate::matrix;
 ///
 /// This method will ignore rooms for which `filter` returns `false`.
 ///
+/// The wall through which to leave a room is picked uniformly among all walls
+/// leading to unvisited rooms, so the static order of the walls of a shape
+/// does not introduce any directional bias; shuffling the walls before
+/// picking one would not change the distribution of generated mazes.
+///
 /// # Arguments
 /// *  `maze``- The maze to initialise.
 /// *  `rng` - A random number generator.
 /// *  `candidates` - A filter for the rooms to modify.
+/// *  `progress` - A function receiving the fraction of visited rooms, if any.
 pub(crate) fn initialize<R, T>(
     mut maze: Maze<T>,
     rng: &mut R,
     mut candidates: matrix::Matrix<bool>,
+    mut progress: Option<&mut dyn FnMut(f32)>,
 ) -> Maze<T>
 where
     R: super::Randomizer + Sized,
@@ -28,10 +35,27 @@ where
     // The backracking path is initially empty
     let mut path = Vec::new();
 
+    // Progress is reported for every percent of the rooms visited
+    let total = if progress.is_some() {
+        candidates.values().filter(|&&candidate| candidate).count()
+    } else {
+        0
+    };
+    let step = (total / 100).max(1);
+    let mut visited = 0;
+
     // Start in a random room; we know that at least one candidate exists
     let mut current = super::random_room(rng, &candidates).unwrap();
 
     loop {
+        if let Some(progress) = progress.as_mut() {
+            if candidates[current] {
+                visited += 1;
+                if visited % step == 0 {
+                    progress(visited as f32 / total as f32);
+                }
+            }
+        }
         candidates[current] = false;
 
         // Find all non-visited neighbours as the tuple (neighbour-position,
@@ -63,3 +87,75 @@ where
 
     maze
 }
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+    use rand::SeedableRng;
+
+    use crate::initialize::{Method, LFSR};
+    use crate::test_utils::*;
+
+    #[test]
+    fn initialize_door_directions_uniform() {
+        // The centre room of a square maze is symmetric, so without any
+        // directional bias its doors are equally likely to lead anywhere
+        let maze = crate::Shape::Quad.create::<()>(5, 5);
+        let centre = matrix_pos(2, 2);
+        let mut counts = vec![0usize; maze.walls(centre).len()];
+        for seed in 0..2000 {
+            let maze = maze.clone().initialize(
+                Method::Winding,
+                &mut rand::rngs::StdRng::seed_from_u64(seed),
+            );
+            for wall in maze.doors(centre) {
+                counts[wall.ordinal] += 1;
+            }
+        }
+
+        let expected =
+            counts.iter().sum::<usize>() as f32 / counts.len() as f32;
+        assert!(expected > 500.0);
+        for (ordinal, &count) in counts.iter().enumerate() {
+            assert!(
+                (count as f32 - expected).abs() < 0.1 * expected,
+                "wall {} open {} times, expected {}",
+                ordinal,
+                count,
+                expected,
+            );
+        }
+    }
+
+    #[maze_test]
+    fn initialize_progress(maze: TestMaze) {
+        let mut reported = Vec::new();
+        let maze = maze.initialize_filter_progress(
+            Method::Winding,
+            &mut LFSR::new(12345),
+            |_| true,
+            Some(&mut |progress| reported.push(progress)),
+        );
+
+        assert!(maze.positions().all(|pos| maze[pos].visited));
+        assert!(reported.len() > 2);
+        assert!(reported.windows(2).all(|w| w[0] <= w[1]));
+        assert!(reported.iter().all(|p| (0.0..=1.0).contains(p)));
+        assert_eq!(Some(&1.0), reported.last());
+    }
+
+    #[maze_test]
+    fn initialize_progress_segmented(maze: TestMaze) {
+        let width = maze.width();
+        let mut reported = Vec::new();
+        maze.initialize_filter_progress(
+            Method::Winding,
+            &mut LFSR::new(12345),
+            |pos| pos.col as usize != width / 2,
+            Some(&mut |progress| reported.push(progress)),
+        );
+
+        assert!(reported.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(Some(&1.0), reported.last());
+    }
+}
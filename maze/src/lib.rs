@@ -1,4 +1,4 @@
-#![cfg_attr(feature = "cargo-clippy", deny(clippy::all))]
+#![deny(clippy::all)]
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,12 @@ pub mod wall;
 pub mod shape;
 pub use self::shape::Shape;
 
+pub mod analysis;
+pub mod error;
+pub use self::error::ParseError;
+pub mod export;
+pub mod generate;
+pub mod graph;
 pub mod initialize;
 pub mod matrix;
 pub mod physical;
@@ -39,6 +45,40 @@ where
 
     /// The actual rooms.
     rooms: Rooms<T>,
+
+    /// The horizontal and vertical scale of rooms.
+    #[cfg_attr(feature = "serde", serde(default = "default_aspect"))]
+    aspect: (f32, f32),
+
+    /// Whether the horizontal and vertical physical axes are swapped.
+    #[cfg_attr(feature = "serde", serde(default))]
+    transposed: bool,
+
+    /// How the edges of the maze connect.
+    #[cfg_attr(feature = "serde", serde(default))]
+    topology: Topology,
+}
+
+/// How the edges of a maze connect.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Deserialize, Serialize),
+    serde(rename_all = "lowercase")
+)]
+pub enum Topology {
+    /// The maze is surrounded by walls leading out of the maze.
+    #[default]
+    Bounded,
+
+    /// The maze wraps around; walls on one edge lead to the rooms on the
+    /// opposite edge.
+    Torus,
+}
+
+/// The aspect of mazes with unit rooms.
+fn default_aspect() -> (f32, f32) {
+    (1.0, 1.0)
 }
 
 impl<T> Maze<T>
@@ -47,13 +87,21 @@ where
 {
     /// Creates an uninitialised maze.
     ///
+    /// All walls of the new maze are closed, and no room is visited.
+    ///
     /// # Arguments
     /// *  `shape` - The shape of the rooms.
     /// *  `width` - The width, in rooms, of the maze.
     /// *  `height` - The height, in rooms, of the maze.
     pub fn new(shape: Shape, width: usize, height: usize) -> Self {
         let rooms = Rooms::new(width, height);
-        Self { shape, rooms }
+        Self {
+            shape,
+            rooms,
+            aspect: default_aspect(),
+            transposed: false,
+            topology: Topology::Bounded,
+        }
     }
 }
 
@@ -63,7 +111,8 @@ where
 {
     /// Creates an uninitialised maze.
     ///
-    /// This method allows creating a maze initialised with data.
+    /// This method allows creating a maze initialised with data. All walls of
+    /// the new maze are closed, and no room is visited.
     ///
     /// # Arguments
     /// *  `shape` - The shape of the rooms.
@@ -80,7 +129,13 @@ where
         F: FnMut(matrix::Pos) -> T,
     {
         let rooms = Rooms::new_with_data(width, height, |pos| data(pos).into());
-        Self { shape, rooms }
+        Self {
+            shape,
+            rooms,
+            aspect: default_aspect(),
+            transposed: false,
+            topology: Topology::Bounded,
+        }
     }
 
     /// Maps each room, yielding a maze with the same layout but with
@@ -98,6 +153,192 @@ where
             rooms: self.rooms.map_with_pos(|pos, value| {
                 value.with_data(data(pos, value.data.clone()))
             }),
+            aspect: self.aspect,
+            transposed: self.transposed,
+            topology: self.topology,
+        }
+    }
+
+    /// Places another maze to the right of this one.
+    ///
+    /// The new maze is as wide as both mazes together; the rooms of this maze
+    /// keep their positions, and the rooms of `other` are moved right by the
+    /// width of this maze. All walls crossing the seam between them are
+    /// closed; see [`Maze::seam_walls`] to open gateways.
+    ///
+    /// The new maze has the aspect and orientation of this maze.
+    ///
+    /// # Arguments
+    /// *  `other` - The maze to place to the right.
+    ///
+    /// # Errors
+    /// An error is returned if the mazes differ in shape, height or
+    /// orientation, if either is a torus, or if the rooms of `other` would
+    /// change orientation; the latter happens for tri mazes when this maze
+    /// has an odd width.
+    pub fn concat_horizontal(&self, other: &Maze<T>) -> Result<Self, String> {
+        if self.shape != other.shape {
+            Err(format!(
+                "cannot concatenate {} and {} mazes",
+                self.shape, other.shape,
+            ))
+        } else if self.height() != other.height() {
+            Err(format!(
+                "cannot concatenate mazes with heights {} and {}",
+                self.height(),
+                other.height(),
+            ))
+        } else if self.transposed != other.transposed {
+            Err("cannot concatenate mazes with different orientations".into())
+        } else if self.topology != Topology::Bounded
+            || other.topology != Topology::Bounded
+        {
+            Err("cannot concatenate a torus".into())
+        } else if self.shape == Shape::Tri && self.width() % 2 == 1 {
+            Err(format!(
+                "cannot concatenate tri mazes after an odd width {}",
+                self.width(),
+            ))
+        } else {
+            let offset = self.width() as isize;
+            let rooms = matrix::Matrix::new_with_data(
+                self.width() + other.width(),
+                self.height(),
+                |pos| {
+                    if pos.col < offset {
+                        self.rooms[pos].clone()
+                    } else {
+                        other.rooms[matrix::Pos {
+                            col: pos.col - offset,
+                            row: pos.row,
+                        }]
+                        .clone()
+                    }
+                },
+            );
+            let mut maze = Self {
+                shape: self.shape,
+                rooms,
+                aspect: self.aspect,
+                transposed: self.transposed,
+                topology: self.topology,
+            };
+
+            for wall_pos in maze.seam_walls(self.width()).collect::<Vec<_>>() {
+                maze.close(wall_pos);
+            }
+
+            Ok(maze)
+        }
+    }
+
+    /// Sets the aspect of rooms.
+    ///
+    /// The aspect scales the physical coordinates of the maze horizontally and
+    /// vertically. Only quad mazes support an aspect other than `(1.0, 1.0)`.
+    ///
+    /// # Arguments
+    /// *  `aspect` - The horizontal and vertical scale.
+    ///
+    /// # Errors
+    /// An error is returned if the shape does not support the aspect, or if
+    /// any value is not positive.
+    pub fn with_aspect(self, aspect: (f32, f32)) -> Result<Self, String> {
+        if !(aspect.0 > 0.0 && aspect.1 > 0.0) {
+            Err(format!("invalid aspect: {:?}", aspect))
+        } else if self.shape != Shape::Quad && aspect != default_aspect() {
+            Err(format!(
+                "unsupported aspect for {:?}: {:?}",
+                self.shape, aspect
+            ))
+        } else {
+            Ok(Self { aspect, ..self })
+        }
+    }
+
+    /// Sets the orientation of hex rooms.
+    ///
+    /// Hex mazes are [`HexOrientation::PointyTop`] by default. A
+    /// [`HexOrientation::FlatTop`] maze is its pointy-top counterpart with the
+    /// horizontal and vertical physical axes swapped; matrix columns then run
+    /// vertically and rows horizontally.
+    ///
+    /// # Arguments
+    /// *  `orientation` - The orientation.
+    ///
+    /// # Errors
+    /// An error is returned if this is not a hex maze.
+    pub fn with_orientation(
+        self,
+        orientation: shape::hex::HexOrientation,
+    ) -> Result<Self, String> {
+        if self.shape != Shape::Hex {
+            Err(format!("unsupported orientation for {:?}", self.shape))
+        } else {
+            Ok(Self {
+                transposed: orientation == shape::hex::HexOrientation::FlatTop,
+                ..self
+            })
+        }
+    }
+
+    /// The orientation of hex rooms, or `None` if this is not a hex maze.
+    pub fn orientation(&self) -> Option<shape::hex::HexOrientation> {
+        match (self.shape, self.transposed) {
+            (Shape::Hex, false) => Some(shape::hex::HexOrientation::PointyTop),
+            (Shape::Hex, true) => Some(shape::hex::HexOrientation::FlatTop),
+            _ => None,
+        }
+    }
+
+    /// Sets the topology of the maze.
+    ///
+    /// On a [`Topology::Torus`], the walls on every edge lead to the rooms on
+    /// the opposite edge, so [`Maze::back`], [`Maze::adjacent`] and everything
+    /// built on them never leave the maze. Only quad mazes of at least 3 by 3
+    /// rooms support a torus.
+    ///
+    /// # Arguments
+    /// *  `topology` - The topology.
+    ///
+    /// # Errors
+    /// An error is returned if the shape or dimensions do not support the
+    /// topology.
+    pub fn with_topology(self, topology: Topology) -> Result<Self, String> {
+        match topology {
+            Topology::Torus if self.shape != Shape::Quad => {
+                Err(format!("unsupported topology for {:?}", self.shape))
+            }
+            Topology::Torus if self.width() < 3 || self.height() < 3 => {
+                Err(format!(
+                    "a torus must be at least 3x3 rooms, not {}x{}",
+                    self.width(),
+                    self.height(),
+                ))
+            }
+            _ => Ok(Self { topology, ..self }),
+        }
+    }
+
+    /// The topology of the maze.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Wraps a position according to the topology of the maze.
+    ///
+    /// On a torus, positions outside of the maze are moved inside by the
+    /// width and height of the maze; otherwise positions are returned as is.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn wrap(&self, pos: matrix::Pos) -> matrix::Pos {
+        match self.topology {
+            Topology::Bounded => pos,
+            Topology::Torus => matrix::Pos {
+                col: pos.col.rem_euclid(self.width() as isize),
+                row: pos.row.rem_euclid(self.height() as isize),
+            },
         }
     }
 
@@ -172,12 +413,30 @@ where
         self.walls(pos1)
             .iter()
             .find(|wall| {
-                (pos1.col + wall.dir.0 == pos2.col)
-                    && (pos1.row + wall.dir.1 == pos2.row)
+                self.wrap(matrix::Pos {
+                    col: pos1.col + wall.dir.0,
+                    row: pos1.row + wall.dir.1,
+                }) == pos2
             })
             .map(|&wall| (pos1, wall))
     }
 
+    /// Finds the wall of a room leading to an adjacent room.
+    ///
+    /// This is [`Maze::connecting_wall`] without the room position. If the
+    /// rooms are not adjacent, `None` is returned.
+    ///
+    /// # Arguments
+    /// *  `a` - The room whose wall to find.
+    /// *  `b` - The adjacent room.
+    pub fn wall_between(
+        &self,
+        a: matrix::Pos,
+        b: matrix::Pos,
+    ) -> Option<&'static wall::Wall> {
+        self.connecting_wall(a, b).map(|(_, wall)| wall)
+    }
+
     /// Whether two rooms are connected.
     ///
     /// Two rooms are connected if there is an open wall between them, or if
@@ -230,6 +489,137 @@ where
         self.set_open(wall_pos, false);
     }
 
+    /// Closes all walls on the border of the maze.
+    ///
+    /// Afterwards the maze is fully enclosed; walls between rooms inside of
+    /// the maze are not modified. See [`Maze::border_walls`].
+    pub fn seal_border(&mut self) {
+        for wall_pos in self.border_walls().collect::<Vec<_>>() {
+            if let Some(room) = self.rooms.get_mut(wall_pos.0) {
+                room.set_open(wall_pos.1, false);
+            }
+        }
+    }
+
+    /// The open walls of a room as a bit mask.
+    ///
+    /// The value is the union of [`wall::Wall::mask`] for all open walls of
+    /// the room. Rooms outside of the maze have no open walls.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn walls_mask(&self, pos: matrix::Pos) -> wall::Mask {
+        self.doors(pos).fold(0, |acc, wall| acc | wall.mask())
+    }
+
+    /// Sets the open walls of a room from a bit mask.
+    ///
+    /// Every wall of the room is opened if its [`wall::Wall::mask`] is part of
+    /// `mask`, and closed otherwise, and the backs of all walls are updated to
+    /// match. Bits not corresponding to a wall of the room are ignored.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    /// *  `mask` - The bit mask of open walls.
+    pub fn set_walls_mask(&mut self, pos: matrix::Pos, mask: wall::Mask) {
+        for &wall in self.walls(pos) {
+            let value = mask & wall.mask() != 0;
+            if self.is_open((pos, wall)) != value {
+                self.set_open((pos, wall), value);
+            }
+        }
+    }
+
+    /// Sets whether all walls inside of a region are open.
+    ///
+    /// Only walls between two rooms inside of the maze both satisfying
+    /// `filter` are modified.
+    ///
+    /// # Arguments
+    /// *  `filter` - A filter for the rooms in the region.
+    /// *  `value` - Whether the walls are open.
+    pub fn set_open_region<F>(&mut self, filter: F, value: bool)
+    where
+        F: Fn(matrix::Pos) -> bool,
+    {
+        for pos in self.positions().filter(|&pos| filter(pos)) {
+            for &wall in self.walls(pos) {
+                let (back, _) = self.back((pos, wall));
+                if self.is_inside(back) && filter(back) {
+                    self.set_open((pos, wall), value);
+                }
+            }
+        }
+    }
+
+    /// Opens all walls inside of a region.
+    ///
+    /// See [`Maze::set_open_region`].
+    ///
+    /// # Arguments
+    /// *  `filter` - A filter for the rooms in the region.
+    pub fn open_region<F>(&mut self, filter: F)
+    where
+        F: Fn(matrix::Pos) -> bool,
+    {
+        self.set_open_region(filter, true);
+    }
+
+    /// Closes all walls inside of a region.
+    ///
+    /// See [`Maze::set_open_region`].
+    ///
+    /// # Arguments
+    /// *  `filter` - A filter for the rooms in the region.
+    pub fn close_region<F>(&mut self, filter: F)
+    where
+        F: Fn(matrix::Pos) -> bool,
+    {
+        self.set_open_region(filter, false);
+    }
+
+    /// Closes all walls of the maze.
+    ///
+    /// This includes walls leading out of the maze. After calling this
+    /// method, the layout is the same as that of a newly created maze; this
+    /// is the baseline expected by the initialisation methods in
+    /// [`initialize`].
+    ///
+    /// The visited state and data of rooms are not modified.
+    pub fn close_all(&mut self) {
+        for pos in self.positions() {
+            for &wall in self.walls(pos) {
+                self.close((pos, wall));
+            }
+        }
+    }
+
+    /// Closes all walls and marks all rooms as not visited.
+    ///
+    /// Unlike [`Maze::close_all`], this also clears the visited state, so the
+    /// maze is afterwards equivalent to a newly created maze with the same
+    /// shape and dimensions. The rooms are modified in place, which allows
+    /// reusing a maze when generating many mazes of the same size.
+    ///
+    /// The data of rooms is not modified.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::initialize::{Method, LFSR};
+    /// let mut maze = maze::Shape::Quad.create::<()>(5, 5);
+    /// for seed in 1..5 {
+    ///     maze.reset();
+    ///     maze = maze.initialize(Method::Winding, &mut LFSR::new(seed));
+    ///     assert!(maze::analysis::is_perfect(&maze));
+    /// }
+    /// ```
+    pub fn reset(&mut self) {
+        for pos in self.positions() {
+            self.rooms[pos].reset();
+        }
+    }
+
     /// Iterates over all room positions.
     ///
     /// The positions are visited row by row, starting from `(0, 0)` and ending
@@ -238,13 +628,22 @@ where
         self.rooms.positions()
     }
 
+    /// Iterates over the positions of all visited rooms.
+    ///
+    /// These are the rooms that have been carved by an initialiser; for a
+    /// masked maze, rooms outside of the mask are skipped. The positions are
+    /// visited in the same order as by [`Maze::positions`].
+    pub fn visited_positions(&self) -> impl Iterator<Item = matrix::Pos> + '_ {
+        self.positions().filter(move |&pos| self.rooms[pos].visited)
+    }
+
     /// The physical positions of the two corners of a wall.
     ///
     /// # Arguments
     /// *  `wall_pos` - The wall position.
     pub fn corners(&self, wall_pos: WallPos) -> (physical::Pos, physical::Pos) {
-        let center = self.center(wall_pos.0);
-        (center + wall_pos.1.span.0, center + wall_pos.1.span.1)
+        let (start, end) = self.shape.corners(wall_pos);
+        (self.transform(start), self.transform(end))
     }
 
     /// All walls that meet in the corner where a wall has its start span.
@@ -255,12 +654,28 @@ where
     ///
     /// This method will visit rooms outside of the maze for rooms on the edge.
     ///
+    /// This is useful when tracing the outlines of cells, since the walls
+    /// yielded are exactly those sharing the first corner of `wall_pos`.
+    ///
     /// # Arguments
     /// *  `wall_pos` - The wall position.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix;
+    /// let maze = maze::Shape::Quad.create::<()>(5, 5);
+    /// let pos = matrix::Pos { col: 2, row: 2 };
+    /// let wall = maze.walls(pos)[0];
+    /// let walls = maze.corner_walls((pos, wall)).collect::<Vec<_>>();
+    /// assert_eq!(walls.len(), 4);
+    /// assert_eq!(walls[0], (pos, wall));
+    /// assert_eq!(walls[1].0, maze.back((pos, wall)).0);
+    /// ```
     pub fn corner_walls(
         &self,
         wall_pos: WallPos,
-    ) -> impl Iterator<Item = WallPos> + DoubleEndedIterator {
+    ) -> impl DoubleEndedIterator<Item = WallPos> {
         let (matrix::Pos { col, row }, wall) = wall_pos;
         std::iter::once(wall_pos).chain(wall.corner_wall_offsets.iter().map(
             move |&wall::Offset { dx, dy, wall }| {
@@ -277,15 +692,66 @@ where
 
     /// Iterates over all wall positions of a room.
     ///
+    /// The wall positions are yielded as `(pos, wall)` in the same order as
+    /// [`Maze::walls`].
+    ///
     /// # Arguments
     /// *  `pos` - The room position.
     pub fn wall_positions(
         &self,
         pos: matrix::Pos,
-    ) -> impl Iterator<Item = WallPos> + DoubleEndedIterator + '_ {
+    ) -> impl DoubleEndedIterator<Item = WallPos> + '_ {
         self.walls(pos).iter().map(move |&wall| (pos, wall))
     }
 
+    /// Iterates over all wall positions of a room leading to another room
+    /// inside of the maze.
+    ///
+    /// This is [`Maze::wall_positions`] without the walls on the border of the
+    /// maze.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn internal_wall_positions(
+        &self,
+        pos: matrix::Pos,
+    ) -> impl DoubleEndedIterator<Item = WallPos> + '_ {
+        self.wall_positions(pos)
+            .filter(move |&wall_pos| self.is_inside(self.back(wall_pos).0))
+    }
+
+    /// Iterates over all walls on the border of the maze.
+    ///
+    /// These are the walls of rooms inside of the maze whose backs are outside
+    /// of it. A torus has no border walls.
+    pub fn border_walls(&self) -> impl Iterator<Item = WallPos> + '_ {
+        self.positions()
+            .flat_map(move |pos| self.wall_positions(pos))
+            .filter(move |&wall_pos| !self.is_inside(self.back(wall_pos).0))
+    }
+
+    /// Iterates over all walls crossing the seam before a column.
+    ///
+    /// These are the walls of rooms in column `col - 1` whose backs are in
+    /// column `col` or later. For a maze created by
+    /// [`Maze::concat_horizontal`], passing the width of the left maze yields
+    /// the walls between the two mazes; open some of them to connect the
+    /// mazes.
+    ///
+    /// # Arguments
+    /// *  `col` - The first column to the right of the seam.
+    pub fn seam_walls(&self, col: usize) -> impl Iterator<Item = WallPos> + '_ {
+        let col = col as isize;
+        (0..self.height() as isize)
+            .map(move |row| matrix::Pos { col: col - 1, row })
+            .filter(move |&pos| self.is_inside(pos))
+            .flat_map(move |pos| self.wall_positions(pos))
+            .filter(move |&wall_pos| {
+                let (back, _) = self.back(wall_pos);
+                self.is_inside(back) && back.col >= col
+            })
+    }
+
     /// Iterates over all open walls of a room.
     ///
     /// # Arguments
@@ -293,8 +759,7 @@ where
     pub fn doors(
         &self,
         pos: matrix::Pos,
-    ) -> impl Iterator<Item = &'static wall::Wall> + DoubleEndedIterator + '_
-    {
+    ) -> impl DoubleEndedIterator<Item = &'static wall::Wall> + '_ {
         self.walls(pos)
             .iter()
             .filter(move |&wall| self.is_open((pos, wall)))
@@ -303,31 +768,50 @@ where
 
     /// Iterates over all adjacent rooms.
     ///
-    /// This method will visit rooms outside of the maze for rooms on the edge.
+    /// This method will visit rooms outside of the maze for rooms on the edge,
+    /// unless the maze is a torus.
     ///
     /// # Arguments
     /// *  `pos` - The room position.
     pub fn adjacent(
         &self,
         pos: matrix::Pos,
-    ) -> impl Iterator<Item = matrix::Pos> + DoubleEndedIterator + '_ {
-        self.walls(pos).iter().map(move |&wall| matrix::Pos {
-            col: pos.col + wall.dir.0,
-            row: pos.row + wall.dir.1,
+    ) -> impl DoubleEndedIterator<Item = matrix::Pos> + '_ {
+        self.walls(pos).iter().map(move |&wall| {
+            self.wrap(matrix::Pos {
+                col: pos.col + wall.dir.0,
+                row: pos.row + wall.dir.1,
+            })
         })
     }
 
+    /// Lists all rooms adjacent to a room inside of the maze.
+    ///
+    /// Unlike [`Maze::adjacent`], only rooms inside of the maze are included.
+    /// Unlike [`Maze::neighbors`], whether the walls between the rooms are open
+    /// is not considered, so rooms not reachable from `pos` are included as
+    /// well.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn adjacent_inside(&self, pos: matrix::Pos) -> Vec<matrix::Pos> {
+        self.adjacent(pos)
+            .filter(|&pos| self.is_inside(pos))
+            .collect()
+    }
+
     /// Iterates over all reachable neighbours of a room.
     ///
-    /// This method will visit rooms outside of the maze if an opening outside
-    /// from the room exists.
+    /// Only rooms behind open walls are visited; see [`Maze::adjacent_inside`]
+    /// for all adjacent rooms. This method will visit rooms outside of the
+    /// maze if an opening outside from the room exists.
     ///
     /// # Arguments
     /// *  `pos` - The room position.
     pub fn neighbors(
         &self,
         pos: matrix::Pos,
-    ) -> impl Iterator<Item = matrix::Pos> + DoubleEndedIterator + '_ {
+    ) -> impl DoubleEndedIterator<Item = matrix::Pos> + '_ {
         self.doors(pos).map(move |wall| self.back((pos, wall)).0)
     }
 }
@@ -346,6 +830,29 @@ where
 /// A matrix of scores for rooms.
 pub type HeatMap = matrix::Matrix<u32>;
 
+impl HeatMap {
+    /// The greatest value of this heat map.
+    ///
+    /// An empty heat map has the greatest value `0`.
+    pub fn max(&self) -> u32 {
+        self.values().copied().max().unwrap_or(0)
+    }
+
+    /// Creates a matrix with every value of this heat map divided by its
+    /// greatest value.
+    ///
+    /// All values are in the range `[0, 1]`. If the greatest value is `0`, all
+    /// values are `0.0`.
+    pub fn normalized(&self) -> matrix::Matrix<f32> {
+        let max = self.max();
+        if max == 0 {
+            self.map(|_| 0.0)
+        } else {
+            self.map(|&value| value as f32 / max as f32)
+        }
+    }
+}
+
 /// Generates a heat map where the value for each cell is the number of times it
 /// has been traversed when walking between the positions.
 ///
@@ -353,7 +860,7 @@ pub type HeatMap = matrix::Matrix<u32>;
 ///
 /// # Arguments
 /// *  `positions` - The positions as the tuple `(from, to)`. These are used as
-///   positions between which to walk.
+///    positions between which to walk.
 pub fn heatmap<I, T>(maze: &crate::Maze<T>, positions: I) -> HeatMap
 where
     I: Iterator<Item = (matrix::Pos, matrix::Pos)>,
@@ -362,16 +869,63 @@ where
     let mut result = matrix::Matrix::new(maze.width(), maze.height());
 
     for (from, to) in positions {
-        if let Some(path) = maze.walk(from, to) {
-            for pos in path.into_iter() {
-                result[pos] += 1;
-            }
-        }
+        heat_path(&mut result, maze, from, to);
     }
 
     result
 }
 
+/// Generates a heat map in parallel.
+///
+/// This is equivalent to [`heatmap`], but the positions are consumed by
+/// several threads as they are produced, without collecting them first. Every
+/// thread accumulates its own heat map, and these are summed at the end.
+///
+/// # Arguments
+/// *  `positions` - The positions as the tuple `(from, to)`. These are used as
+///    positions between which to walk.
+#[cfg(feature = "parallel")]
+pub fn heatmap_par<I, T>(maze: &crate::Maze<T>, positions: I) -> HeatMap
+where
+    I: Iterator<Item = (matrix::Pos, matrix::Pos)> + Send,
+    T: Clone + Sync,
+{
+    use rayon::prelude::*;
+
+    let empty = || HeatMap::new(maze.width(), maze.height());
+    positions
+        .par_bridge()
+        .fold(empty, |mut result, (from, to)| {
+            heat_path(&mut result, maze, from, to);
+            result
+        })
+        .reduce(empty, std::ops::Add::add)
+}
+
+/// Increments the heat of every room along the path between two rooms.
+///
+/// If the rooms are not connected, the heat map is not modified.
+///
+/// # Arguments
+/// *  `heatmap` - The heat map to update.
+/// *  `maze` - The maze.
+/// *  `from` - The starting position.
+/// *  `to` - The desired goal.
+fn heat_path<T>(
+    heatmap: &mut HeatMap,
+    maze: &crate::Maze<T>,
+    from: matrix::Pos,
+    to: matrix::Pos,
+) where
+    T: Clone,
+{
+    if let Some(path) = maze.walk(from, to) {
+        for pos in path.into_iter() {
+            heatmap[pos] += 1;
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use maze_test::maze_test;
@@ -388,6 +942,109 @@ mod tests {
         assert_eq!(Some(&true), maze.data(pos));
     }
 
+    #[maze_test]
+    fn concat_horizontal_halves(maze: TestMaze) {
+        let left = maze.clone().initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(1),
+        );
+        let right = Maze::new(maze.shape(), 4, maze.height()).initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(2),
+        );
+        let mut combined = left.concat_horizontal(&right).unwrap();
+        let offset = left.width() as isize;
+
+        assert_eq!(left.width() + right.width(), combined.width());
+        assert_eq!(left.height(), combined.height());
+        for (source, offset) in [(&left, 0), (&right, offset)] {
+            for pos in source.positions() {
+                let moved = matrix_pos(pos.col + offset, pos.row);
+                assert_eq!(source[pos].visited, combined[moved].visited);
+                assert_eq!(source.walls(pos), combined.walls(moved));
+                for wall_pos in source.internal_wall_positions(pos) {
+                    assert_eq!(
+                        source.is_open(wall_pos),
+                        combined.is_open((moved, wall_pos.1)),
+                    );
+                }
+            }
+        }
+
+        let seam = combined.seam_walls(left.width()).collect::<Vec<_>>();
+        assert!(!seam.is_empty());
+        assert!(seam.iter().all(|&wall_pos| !combined.is_open(wall_pos)));
+        assert!(!combined.connected(matrix_pos(0, 0), matrix_pos(offset, 0)));
+
+        combined.open(seam[0]);
+        assert!(crate::analysis::is_perfect(&combined));
+    }
+
+    #[test]
+    fn concat_horizontal_invalid() {
+        let quad = Shape::Quad.create::<()>(4, 3);
+        assert!(quad.concat_horizontal(&Shape::Hex.create(4, 3)).is_err());
+        assert!(quad.concat_horizontal(&Shape::Quad.create(4, 4)).is_err());
+        assert!(quad
+            .concat_horizontal(
+                &Shape::Quad
+                    .create(4, 3)
+                    .with_topology(Topology::Torus)
+                    .unwrap(),
+            )
+            .is_err());
+        assert!(Shape::Tri
+            .create::<()>(3, 3)
+            .concat_horizontal(&Shape::Tri.create(4, 3))
+            .is_err());
+        assert!(Shape::Tri
+            .create::<()>(4, 3)
+            .concat_horizontal(&Shape::Tri.create(3, 3))
+            .is_ok());
+    }
+
+    #[maze_test]
+    fn reset_in_place(maze: TestMaze) {
+        let mut maze = maze.initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+        let (shape, width, height) =
+            (maze.shape(), maze.width(), maze.height());
+        let storage = &maze[matrix_pos(0, 0)] as *const _;
+
+        maze.reset();
+
+        assert_eq!(storage, &maze[matrix_pos(0, 0)] as *const _);
+        assert_eq!(
+            (shape, width, height),
+            (maze.shape(), maze.width(), maze.height())
+        );
+        assert!(maze.positions().all(|pos| maze[pos].open_walls() == 0));
+        assert!(maze.positions().all(|pos| !maze[pos].visited));
+        assert_eq!(
+            export::to_bitmask_matrix(&Maze::<()>::new(shape, width, height)),
+            export::to_bitmask_matrix(&maze),
+        );
+    }
+
+    #[maze_test]
+    fn clone_is_independent(mut maze: TestMaze) {
+        let pos = matrix_pos(1, 1);
+        let wall = maze.walls(pos)[0];
+        maze.open((pos, wall));
+
+        let mut clone = maze.clone();
+        clone.close((pos, wall));
+        clone.open((pos, maze.walls(pos)[1]));
+        clone.rooms[matrix_pos(3, 3)].visited = true;
+
+        assert!(maze.is_open((pos, wall)));
+        assert!(!maze.is_open((pos, maze.walls(pos)[1])));
+        assert!(!maze[matrix_pos(3, 3)].visited);
+        assert!(clone[pos].visited);
+    }
+
     #[maze_test]
     fn is_inside_correct(maze: TestMaze) {
         assert!(maze.is_inside(matrix_pos(0, 0)));
@@ -444,6 +1101,122 @@ mod tests {
         );
     }
 
+    #[maze_test]
+    fn set_walls_mask(mut maze: TestMaze) {
+        let pos = matrix_pos(2, 2);
+        let walls = maze.walls(pos);
+        let mask = walls[0].mask() | walls[2].mask();
+        maze.set_walls_mask(pos, mask);
+        assert_eq!(mask, maze.walls_mask(pos));
+        assert!(maze[pos].visited);
+
+        let mask = walls[1].mask() | walls[2].mask();
+        maze.set_walls_mask(pos, mask);
+        assert_eq!(mask, maze.walls_mask(pos));
+
+        for &wall in walls {
+            let back = maze.back((pos, wall));
+            assert_eq!(maze.is_open((pos, wall)), maze.is_open(back));
+        }
+        assert_eq!(
+            4,
+            maze.positions()
+                .map(|pos| maze[pos].open_walls())
+                .sum::<usize>(),
+        );
+    }
+
+    #[maze_test]
+    fn set_walls_mask_ignores_invalid(mut maze: TestMaze) {
+        let pos = matrix_pos(2, 2);
+        let valid = maze
+            .walls(pos)
+            .iter()
+            .fold(0, |acc, wall| acc | wall.mask());
+        maze.set_walls_mask(pos, !0);
+
+        assert_eq!(valid, maze.walls_mask(pos));
+        assert_eq!(0, maze.walls_mask(matrix_pos(-1, -1)));
+    }
+
+    #[maze_test]
+    fn open_region(mut maze: TestMaze) {
+        let region = |pos: matrix::Pos| {
+            (1..=2).contains(&pos.col) && (1..=2).contains(&pos.row)
+        };
+        maze.open_region(region);
+
+        let rooms = maze
+            .positions()
+            .filter(|&pos| region(pos))
+            .collect::<Vec<_>>();
+        for &pos1 in rooms.iter() {
+            for &pos2 in rooms.iter() {
+                assert!(maze.walk(pos1, pos2).is_some());
+            }
+            for &wall in maze.walls(pos1) {
+                let (back, _) = maze.back((pos1, wall));
+                assert_eq!(region(back), maze.is_open((pos1, wall)));
+            }
+        }
+        assert!(maze
+            .positions()
+            .filter(|&pos| !region(pos))
+            .all(|pos| !maze[pos].visited));
+    }
+
+    #[maze_test]
+    fn close_region(maze: TestMaze) {
+        let mut maze = maze.initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+        let region = |pos: matrix::Pos| pos.col < 3;
+        maze.close_region(region);
+
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                let (back, _) = maze.back((pos, wall));
+                if maze.is_inside(back) {
+                    assert_eq!(
+                        !(region(pos) && region(back)),
+                        maze.is_open((pos, wall)),
+                    );
+                }
+            }
+        }
+    }
+
+    #[maze_test]
+    fn new_closed(maze: TestMaze) {
+        for pos in maze.positions() {
+            assert!(maze.walls(pos).iter().all(|w| !maze.is_open((pos, w))));
+            assert!(!maze[pos].visited);
+        }
+    }
+
+    #[maze_test]
+    fn close_all(maze: TestMaze) {
+        let mut maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        maze.open((matrix_pos(0, 0), maze.walls(matrix_pos(0, 0))[0]));
+        maze.close_all();
+
+        for pos in maze.positions() {
+            assert_eq!(0, maze.doors(pos).count());
+        }
+        assert!(maze
+            .clone()
+            .initialize(
+                initialize::Method::Winding,
+                &mut initialize::LFSR::new(12345),
+            )
+            .walk(matrix_pos(0, 0), matrix_pos(1, 1))
+            .is_some());
+    }
+
     #[maze_test]
     fn connecting_wall_correct(maze: TestMaze) {
         for pos in maze.positions() {
@@ -467,6 +1240,182 @@ mod tests {
         }
     }
 
+    #[maze_test(quad)]
+    fn wall_between(maze: TestMaze) {
+        let pos = matrix_pos(2, 2);
+        for (other, wall) in [
+            (matrix_pos(1, 2), &shape::quad::walls::LEFT),
+            (matrix_pos(2, 1), &shape::quad::walls::UP),
+            (matrix_pos(3, 2), &shape::quad::walls::RIGHT),
+            (matrix_pos(2, 3), &shape::quad::walls::DOWN),
+        ] {
+            assert_eq!(Some(wall), maze.wall_between(pos, other));
+            assert_eq!(other, maze.back((pos, wall)).0);
+        }
+        assert_eq!(None, maze.wall_between(pos, matrix_pos(3, 3)));
+        assert_eq!(None, maze.wall_between(pos, pos));
+    }
+
+    #[maze_test]
+    fn wall_between_back(maze: TestMaze) {
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                let (other, back) = maze.back((pos, wall));
+                assert_eq!(Some(wall), maze.wall_between(pos, other));
+                assert_eq!(Some(back), maze.wall_between(other, pos));
+            }
+        }
+    }
+
+    #[test]
+    fn with_topology_unsupported() {
+        assert!(Shape::Hex
+            .create::<()>(5, 5)
+            .with_topology(Topology::Torus)
+            .is_err());
+        assert!(Shape::Quad
+            .create::<()>(2, 5)
+            .with_topology(Topology::Torus)
+            .is_err());
+        assert!(Shape::Hex
+            .create::<()>(5, 5)
+            .with_topology(Topology::Bounded)
+            .is_ok());
+    }
+
+    #[test]
+    fn torus_adjacent() {
+        let maze = Shape::Quad
+            .create::<()>(5, 3)
+            .with_topology(Topology::Torus)
+            .unwrap();
+
+        assert_eq!(Topology::Torus, maze.topology());
+        let mut adjacent = maze.adjacent(matrix_pos(0, 0)).collect::<Vec<_>>();
+        adjacent.sort();
+        assert_eq!(
+            vec![
+                matrix_pos(0, 1),
+                matrix_pos(0, 2),
+                matrix_pos(1, 0),
+                matrix_pos(4, 0),
+            ],
+            adjacent,
+        );
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                let (other, back) = maze.back((pos, wall));
+                assert!(maze.is_inside(other));
+                assert_eq!((pos, wall), maze.back((other, back)));
+            }
+        }
+    }
+
+    #[test]
+    fn torus_straight_path() {
+        let mut maze = Shape::Quad
+            .create::<()>(5, 3)
+            .with_topology(Topology::Torus)
+            .unwrap();
+        maze.open((matrix_pos(3, 1), &shape::quad::walls::RIGHT));
+        maze.open((matrix_pos(4, 1), &shape::quad::walls::RIGHT));
+        maze.open((matrix_pos(0, 1), &shape::quad::walls::RIGHT));
+
+        assert!(maze.is_open((matrix_pos(0, 1), &shape::quad::walls::LEFT)));
+        assert!(maze.connected(matrix_pos(4, 1), matrix_pos(0, 1)));
+        assert_eq!(
+            vec![
+                matrix_pos(3, 1),
+                matrix_pos(4, 1),
+                matrix_pos(0, 1),
+                matrix_pos(1, 1),
+            ],
+            maze.walk(matrix_pos(3, 1), matrix_pos(1, 1))
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn torus_perfect() {
+        let maze = Shape::Quad
+            .create::<()>(8, 6)
+            .with_topology(Topology::Torus)
+            .unwrap()
+            .initialize(
+                initialize::Method::Winding,
+                &mut initialize::LFSR::new(12345),
+            );
+
+        let rooms = maze.positions().count();
+        let passages = maze
+            .positions()
+            .flat_map(|pos| maze.doors(pos).map(move |wall| (pos, wall)))
+            .count()
+            / 2;
+        assert_eq!(rooms - 1, passages);
+        let start = matrix_pos(0, 0);
+        assert!(maze.positions().all(|pos| maze.walk(start, pos).is_some()));
+        assert!(maze.positions().any(|pos| {
+            maze.doors(pos).any(|wall| {
+                !maze.is_inside(matrix::Pos {
+                    col: pos.col + wall.dir.0,
+                    row: pos.row + wall.dir.1,
+                })
+            })
+        }));
+    }
+
+    #[maze_test]
+    fn visited_positions_masked(maze: TestMaze) {
+        let mask = |pos: matrix::Pos| pos.col != 2 && pos.row != 1;
+        let maze = maze.initialize_filter(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+            mask,
+        );
+
+        assert_eq!(
+            maze.positions()
+                .filter(|&pos| mask(pos))
+                .collect::<Vec<_>>(),
+            maze.visited_positions().collect::<Vec<_>>(),
+        );
+        assert_eq!(
+            0,
+            Shape::Quad.create::<()>(3, 3).visited_positions().count(),
+        );
+    }
+
+    #[maze_test]
+    fn seal_border(maze: TestMaze) {
+        let mut maze = maze.initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+        let border = maze.border_walls().collect::<Vec<_>>();
+        for &wall_pos in &border {
+            maze.open(wall_pos);
+        }
+        let internal = maze
+            .positions()
+            .flat_map(|pos| maze.internal_wall_positions(pos))
+            .map(|wall_pos| maze.is_open(wall_pos))
+            .collect::<Vec<_>>();
+        maze.seal_border();
+
+        assert!(!border.is_empty());
+        assert!(border.iter().all(|&wall_pos| !maze.is_open(wall_pos)));
+        assert_eq!(
+            internal,
+            maze.positions()
+                .flat_map(|pos| maze.internal_wall_positions(pos))
+                .map(|wall_pos| maze.is_open(wall_pos))
+                .collect::<Vec<_>>(),
+        );
+    }
+
     #[maze_test]
     fn connected_correct(mut maze: TestMaze) {
         for pos in maze.positions() {
@@ -507,12 +1456,44 @@ mod tests {
             .walls(pos)
             .iter()
             .filter(|wall| maze.is_inside(maze.back((pos, wall)).0))
-            .map(|&wall| wall)
+            .copied()
             .collect::<Vec<_>>();
         walls.iter().for_each(|wall| maze.open((pos, wall)));
         assert_eq!(maze.doors(pos).collect::<Vec<_>>(), walls);
     }
 
+    #[maze_test]
+    fn wall_positions(maze: TestMaze) {
+        let pos = matrix_pos(1, 1);
+        assert_eq!(
+            maze.wall_positions(pos).collect::<Vec<_>>(),
+            maze.walls(pos)
+                .iter()
+                .map(|&wall| (pos, wall))
+                .collect::<Vec<_>>(),
+        );
+    }
+
+    #[maze_test]
+    fn internal_wall_positions(maze: TestMaze) {
+        let corner = matrix_pos(0, 0);
+        let internal = maze.internal_wall_positions(corner).collect::<Vec<_>>();
+        assert!(internal.len() < maze.walls(corner).len());
+        for wall_pos in maze.wall_positions(corner) {
+            assert_eq!(
+                maze.is_inside(maze.back(wall_pos).0),
+                internal.contains(&wall_pos),
+            );
+        }
+
+        let center =
+            matrix_pos(maze.width() as isize / 2, maze.height() as isize / 2);
+        assert_eq!(
+            maze.wall_positions(center).collect::<Vec<_>>(),
+            maze.internal_wall_positions(center).collect::<Vec<_>>(),
+        );
+    }
+
     #[maze_test]
     fn adjacent(maze: TestMaze) {
         for pos1 in maze.positions() {
@@ -525,6 +1506,51 @@ mod tests {
         }
     }
 
+    #[maze_test(hex)]
+    fn adjacent_inside_hex(maze: TestMaze) {
+        assert_eq!(
+            maze.adjacent_inside(matrix_pos(3, 2)),
+            vec![
+                matrix_pos(2, 2),
+                matrix_pos(3, 1),
+                matrix_pos(4, 1),
+                matrix_pos(4, 2),
+                matrix_pos(4, 3),
+                matrix_pos(3, 3),
+            ],
+        );
+        assert_eq!(
+            maze.adjacent_inside(matrix_pos(3, 1)),
+            vec![
+                matrix_pos(2, 1),
+                matrix_pos(2, 0),
+                matrix_pos(3, 0),
+                matrix_pos(4, 1),
+                matrix_pos(3, 2),
+                matrix_pos(2, 2),
+            ],
+        );
+    }
+
+    #[maze_test]
+    fn adjacent_inside_all(mut maze: TestMaze) {
+        for pos in maze.positions().collect::<Vec<_>>() {
+            let adjacent = maze.adjacent_inside(pos);
+            assert!(adjacent.iter().all(|&pos| maze.is_inside(pos)));
+            assert!(maze
+                .adjacent(pos)
+                .filter(|&pos| maze.is_inside(pos))
+                .all(|pos| adjacent.contains(&pos)));
+        }
+
+        let pos = matrix_pos(0, 0);
+        let before = maze.adjacent_inside(pos);
+        maze.walls(pos)
+            .iter()
+            .for_each(|wall| maze.open((pos, wall)));
+        assert_eq!(before, maze.adjacent_inside(pos));
+    }
+
     #[maze_test]
     fn neighbors(mut maze: TestMaze) {
         let pos = matrix::Pos { col: 0, row: 0 };
@@ -543,4 +1569,47 @@ mod tests {
                 .collect::<Vec<_>>(),
         );
     }
+
+    #[cfg(feature = "parallel")]
+    #[maze_test]
+    fn heatmap_par_matches(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let positions = || {
+            maze.positions()
+                .map(|pos| (pos, matrix_pos(0, 0)))
+                .collect::<Vec<_>>()
+                .into_iter()
+        };
+
+        assert_eq!(
+            heatmap(&maze, positions()).values().collect::<Vec<_>>(),
+            heatmap_par(&maze, positions()).values().collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn heatmap_normalized() {
+        let heatmap = HeatMap::new_with_data(3, 2, |pos| {
+            (pos.col * (pos.row + 1)) as u32
+        });
+        let normalized = heatmap.normalized();
+
+        assert_eq!(4, heatmap.max());
+        assert_eq!(
+            vec![0.0, 0.25, 0.5, 0.0, 0.5, 1.0],
+            normalized.values().copied().collect::<Vec<_>>(),
+        );
+    }
+
+    #[test]
+    fn heatmap_normalized_zero() {
+        let heatmap = HeatMap::new(3, 2);
+
+        assert_eq!(0, heatmap.max());
+        assert!(heatmap.normalized().values().all(|&v| v == 0.0));
+        assert_eq!(0, HeatMap::new(0, 0).max());
+    }
 }
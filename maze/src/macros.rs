@@ -6,6 +6,7 @@ macro_rules! dispatch {
         match $on {
             crate::Shape::Hex => hex::$func($($args,)*),
             crate::Shape::Quad => quad::$func($($args,)*),
+            crate::Shape::QuadDiagonal => quad_diagonal::$func($($args,)*),
             crate::Shape::Tri => tri::$func($($args,)*),
         }
     }
@@ -215,6 +215,154 @@ where
         })
     }
 
+    /// Extracts a rectangular region of this matrix.
+    ///
+    /// The position `corner` of this matrix becomes position `(0, 0)` of the
+    /// returned matrix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix::*;
+    ///
+    /// let matrix = Matrix::new_with_data(4, 3, |pos| pos.col + 10 * pos.row);
+    /// assert_eq!(
+    ///     matrix
+    ///         .submatrix(Pos { col: 1, row: 1 }, 2, 2)
+    ///         .unwrap()
+    ///         .values()
+    ///         .cloned()
+    ///         .collect::<Vec<_>>(),
+    ///     vec![
+    ///         11,
+    ///         12,
+    ///         21,
+    ///         22,
+    ///     ],
+    /// );
+    /// assert!(matrix.submatrix(Pos { col: 3, row: 1 }, 2, 2).is_err());
+    /// assert!(matrix.submatrix(Pos { col: -1, row: 0 }, 2, 2).is_err());
+    /// # assert_eq!(
+    /// #     matrix.submatrix(Pos { col: 0, row: 0 }, 4, 3).unwrap().values()
+    /// #         .cloned()
+    /// #         .collect::<Vec<_>>(),
+    /// #     matrix.values().cloned().collect::<Vec<_>>(),
+    /// # );
+    /// # assert_eq!(
+    /// #     matrix.submatrix(Pos { col: 4, row: 3 }, 0, 0).unwrap().width,
+    /// #     0,
+    /// # );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `corner` - The position of the top left cell of the region.
+    /// *  `width` - The width of the region.
+    /// *  `height` - The height of the region.
+    ///
+    /// # Errors
+    /// This method returns an error if any part of the region lies outside of
+    /// this matrix.
+    pub fn submatrix(
+        &self,
+        corner: Pos,
+        width: usize,
+        height: usize,
+    ) -> Result<Self, String> {
+        if corner.col < 0
+            || corner.row < 0
+            || corner.col as usize + width > self.width
+            || corner.row as usize + height > self.height
+        {
+            Err(format!(
+                "region {}x{} at ({}, {}) outside of {}x{} matrix",
+                width, height, corner.col, corner.row, self.width, self.height,
+            ))
+        } else {
+            Ok(Self::new_with_data(width, height, |pos| {
+                self[Pos {
+                    col: corner.col + pos.col,
+                    row: corner.row + pos.row,
+                }]
+                .clone()
+            }))
+        }
+    }
+
+    /// Finds the cells reachable from a position.
+    ///
+    /// Starting at `start`, all cells for which `predicate` holds and that are
+    /// reachable through horizontally or vertically adjacent cells for which
+    /// it also holds are marked. If `start` is outside of the matrix, or
+    /// `predicate` does not hold for it, no cells are marked.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix::*;
+    ///
+    /// let matrix = Matrix::new_with_data(5, 3, |pos| pos.col != 2);
+    /// let region = matrix.flood_fill(Pos { col: 0, row: 1 }, |&v| v);
+    /// assert_eq!(
+    ///     region.positions().filter(|&pos| region[pos]).collect::<Vec<_>>(),
+    ///     vec![
+    ///         Pos { col: 0, row: 0 },
+    ///         Pos { col: 1, row: 0 },
+    ///         Pos { col: 0, row: 1 },
+    ///         Pos { col: 1, row: 1 },
+    ///         Pos { col: 0, row: 2 },
+    ///         Pos { col: 1, row: 2 },
+    ///     ],
+    /// );
+    /// # assert!(matrix
+    /// #     .flood_fill(Pos { col: 2, row: 1 }, |&v| v)
+    /// #     .values()
+    /// #     .all(|&v| !v));
+    /// # assert!(matrix
+    /// #     .flood_fill(Pos { col: -1, row: 1 }, |&v| v)
+    /// #     .values()
+    /// #     .all(|&v| !v));
+    /// # assert_eq!(
+    /// #     matrix
+    /// #         .flood_fill(Pos { col: 2, row: 1 }, |_| true)
+    /// #         .values()
+    /// #         .filter(|&&v| v)
+    /// #         .count(),
+    /// #     15,
+    /// # );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `start` - The starting position.
+    /// *  `predicate` - A predicate for the values of cells to mark.
+    pub fn flood_fill<F>(&self, start: Pos, predicate: F) -> Matrix<bool>
+    where
+        F: Fn(&T) -> bool,
+    {
+        let mut result = Matrix::new(self.width, self.height);
+        let mut queue = Vec::new();
+        if self.get(start).map(&predicate).unwrap_or(false) {
+            result[start] = true;
+            queue.push(start);
+        }
+
+        while let Some(pos) = queue.pop() {
+            for (dc, dr) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let next = Pos {
+                    col: pos.col + dc,
+                    row: pos.row + dr,
+                };
+                if self.get(next).map(&predicate).unwrap_or(false)
+                    && !result[next]
+                {
+                    result[next] = true;
+                    queue.push(next);
+                }
+            }
+        }
+
+        result
+    }
+
     /// Whether a position is inside of the matrix.
     ///
     /// # Example
@@ -305,6 +453,34 @@ where
         }
     }
 
+    /// Sets the value at a specific position if it exists.
+    ///
+    /// Positions outside of the matrix are ignored. The return value is
+    /// whether the value was set.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::matrix::*;
+    /// # type Matrix = maze::matrix::Matrix<u32>;
+    ///
+    /// let mut matrix = Matrix::new(5, 5);
+    /// assert!(matrix.try_set(Pos { col: 1, row: 1 }, 5));
+    /// assert!(!matrix.try_set(Pos { col: -1, row: 1 }, 5));
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `pos` - The matrix position.
+    /// *  `value` - The new value.
+    pub fn try_set(&mut self, pos: Pos, value: T) -> bool {
+        if let Some(v) = self.get_mut(pos) {
+            *v = value;
+            true
+        } else {
+            false
+        }
+    }
+
     /// Iterates over all cell positions.
     ///
     /// The positions are visited row by row, starting with `(0, 0)` and ending
@@ -705,33 +881,35 @@ where
 
 /// Partitions a number into its integral part and a fraction.
 ///
-/// Adding the fraction to the integral part will yield the original.
+/// Adding the fraction to the integral part will yield the original. The
+/// integral part is rounded towards negative infinity, so the fraction is
+/// always in the range `[0, 1)`, also for negative numbers.
 ///
 /// # Examples
 ///
 /// ```
-/// # use std::f32::EPSILON;
 /// # use maze::matrix::*;
 ///
 /// let (int, fract) = partition(1.2);
 /// assert_eq!(int, 1);
 /// assert!(
-///     (fract - 0.2).abs() < EPSILON,
+///     (fract - 0.2).abs() < f32::EPSILON,
 /// );
 ///
 /// let (int, fract) = partition(-1.2);
 /// assert_eq!(int, -2);
 /// assert!(
-///     (fract - 0.8).abs() < EPSILON,
+///     (fract - 0.8).abs() < f32::EPSILON,
 /// );
+///
+/// assert_eq!(partition(-2.0), (-2, 0.0));
 /// ```
 ///
 /// # Arguments
 /// *  `x` - a number.
 pub fn partition(x: f32) -> (isize, f32) {
-    let index = x.floor() as isize;
-    let rel = x.fract();
-    (index, if x >= 0.0 { rel } else { rel + 1.0 })
+    let index = x.floor();
+    (index as isize, x - index)
 }
 
 /// Generates a matrix initialised with the value returned by a filter
@@ -775,6 +953,34 @@ mod test {
         assert_eq!(expected, actual);
     }
 
+    #[test]
+    fn get_mut_inside() {
+        let mut matrix = Matrix::<u8>::new(3, 2);
+        *matrix.get_mut(matrix_pos(2, 1)).unwrap() += 3;
+        assert_eq!(3, matrix[matrix_pos(2, 1)]);
+    }
+
+    #[test]
+    fn get_mut_outside() {
+        let mut matrix = Matrix::<u8>::new(3, 2);
+        assert!(matrix.get_mut(matrix_pos(3, 0)).is_none());
+        assert!(matrix.get_mut(matrix_pos(0, -1)).is_none());
+    }
+
+    #[test]
+    fn try_set_inside() {
+        let mut matrix = Matrix::<u8>::new(3, 2);
+        assert!(matrix.try_set(matrix_pos(1, 1), 7));
+        assert_eq!(7, matrix[matrix_pos(1, 1)]);
+    }
+
+    #[test]
+    fn try_set_outside() {
+        let mut matrix = Matrix::<u8>::new(3, 2);
+        assert!(!matrix.try_set(matrix_pos(0, 2), 7));
+        assert!(matrix.values().all(|&v| v == 0));
+    }
+
     #[test]
     fn iterate_positions() {
         assert_eq!(
@@ -828,7 +1034,7 @@ mod test {
             )]
             .iter()
             .map(|(areas, positions)| (
-                areas.clone(),
+                *areas,
                 positions
                     .iter()
                     .cloned()
@@ -870,7 +1076,7 @@ mod test {
             ]
             .iter()
             .map(|(areas, positions)| (
-                areas.clone(),
+                *areas,
                 positions
                     .iter()
                     .cloned()
@@ -906,7 +1112,7 @@ mod test {
             ),]
             .iter()
             .map(|(areas, positions)| (
-                areas.clone(),
+                *areas,
                 positions
                     .iter()
                     .cloned()
@@ -984,8 +1190,8 @@ mod test {
             }
         });
         let count = 1;
-        let filled = matrix
-            .fill(Pos { col: 0, row: 0 }.into(), 1, |_| [].iter().cloned());
+        let filled =
+            matrix.fill(Pos { col: 0, row: 0 }, 1, |_| [].iter().cloned());
         assert_eq!(count, filled);
 
         for pos in matrix.positions() {
@@ -997,8 +1203,7 @@ mod test {
     fn fill_open() {
         let mut matrix = Matrix::new(10, 10);
         let count = matrix.width * matrix.height;
-        let filled =
-            matrix.fill(Pos { col: 0, row: 0 }.into(), 1, all_neighbors);
+        let filled = matrix.fill(Pos { col: 0, row: 0 }, 1, all_neighbors);
         assert_eq!(count, filled);
 
         for pos in matrix.positions() {
@@ -1016,8 +1221,7 @@ mod test {
                 |pos| if filter(pos) { 0 } else { 1 },
             );
         let count = matrix.values().filter(|&&v| v == 0).count();
-        let filled =
-            matrix.fill(Pos { col: 0, row: 0 }.into(), 1, all_neighbors);
+        let filled = matrix.fill(Pos { col: 0, row: 0 }, 1, all_neighbors);
         assert_eq!(count, filled);
 
         for pos in matrix.positions() {
@@ -1035,8 +1239,7 @@ mod test {
                 |pos| if filter(pos) { 0 } else { 1 },
             );
         let count = matrix.height * 2;
-        let filled =
-            matrix.fill(Pos { col: 0, row: 0 }.into(), 1, all_neighbors);
+        let filled = matrix.fill(Pos { col: 0, row: 0 }, 1, all_neighbors);
         assert_eq!(count, filled);
 
         for pos in matrix.positions() {
@@ -1051,9 +1254,7 @@ mod test {
     ///
     /// # Arguments
     /// *  `pos` - The cell position for which to generate neighbours.
-    fn all_neighbors(
-        pos: Pos,
-    ) -> impl Iterator<Item = Pos> + DoubleEndedIterator {
+    fn all_neighbors(pos: Pos) -> impl DoubleEndedIterator<Item = Pos> {
         vec![
             Pos {
                 col: pos.col,
@@ -46,6 +46,71 @@ impl Pos {
     pub fn value(self) -> f32 {
         self.x * self.x + self.y * self.y
     }
+
+    /// The distance between this position and another.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// assert_eq!(
+    ///     Pos { x: 1.0, y: 2.0 }.distance(Pos { x: 4.0, y: 6.0 }),
+    ///     5.0,
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The other position.
+    pub fn distance(self, other: Self) -> f32 {
+        (other - self).value().sqrt()
+    }
+
+    /// Interpolates linearly between this position and another.
+    ///
+    /// A value of `0.0` for `t` yields this position and a value of `1.0`
+    /// yields `other`. Values outside of _[0, 1]_ extrapolate along the line
+    /// through the positions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// let (a, b) = (Pos { x: 1.0, y: 2.0 }, Pos { x: 3.0, y: -2.0 });
+    /// assert_eq!(a.lerp(b, 0.0), a);
+    /// assert_eq!(a.lerp(b, 0.5), Pos { x: 2.0, y: 0.0 });
+    /// assert_eq!(a.lerp(b, 1.0), b);
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The other position.
+    /// *  `t` - The interpolation factor.
+    pub fn lerp(self, other: Self, t: f32) -> Self {
+        Pos {
+            x: self.x + (other.x - self.x) * t,
+            y: self.y + (other.y - self.y) * t,
+        }
+    }
+
+    /// The position halfway between this position and another.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical::*;
+    ///
+    /// assert_eq!(
+    ///     Pos { x: 1.0, y: 2.0 }.midpoint(Pos { x: 3.0, y: -2.0 }),
+    ///     Pos { x: 2.0, y: 0.0 },
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `other` - The other position.
+    pub fn midpoint(self, other: Self) -> Self {
+        self.lerp(other, 0.5)
+    }
 }
 
 impl<T> From<(T, T)> for Pos
@@ -10,9 +10,96 @@ where
     /// The returned value is the minimal rectangle that will contain this
     /// maze.
     pub fn viewbox(&self) -> physical::ViewBox {
-        self.shape().viewbox(self.width(), self.height())
+        let viewbox = self.shape().viewbox(self.width(), self.height());
+        let corner1 = self.transform(viewbox.corner);
+        let corner2 = self.transform(physical::Pos {
+            x: viewbox.corner.x + viewbox.width,
+            y: viewbox.corner.y + viewbox.height,
+        });
+        physical::ViewBox {
+            corner: physical::Pos {
+                x: corner1.x.min(corner2.x),
+                y: corner1.y.min(corner2.y),
+            },
+            width: (corner2.x - corner1.x).abs(),
+            height: (corner2.y - corner1.y).abs(),
+        }
+    }
+
+    /// Calculates the _view box_ for an object when rendered, translated so
+    /// that its corner is at the origin.
+    ///
+    /// The returned value is the tuple `(viewbox, offset)`, where `offset` is
+    /// the translation to add to physical positions of this maze, such as
+    /// room centres, to make them relative to the translated view box. All
+    /// translated positions inside of the maze have non-negative coordinates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::physical;
+    /// let maze = maze::Shape::Hex.create::<()>(5, 5);
+    /// let (viewbox, offset) = maze.normalized_viewbox();
+    ///
+    /// assert_eq!(physical::Pos { x: 0.0, y: 0.0 }, viewbox.corner);
+    /// assert_eq!(
+    ///     physical::Pos { x: 0.0, y: 0.0 },
+    ///     maze.viewbox().corner + offset,
+    /// );
+    /// ```
+    pub fn normalized_viewbox(&self) -> (physical::ViewBox, physical::Pos) {
+        let viewbox = self.viewbox();
+        (
+            physical::ViewBox {
+                corner: physical::Pos { x: 0.0, y: 0.0 },
+                ..viewbox
+            },
+            physical::Pos {
+                x: -viewbox.corner.x,
+                y: -viewbox.corner.y,
+            },
+        )
     }
 }
 
 #[cfg(feature = "svg")]
 pub mod svg;
+
+#[cfg(feature = "svg")]
+pub use self::svg::pipes;
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn normalized_viewbox(maze: TestMaze) {
+        let (viewbox, offset) = maze.normalized_viewbox();
+
+        assert_eq!(physical::Pos { x: 0.0, y: 0.0 }, viewbox.corner);
+        assert!(nearly_equal(maze.viewbox().width, viewbox.width));
+        assert!(nearly_equal(maze.viewbox().height, viewbox.height));
+        for pos in maze.positions() {
+            let center = maze.center(pos) + offset;
+            assert!(center.x >= 0.0 && center.y >= 0.0);
+            assert!(viewbox.contains(center), "{:?} for {:?}", center, pos);
+        }
+    }
+
+    #[maze_test]
+    fn normalized_viewbox_corners(maze: TestMaze) {
+        let (viewbox, offset) = maze.normalized_viewbox();
+        let viewbox = viewbox.expand(0.001);
+
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                let (start, end) = maze.corners((pos, wall));
+                assert!(viewbox.contains(start + offset));
+                assert!(viewbox.contains(end + offset));
+            }
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use svg::node::element::path::{Command, Position};
+use svg::Node;
 
 use crate::Maze;
 use crate::WallPos;
@@ -12,6 +13,88 @@ use crate::walk::*;
 pub trait ToPath {
     /// Generates an _SVG path d_ attribute value.
     fn to_path_d(&self) -> svg::node::element::path::Data;
+
+    /// Generates an _SVG path d_ attribute value with all coordinates rounded
+    /// to a number of decimals.
+    ///
+    /// See [`round`].
+    ///
+    /// # Arguments
+    /// *  `precision` - The number of decimals to keep.
+    fn to_rounded_path_d(
+        &self,
+        precision: usize,
+    ) -> svg::node::element::path::Data {
+        round(self.to_path_d(), precision)
+    }
+}
+
+/// Rounds all coordinates of an _SVG path d_ attribute value to a number of
+/// decimals.
+///
+/// Every coordinate is rounded independently to the nearest multiple of
+/// `10^-precision`, so corners shared by several lines always end up at the
+/// same point and no gaps appear.
+///
+/// # Example
+///
+/// ```
+/// # use maze::render::svg::round;
+/// let data = svg::node::element::path::Data::new()
+///     .move_to((0.123456, 1.0 / 3.0))
+///     .line_to((2.0, 0.996));
+///
+/// assert_eq!(
+///     "M0.12,0.33 L2,1",
+///     svg::node::Value::from(round(data, 2)).to_string(),
+/// );
+/// ```
+///
+/// # Arguments
+/// *  `data` - The path data to round.
+/// *  `precision` - The number of decimals to keep.
+pub fn round(
+    data: svg::node::element::path::Data,
+    precision: usize,
+) -> svg::node::element::path::Data {
+    let factor = 10f32.powi(precision as i32);
+    let round = |parameters: svg::node::element::path::Parameters| {
+        Vec::from(parameters)
+            .into_iter()
+            .map(|value| (value * factor).round() / factor)
+            .collect::<Vec<_>>()
+            .into()
+    };
+
+    svg::node::element::path::Data::from(
+        Vec::from(data)
+            .into_iter()
+            .map(|command| match command {
+                Command::Move(p, v) => Command::Move(p, round(v)),
+                Command::Line(p, v) => Command::Line(p, round(v)),
+                Command::HorizontalLine(p, v) => {
+                    Command::HorizontalLine(p, round(v))
+                }
+                Command::VerticalLine(p, v) => {
+                    Command::VerticalLine(p, round(v))
+                }
+                Command::QuadraticCurve(p, v) => {
+                    Command::QuadraticCurve(p, round(v))
+                }
+                Command::SmoothQuadraticCurve(p, v) => {
+                    Command::SmoothQuadraticCurve(p, round(v))
+                }
+                Command::CubicCurve(p, v) => Command::CubicCurve(p, round(v)),
+                Command::SmoothCubicCurve(p, v) => {
+                    Command::SmoothCubicCurve(p, round(v))
+                }
+                Command::EllipticalArc(p, v) => {
+                    Command::EllipticalArc(p, round(v))
+                }
+                Command::Close => Command::Close,
+            })
+            .collect::<Vec<_>>(),
+    )
 }
 
 impl<T> ToPath for Maze<T>
@@ -19,16 +102,64 @@ where
     T: Clone,
 {
     fn to_path_d(&self) -> svg::node::element::path::Data {
+        self.to_filtered_path_d(|_| true)
+    }
+}
+
+impl<T> Maze<T>
+where
+    T: Clone,
+{
+    /// Generates an _SVG path d_ attribute value for a subset of the closed
+    /// walls.
+    ///
+    /// The filter is only called for the sides of walls belonging to visited
+    /// rooms inside of the maze. A wall is drawn if `filter` returns `true`
+    /// for either of these sides, and shared walls are drawn only once. Walls
+    /// are followed into continuous lines as long as the filter accepts them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::render::svg::ToPath;
+    /// let maze = maze::Shape::Quad.create::<()>(5, 5).initialize(
+    ///     maze::initialize::Method::Winding,
+    ///     &mut maze::initialize::LFSR::new(12345),
+    /// );
+    ///
+    /// // Draw only the interior walls
+    /// let interior = maze.to_filtered_path_d(|(pos, wall)| {
+    ///     maze.is_inside(pos) && maze.is_inside(maze.back((pos, wall)).0)
+    /// });
+    /// assert!(interior.len() < maze.to_path_d().len());
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `filter` - A filter function selecting the walls to draw.
+    pub fn to_filtered_path_d<F>(
+        &self,
+        filter: F,
+    ) -> svg::node::element::path::Data
+    where
+        F: Fn(WallPos) -> bool,
+    {
         let mut commands = Vec::new();
         let mut visitor = Visitor::new(self);
+        let side = |wall_pos: WallPos| {
+            self.is_inside(wall_pos.0)
+                && self.rooms[wall_pos.0].visited
+                && filter(wall_pos)
+        };
+        let accepted = |wall_pos| side(wall_pos) || side(self.back(wall_pos));
 
         // While a non-visited wall still exists, walk along it
-        while let Some((next_pos, next_wall)) = visitor.next_wall() {
+        while let Some((next_pos, next_wall)) = visitor.next_wall(accepted) {
             for (i, (from, to)) in
                 self.follow_wall((next_pos, next_wall)).enumerate()
             {
-                // Ensure the wall has not been visited before
-                if visitor.visited(from) {
+                // Ensure the wall has not been visited before, and stop the
+                // line at walls not selected by the filter
+                if visitor.visited(from) || !accepted(from) {
                     break;
                 } else {
                     visitor.visit(from);
@@ -39,7 +170,8 @@ where
                 // one-wall line
                 if i == 0 {
                     if let Some(next) = to {
-                        let (_, pos) = corners(self, from, center(self, next));
+                        let (_, pos) =
+                            corners(self, from, self.wall_midpoint(next));
                         commands.push(Operation::Move(pos));
                     } else {
                         let (pos, _) = self.corners(from);
@@ -90,6 +222,126 @@ where
     }
 }
 
+impl<'a, T> Path<'a, T>
+where
+    T: Clone,
+{
+    /// Generates an _SVG path d_ attribute value with a smooth curve through
+    /// the centres of the rooms of this path.
+    ///
+    /// The curve is a Catmull-Rom spline converted to cubic Bézier segments.
+    /// The first and last room centres are used as their own neighbours, so
+    /// the curve does not overshoot its end points.
+    pub fn to_smooth_path_d(&self) -> svg::node::element::path::Data {
+        let points = self
+            .into_iter()
+            .map(|pos| self.maze.center(pos))
+            .collect::<Vec<_>>();
+        let point =
+            |i: isize| points[i.clamp(0, points.len() as isize - 1) as usize];
+
+        let mut commands = Vec::with_capacity(points.len());
+        if let Some(&first) = points.first() {
+            commands.push(Operation::Move(first).into());
+        }
+        for i in 1..points.len() as isize {
+            let (p0, p1, p2, p3) =
+                (point(i - 2), point(i - 1), point(i), point(i + 1));
+            commands.push(
+                Operation::Cubic(
+                    physical::Pos {
+                        x: p1.x + (p2.x - p0.x) / 6.0,
+                        y: p1.y + (p2.y - p0.y) / 6.0,
+                    },
+                    physical::Pos {
+                        x: p2.x - (p3.x - p1.x) / 6.0,
+                        y: p2.y - (p3.y - p1.y) / 6.0,
+                    },
+                    p2,
+                )
+                .into(),
+            );
+        }
+
+        svg::node::element::path::Data::from(commands)
+    }
+}
+
+/// Renders the passages of a maze as connected tubes.
+///
+/// Every open wall between two rooms inside of the maze becomes a tube from
+/// the centre of one room, through the centre of the wall, to the centre of
+/// the other room, and every visited room gets a circular junction cap. Since
+/// the tubes have round line caps of the same radius, the union of all
+/// elements looks like a network of pipes.
+///
+/// The tubes and caps are drawn with `currentColor`, so the colour is set
+/// with the `color` attribute of the returned group or one of its parents.
+///
+/// # Example
+///
+/// ```
+/// let maze = maze::Shape::Hex.create::<()>(5, 5).initialize(
+///     maze::initialize::Method::Winding,
+///     &mut maze::initialize::LFSR::new(12345),
+/// );
+/// let group = maze::render::pipes(&maze, 0.25).set("color", "teal");
+///
+/// assert!(group.to_string().contains("teal"));
+/// ```
+///
+/// # Arguments
+/// *  `maze` - The maze to render.
+/// *  `radius` - The radius of the tubes and caps.
+pub fn pipes<T>(maze: &Maze<T>, radius: f32) -> svg::node::element::Group
+where
+    T: Clone,
+{
+    let mut group = svg::node::element::Group::new()
+        .set("fill", "currentColor")
+        .set("stroke", "currentColor")
+        .set("stroke-width", 2.0 * radius)
+        .set("stroke-linecap", "round");
+
+    // Add every tube only once, from the lesser wall position
+    for pos in maze.positions() {
+        for wall in maze.doors(pos) {
+            let back = maze.back((pos, wall));
+            if !maze.is_inside(back.0)
+                || (pos, wall.index) > (back.0, back.1.index)
+            {
+                continue;
+            }
+
+            let (from, to) = (maze.center(pos), maze.center(back.0));
+            let data = svg::node::element::path::Data::from(vec![
+                Operation::Move(from).into(),
+                Operation::Line(maze.wall_midpoint((pos, wall))).into(),
+                Operation::Move(maze.wall_midpoint(back)).into(),
+                Operation::Line(to).into(),
+            ]);
+            group.append(
+                svg::node::element::Path::new()
+                    .set("fill", "none")
+                    .set("d", data),
+            );
+        }
+    }
+
+    for pos in maze.positions().filter(|&pos| maze[pos].visited) {
+        let center = maze.center(pos);
+        group.append(
+            svg::node::element::Circle::new()
+                .set("cx", center.x)
+                .set("cy", center.y)
+                .set("r", radius)
+                .set("stroke", "none"),
+        );
+    }
+
+    group
+}
+
 /// A visitor for wall positions.
 ///
 /// This struct provides means to visit all wall positions of a maze.
@@ -153,8 +405,14 @@ where
         }
     }
 
-    /// Returns the next non-visited wall.
-    fn next_wall(&mut self) -> Option<WallPos> {
+    /// Returns the next non-visited wall accepted by a filter.
+    ///
+    /// # Arguments
+    /// *  `filter` - A filter function for walls.
+    fn next_wall<F>(&mut self, filter: F) -> Option<WallPos>
+    where
+        F: Fn(WallPos) -> bool,
+    {
         while let Some(pos) = self.pos() {
             if let Some(next) = self
                 .maze
@@ -164,7 +422,7 @@ where
                 .filter(|&w| !self.maze.is_open((pos, w)))
                 .filter(|&w| !self.visited((pos, *w)))
                 .map(|&w| (pos, w))
-                .next()
+                .find(|&wall_pos| filter(wall_pos))
             {
                 return Some(next);
             } else {
@@ -212,13 +470,19 @@ enum Operation {
 
     /// Draw a line from the old position to this position.
     Line(physical::Pos),
+
+    /// Draw a cubic Bézier curve from the old position to the last position
+    /// using the first two positions as control points.
+    Cubic(physical::Pos, physical::Pos, physical::Pos),
 }
 
 impl Operation {
     /// Extracts the position from this operation regardless of type.
     fn pos(&self) -> physical::Pos {
         match *self {
-            Operation::Move(pos) | Operation::Line(pos) => pos,
+            Operation::Move(pos)
+            | Operation::Line(pos)
+            | Operation::Cubic(_, _, pos) => pos,
         }
     }
 }
@@ -233,27 +497,14 @@ impl From<Operation> for Command {
             Operation::Line(pos) => {
                 Command::Line(Position::Absolute, (pos.x, pos.y).into())
             }
+            Operation::Cubic(c1, c2, pos) => Command::CubicCurve(
+                Position::Absolute,
+                vec![c1.x, c1.y, c2.x, c2.y, pos.x, pos.y].into(),
+            ),
         }
     }
 }
 
-/// Returns the center of a wall.
-///
-/// The center of a wall is the point between its corners.
-///
-/// # Arguments
-/// *  `wall_pos` - The wall position.
-fn center<T>(maze: &Maze<T>, wall_pos: WallPos) -> physical::Pos
-where
-    T: Clone,
-{
-    let (corner1, corner2) = maze.corners(wall_pos);
-    physical::Pos {
-        x: (corner1.x + corner2.x) / 2.0,
-        y: (corner1.y + corner2.y) / 2.0,
-    }
-}
-
 /// Returns the physical positions of the two corners of a wall ordered by
 /// distance to another point.
 ///
@@ -277,3 +528,153 @@ where
         (pos2, pos1)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use maze_test::maze_test;
+
+    use super::*;
+    use crate::test_utils::*;
+
+    #[maze_test]
+    fn pipes_elements(maze: TestMaze) {
+        let maze = maze.initialize(
+            crate::initialize::Method::Braid,
+            &mut crate::initialize::LFSR::new(12345),
+        );
+        let output = pipes(&maze, 0.2).to_string();
+        let count = |name| {
+            svg::read(&output)
+                .unwrap()
+                .filter(|event| {
+                    matches!(
+                        event,
+                        svg::parser::Event::Tag(tag, _, _) if *tag == name,
+                    )
+                })
+                .count()
+        };
+
+        let open = maze
+            .positions()
+            .flat_map(|pos| maze.doors(pos).map(move |wall| (pos, wall)))
+            .filter(|&(pos, wall)| maze.is_inside(maze.back((pos, wall)).0))
+            .count();
+        assert_eq!(open / 2, count("path"));
+        assert_eq!(maze.positions().count(), count("circle"));
+    }
+
+    #[test]
+    fn filtered_walls_border() {
+        let maze = crate::Shape::Quad.create::<()>(3, 3).initialize_filter(
+            crate::initialize::Method::Winding,
+            &mut crate::initialize::LFSR::new(12345),
+            |pos| pos.col == 1 || pos.row == 1,
+        );
+        let border = maze.to_filtered_path_d(|wall_pos| {
+            let (back, _) = maze.back(wall_pos);
+            !(maze.is_inside(back) && maze[back].visited)
+        });
+
+        let outline = crate::analysis::outline(&maze).into_iter().fold(
+            svg::node::element::path::Data::new(),
+            |data, outline| {
+                outline
+                    .iter()
+                    .enumerate()
+                    .fold(data, |data, (i, pos)| {
+                        if i == 0 {
+                            data.move_to((pos.x, pos.y))
+                        } else {
+                            data.line_to((pos.x, pos.y))
+                        }
+                    })
+                    .close()
+            },
+        );
+        assert_eq!(segments(&outline), segments(&border));
+    }
+
+    #[test]
+    fn filtered_walls_unique() {
+        let maze = crate::Shape::Quad.create::<()>(5, 5).initialize(
+            crate::initialize::Method::Winding,
+            &mut crate::initialize::LFSR::new(12345),
+        );
+
+        // Accepting both sides of interior walls must not draw them twice
+        let interior = maze.to_filtered_path_d(|(pos, wall)| {
+            maze.is_inside(maze.back((pos, wall)).0)
+        });
+        let expected = maze
+            .positions()
+            .flat_map(|pos| maze.wall_positions(pos))
+            .filter(|&wall_pos| !maze.is_open(wall_pos))
+            .filter(|&wall_pos| maze.is_inside(maze.back(wall_pos).0))
+            .count()
+            / 2;
+        assert_eq!(expected, segments(&interior).len());
+        assert_eq!(
+            segments(&maze.to_path_d()),
+            segments(&maze.to_filtered_path_d(|_| true)),
+        );
+    }
+
+    /// The line segments of a path as sorted pairs of rounded points.
+    ///
+    /// Collinear lines are split at every wall of a quad maze, so that the
+    /// segments of two paths can be compared regardless of how they were
+    /// joined.
+    ///
+    /// # Arguments
+    /// *  `commands` - The path commands.
+    fn segments(commands: &[Command]) -> Vec<((i32, i32), (i32, i32))> {
+        let (a, b) = crate::Shape::Quad
+            .corners((matrix_pos(0, 0), crate::Shape::Quad.all_walls()[0]));
+        let unit = a.distance(b);
+        let point = |parameters: &svg::node::element::path::Parameters| {
+            (
+                (parameters[0] / unit).round() as i32,
+                (parameters[1] / unit).round() as i32,
+            )
+        };
+
+        let mut result = Vec::new();
+        let (mut start, mut current) = ((0, 0), (0, 0));
+        let mut add = |a: (i32, i32), b: (i32, i32)| {
+            let steps = (b.0 - a.0).abs().max((b.1 - a.1).abs());
+            for i in 0..steps {
+                let at = |i| {
+                    (
+                        a.0 + (b.0 - a.0) * i / steps,
+                        a.1 + (b.1 - a.1) * i / steps,
+                    )
+                };
+                let (p, q) = (at(i), at(i + 1));
+                result.push(if p < q { (p, q) } else { (q, p) });
+            }
+        };
+        for command in commands {
+            match command {
+                Command::Move(_, parameters) => {
+                    start = point(parameters);
+                    current = start;
+                }
+                Command::Line(_, parameters) => {
+                    let next = point(parameters);
+                    add(current, next);
+                    current = next;
+                }
+                Command::Close => {
+                    add(current, start);
+                    current = start;
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        result.sort();
+        result.dedup();
+        result
+    }
+}
@@ -91,6 +91,14 @@ where
         self.walls &= !wall.mask();
     }
 
+    /// Closes all walls and marks this room as not visited.
+    ///
+    /// The data is not modified.
+    pub fn reset(&mut self) {
+        self.walls = 0;
+        self.visited = false;
+    }
+
     /// Returns the number of open walls.
     pub fn open_walls(&self) -> usize {
         self.walls.count_ones() as usize
@@ -25,6 +25,17 @@ const VERTICAL_MULTIPLICATOR: f32 = 2.0 - SIN_30;
 /// The height of the top corner.
 const TOP_HEIGHT: f32 = 1.0 + SIN_30;
 
+/// The orientation of hex rooms.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexOrientation {
+    /// Rooms have a flat top and bottom, and columns are offset vertically.
+    FlatTop,
+
+    /// Rooms have a corner at the top and bottom, and rows are offset
+    /// horizontally. This is the default.
+    PointyTop,
+}
+
 // The walls are arranged in back-to-back pairs
 define_shape! {
     << Hex >>
@@ -345,6 +356,12 @@ pub fn walls(pos: matrix::Pos) -> &'static [&'static wall::Wall] {
     }
 }
 
+pub fn heuristic(a: matrix::Pos, b: matrix::Pos) -> f32 {
+    let ((aq, ar), (bq, br)) = (to_axial(a), to_axial(b));
+    let (dq, dr) = (aq - bq, ar - br);
+    ((dq.abs() + dr.abs() + (dq + dr).abs()) / 2) as f32
+}
+
 pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     physical::Pos {
         x: (pos.col as f32 + if pos.row & 1 == 1 { 0.5 } else { 1.0 })
@@ -394,7 +411,7 @@ pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
     }
 }
 
-#[cfg_attr(feature = "cargo-clippy", allow(clippy::collapsible_else_if))]
+#[allow(clippy::collapsible_else_if)]
 pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
     let matrix_pos = physical_to_cell(pos);
     let odd_row = matrix_pos.row & 1 == 1;
@@ -424,14 +441,137 @@ pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
     (matrix_pos, wall)
 }
 
+/// Converts a room position to axial hex coordinates.
+///
+/// Room positions use an offset layout where even rows are shifted half a room
+/// to the right. Axial coordinates `(q, r)` instead use a column axis slanted
+/// to follow the rows; the six neighbours of a room are then always offset by
+/// `(±1, 0)`, `(0, ±1)`, `(1, -1)` and `(-1, 1)`.
+///
+/// The orientation of a maze does not affect its room positions, so this
+/// conversion is valid for all orientations.
+///
+/// # Arguments
+/// *  `pos` - The room position.
+pub fn to_axial(pos: matrix::Pos) -> (i32, i32) {
+    let q = pos.col - (pos.row + (pos.row & 1)) / 2;
+    (q as i32, pos.row as i32)
+}
+
+/// Converts axial hex coordinates to a room position.
+///
+/// This is the inverse of [`to_axial`].
+///
+/// # Arguments
+/// *  `axial` - The axial coordinates `(q, r)`.
+pub fn from_axial((q, r): (i32, i32)) -> matrix::Pos {
+    let (q, r) = (q as isize, r as isize);
+    matrix::Pos {
+        col: q + (r + (r & 1)) / 2,
+        row: r,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use maze_test::maze_test;
 
     use super::walls;
+    use super::HexOrientation;
+    use super::{from_axial, to_axial};
+    use crate::physical;
     use crate::test_utils::*;
     use crate::WallPos;
 
+    #[maze_test(hex)]
+    fn flat_top_room_at(maze: TestMaze) {
+        let maze = maze.with_orientation(HexOrientation::FlatTop).unwrap();
+        assert_eq!(Some(HexOrientation::FlatTop), maze.orientation());
+        for pos in maze.positions() {
+            assert_eq!(pos, maze.room_at(maze.center(pos)));
+            assert_eq!(pos, maze.wall_pos_at(maze.center(pos)).0);
+        }
+    }
+
+    #[maze_test(hex)]
+    fn flat_top_corners(maze: TestMaze) {
+        let maze = maze.with_orientation(HexOrientation::FlatTop).unwrap();
+        let pos = matrix_pos(2, 2);
+        let center = maze.center(pos);
+        let (top, bottom) = maze
+            .walls(pos)
+            .iter()
+            .map(|&wall| maze.corners((pos, wall)))
+            .fold((0, 0), |(top, bottom), (a, b)| {
+                let flat = (a.y - b.y).abs() < 0.00001;
+                (
+                    top + (flat && a.y < center.y) as usize,
+                    bottom + (flat && a.y > center.y) as usize,
+                )
+            });
+        assert_eq!((1, 1), (top, bottom));
+    }
+
+    #[maze_test(hex)]
+    fn flat_top_viewbox(maze: TestMaze) {
+        let pointy = maze.viewbox();
+        let flat = maze
+            .with_orientation(HexOrientation::FlatTop)
+            .unwrap()
+            .viewbox();
+        assert!(is_close(
+            physical::Pos {
+                x: pointy.width,
+                y: pointy.height,
+            },
+            physical::Pos {
+                x: flat.height,
+                y: flat.width,
+            },
+        ));
+        assert_ne!(pointy.width / pointy.height, flat.width / flat.height);
+    }
+
+    #[maze_test(quad, tri)]
+    fn orientation_unsupported(maze: TestMaze) {
+        assert_eq!(None, maze.orientation());
+        assert!(maze.with_orientation(HexOrientation::FlatTop).is_err());
+    }
+
+    #[test]
+    fn axial_round_trip() {
+        for row in -5..=5 {
+            for col in -5..=5 {
+                let pos = matrix_pos(col, row);
+                assert_eq!(pos, from_axial(to_axial(pos)));
+            }
+        }
+        assert_eq!((0, 0), to_axial(matrix_pos(0, 0)));
+        assert_eq!((-1, 1), to_axial(matrix_pos(0, 1)));
+        assert_eq!((-1, 2), to_axial(matrix_pos(0, 2)));
+    }
+
+    #[maze_test(hex)]
+    fn axial_neighbours(maze: TestMaze) {
+        let mut expected =
+            vec![(1, 0), (-1, 0), (0, 1), (0, -1), (1, -1), (-1, 1)];
+        expected.sort();
+        for pos in maze.positions() {
+            let (q, r) = to_axial(pos);
+            let mut actual = maze
+                .walls(pos)
+                .iter()
+                .map(|&wall| {
+                    let (next, _) = maze.back((pos, wall));
+                    let (nq, nr) = to_axial(next);
+                    (nq - q, nr - r)
+                })
+                .collect::<Vec<_>>();
+            actual.sort();
+            assert_eq!(expected, actual, "for {:?}", pos);
+        }
+    }
+
     #[maze_test(hex)]
     fn back(maze: TestMaze) {
         assert_eq!(
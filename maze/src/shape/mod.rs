@@ -22,7 +22,7 @@ const COS_45: f32 = 0.5 * SQRT_2;
 const SIN_45: f32 = 0.5 * SQRT_2;
 
 /// The different types of mazes implemented, identified by number of walls.
-#[derive(Clone, Copy, Debug, Hash, PartialEq, PartialOrd)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd)]
 #[cfg_attr(
     feature = "serde",
     derive(Deserialize, Serialize),
@@ -37,8 +37,19 @@ pub enum Shape {
 
     /// A maze with hexagonal rooms.
     Hex = 6,
+
+    /// An experimental maze with quadratic rooms also connected to their
+    /// diagonal neighbours.
+    ///
+    /// See [`quad_diagonal`] for a description of the geometry.
+    #[cfg_attr(feature = "serde", serde(rename = "quad-diagonal"))]
+    QuadDiagonal = 8,
 }
 
+/// The factor by which [`Shape::minimal_dimensions_with_mask`] may grow the
+/// minimal dimensions of a maze before giving up.
+pub const MASK_GROWTH_LIMIT: usize = 16;
+
 impl Shape {
     /// Creates a maze of this type.
     ///
@@ -52,6 +63,24 @@ impl Shape {
         Maze::new(self, width, height)
     }
 
+    /// Creates a maze of this type with all rooms marked as visited.
+    ///
+    /// Unlike [`create`](Self::create), which leaves rooms unvisited until an
+    /// initialiser has carved passages through them, this treats the entire
+    /// grid as part of the maze. All walls are still closed. This is useful
+    /// when rendering rooms, since renderers ignore unvisited rooms.
+    ///
+    /// # Arguments
+    /// *  `width` - The width, in rooms, of the maze.
+    /// *  `height` - The height, in rooms, of the maze.
+    pub fn create_visited(self, width: usize, height: usize) -> Maze<()> {
+        let mut maze = self.create(width, height);
+        for pos in maze.rooms.positions() {
+            maze.rooms[pos].visited = true;
+        }
+        maze
+    }
+
     /// Creates a fully initialised maze of this type.
     ///
     /// # Arguments
@@ -87,11 +116,134 @@ impl Shape {
         dispatch!(self => minimal_dimensions(width, height))
     }
 
+    /// Calculates the minimal dimensions for a maze with a specific aspect.
+    ///
+    /// See [`Shape::minimal_dimensions`] and [`Maze::with_aspect`].
+    ///
+    /// # Arguments
+    /// *  `width` - The required physical width.
+    /// *  `height` - The required physical height.
+    /// *  `aspect` - The horizontal and vertical scale of rooms.
+    pub fn minimal_dimensions_with_aspect(
+        self,
+        width: f32,
+        height: f32,
+        aspect: (f32, f32),
+    ) -> (usize, usize) {
+        self.minimal_dimensions(width / aspect.0, height / aspect.1)
+    }
+
+    /// Calculates the minimal dimensions for a masked maze to let the outline
+    /// of the mask be `width` wide and `height` tall.
+    ///
+    /// The mask is stretched over the entire maze, like a mask image: a room
+    /// is part of the maze if `mask` returns `true` for the centre of the room,
+    /// expressed as a fraction of the width and height of the view box. The
+    /// size of the mask is the bounding box of its outline, as calculated by
+    /// [`crate::analysis::outline`].
+    ///
+    /// If the mask selects no rooms even for dimensions
+    /// [`MASK_GROWTH_LIMIT`] times those returned by
+    /// [`Shape::minimal_dimensions`], `None` is returned.
+    ///
+    /// # Arguments
+    /// *  `width` - The required physical width of the mask.
+    /// *  `height` - The required physical height of the mask.
+    /// *  `mask` - A predicate for the relative room centres to include.
+    pub fn minimal_dimensions_with_mask<F>(
+        self,
+        width: f32,
+        height: f32,
+        mask: F,
+    ) -> Option<(usize, usize)>
+    where
+        F: Fn(physical::Pos) -> bool,
+    {
+        // The mask can never be larger than the full maze, so we start with
+        // the minimal dimensions and grow until the outline is large enough
+        let (mut w, mut h) = self.minimal_dimensions(width, height);
+        let (limit_w, limit_h) = (w * MASK_GROWTH_LIMIT, h * MASK_GROWTH_LIMIT);
+        while w <= limit_w && h <= limit_h {
+            let maze = self.create_masked(w, h, &mask);
+            let corners = crate::analysis::outline(&maze)
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            if corners.is_empty() {
+                w += 1;
+                h += 1;
+                continue;
+            }
+            let extent = |value: fn(&physical::Pos) -> f32| {
+                let values = corners.iter().map(value);
+                values.clone().fold(f32::MIN, f32::max)
+                    - values.fold(f32::MAX, f32::min)
+            };
+
+            let (too_narrow, too_low) =
+                (extent(|p| p.x) < width, extent(|p| p.y) < height);
+            if !too_narrow && !too_low {
+                return Some((w, h));
+            }
+            w += usize::from(too_narrow);
+            h += usize::from(too_low);
+        }
+
+        None
+    }
+
+    /// Creates a maze where only the rooms selected by a mask are visited.
+    ///
+    /// See [`Shape::minimal_dimensions_with_mask`].
+    ///
+    /// # Arguments
+    /// *  `width` - The width, in rooms, of the maze.
+    /// *  `height` - The height, in rooms, of the maze.
+    /// *  `mask` - A predicate for the relative room centres to include.
+    fn create_masked<F>(self, width: usize, height: usize, mask: &F) -> Maze<()>
+    where
+        F: Fn(physical::Pos) -> bool,
+    {
+        let mut maze = self.create::<()>(width, height);
+        let viewbox = maze.viewbox();
+        for pos in maze.positions() {
+            let center = maze.center(pos);
+            maze.rooms[pos].visited = mask(physical::Pos {
+                x: (center.x - viewbox.corner.x) / viewbox.width,
+                y: (center.y - viewbox.corner.y) / viewbox.height,
+            });
+        }
+
+        maze
+    }
+
     /// Returns all walls for a shape.
     pub fn all_walls(self) -> &'static [&'static wall::Wall] {
         dispatch!(self => all_walls())
     }
 
+    /// Finds the wall of this shape with a specific index.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use maze::shape::*;
+    ///
+    /// assert_eq!(
+    ///     Shape::Quad.wall_by_index(quad::walls::UP.index).unwrap().name,
+    ///     "Quad:UP",
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    /// *  `index` - The wall index, as found in [`wall::Wall::index`].
+    pub fn wall_by_index(
+        self,
+        index: wall::Index,
+    ) -> Option<&'static wall::Wall> {
+        self.all_walls().get(index).copied()
+    }
+
     /// Returns the back of a wall.
     ///
     /// The back is the other side of the wall, located in a neighbouring room.
@@ -121,6 +273,19 @@ impl Shape {
         dispatch!(self => walls(pos))
     }
 
+    /// Estimates the number of steps between two rooms.
+    ///
+    /// The estimate is the number of steps in a maze with all walls open, or
+    /// a lower bound of it, and thus never exceeds the length of an actual
+    /// path. This makes it an admissible heuristic for path finding.
+    ///
+    /// # Arguments
+    /// *  `a` - One room position.
+    /// *  `b` - Another room position.
+    pub fn heuristic(self, a: matrix::Pos, b: matrix::Pos) -> f32 {
+        dispatch!(self => heuristic(a, b))
+    }
+
     /// Converts a physical position to a matrix cell.
     ///
     /// # Arguments
@@ -145,6 +310,66 @@ impl Shape {
         dispatch!(self => cell_to_physical(pos))
     }
 
+    /// Returns the physical positions of the two corners of a wall.
+    ///
+    /// This is the geometry of a maze with unit aspect that is not
+    /// transposed; see [`Maze::corners`](crate::Maze::corners).
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The wall position.
+    pub fn corners(self, wall_pos: WallPos) -> (physical::Pos, physical::Pos) {
+        let center = self.cell_to_physical(wall_pos.0);
+        (center + wall_pos.1.span.0, center + wall_pos.1.span.1)
+    }
+
+    /// The area of a single room.
+    ///
+    /// This is the area of the polygon formed by the corners of the walls of
+    /// a room, in the units of a maze with unit aspect; all rooms of a shape
+    /// have the same area. Rooms are regular polygons with a distance of `1.0`
+    /// from their centre to their corners, which makes the area of a quad
+    /// room `2.0`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// assert!((maze::Shape::Quad.room_area() - 2.0).abs() < 1e-5);
+    /// ```
+    pub fn room_area(self) -> f32 {
+        let corners = self.room_corners();
+        corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .map(|(a, b)| a.x * b.y - b.x * a.y)
+            .sum::<f32>()
+            .abs()
+            / 2.0
+    }
+
+    /// The perimeter of a single room.
+    ///
+    /// This is the total length of the walls of a room, in the units of a
+    /// maze with unit aspect; see [`Shape::room_area`].
+    pub fn room_perimeter(self) -> f32 {
+        let corners = self.room_corners();
+        corners
+            .iter()
+            .zip(corners.iter().cycle().skip(1))
+            .map(|(&a, &b)| a.distance(b))
+            .sum()
+    }
+
+    /// The corners of a room relative to its centre, in wall order.
+    fn room_corners(self) -> Vec<physical::Pos> {
+        self.walls(matrix::Pos { col: 0, row: 0 })
+            .iter()
+            .map(|wall| physical::Pos {
+                x: wall.span.0.dx,
+                y: wall.span.0.dy,
+            })
+            .collect()
+    }
+
     /// Calculates the _view box_ for a maze with this shape when rendered.
     ///
     /// The returned value is the minimal rectangle that will contain a maze
@@ -154,8 +379,7 @@ impl Shape {
     /// *  `cols` - The number of columns in the matrix.
     /// *  `rows` - The number of rows in the matrix.
     pub fn viewbox(self, cols: usize, rows: usize) -> physical::ViewBox {
-        let mut window =
-            (std::f32::MAX, std::f32::MAX, std::f32::MIN, std::f32::MIN);
+        let mut window = (f32::MAX, f32::MAX, f32::MIN, f32::MIN);
         for y in 0..rows {
             let lpos = matrix::Pos {
                 col: 0,
@@ -215,6 +439,7 @@ impl TryFrom<u32> for Shape {
             x if x == Shape::Tri as u32 => Ok(Shape::Tri),
             x if x == Shape::Quad as u32 => Ok(Shape::Quad),
             x if x == Shape::Hex as u32 => Ok(Shape::Hex),
+            x if x == Shape::QuadDiagonal as u32 => Ok(Shape::QuadDiagonal),
             _ => Err(source),
         }
     }
@@ -247,12 +472,13 @@ impl std::fmt::Display for Shape {
             Tri => write!(f, "tri"),
             Quad => write!(f, "quad"),
             Hex => write!(f, "hex"),
+            QuadDiagonal => write!(f, "quad-diagonal"),
         }
     }
 }
 
 impl std::str::FromStr for Shape {
-    type Err = String;
+    type Err = crate::ParseError;
 
     /// Converts a string to a maze type.
     ///
@@ -285,7 +511,8 @@ impl std::str::FromStr for Shape {
             "tri" => Ok(Shape::Tri),
             "quad" => Ok(Shape::Quad),
             "hex" => Ok(Shape::Hex),
-            e => Err(e.to_owned()),
+            "quad-diagonal" => Ok(Shape::QuadDiagonal),
+            e => Err(crate::ParseError::UnknownShape(e.to_owned())),
         }
     }
 }
@@ -310,7 +537,8 @@ where
     /// # Arguments
     /// *  `wall_pos` - The wall position.
     pub fn back(&self, wall_pos: WallPos) -> WallPos {
-        self.shape.back(wall_pos)
+        let (pos, wall) = self.shape.back(wall_pos);
+        (self.wrap(pos), wall)
     }
 
     /// The opposite of a wall.
@@ -324,6 +552,34 @@ where
         self.shape.opposite(wall_pos)
     }
 
+    /// The wall most nearly opposite a wall.
+    ///
+    /// For mazes with rooms with an even number of walls, this is the same
+    /// wall as returned by [`Maze::opposite`]. Otherwise, it is the wall whose
+    /// midpoint is farthest from the midpoint of the wall. If several walls
+    /// are equally far away, the first one in the order of [`Maze::walls`] is
+    /// returned.
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The wall position.
+    pub fn reflect_wall(&self, wall_pos: WallPos) -> WallPos {
+        let (pos, wall) = wall_pos;
+        if let Some(opposite) = self.opposite(wall_pos) {
+            return (pos, opposite);
+        }
+
+        let origin = self.wall_midpoint(wall_pos);
+        let mut result = (wall, 0.0);
+        for &other in self.walls(pos).iter().filter(|&&other| other != wall) {
+            let distance = (self.wall_midpoint((pos, other)) - origin).value();
+            if distance > result.1 {
+                result = (other, distance);
+            }
+        }
+
+        (pos, result.0)
+    }
+
     /// All walls of a specific room.
     ///
     /// # Arguments
@@ -332,12 +588,88 @@ where
         self.shape.walls(pos)
     }
 
+    /// All walls of a specific room, ordered by angle.
+    ///
+    /// The walls are sorted by the start angle of their spans, normalised to
+    /// _[0, 2𝜋)_, so they are visited clockwise starting from the positive _x_
+    /// axis. Unlike [`Maze::walls`], whose order is not necessarily angular,
+    /// the span of each wall thus ends where the span of the next one starts.
+    ///
+    /// # Arguments
+    /// *  `pos` - The room position.
+    pub fn walls_cw(&self, pos: matrix::Pos) -> Vec<&'static wall::Wall> {
+        let mut walls = self.walls(pos).to_vec();
+        walls.sort_by(|a, b| {
+            wall::Wall::normalized_angle(a.span.0.a)
+                .total_cmp(&wall::Wall::normalized_angle(b.span.0.a))
+        });
+        walls
+    }
+
     /// The physical centre of a matrix position.
     ///
     /// # Arguments
     /// *  `pos` - The matrix position.
     pub fn center(&self, pos: matrix::Pos) -> physical::Pos {
-        self.shape.cell_to_physical(pos)
+        self.transform(self.shape.cell_to_physical(pos))
+    }
+
+    /// The physical centres of all rooms.
+    ///
+    /// The geometry of a maze never changes after creation, so callers
+    /// looking up the centres of many rooms repeatedly, such as renderers,
+    /// may calculate this matrix once and then index it instead of calling
+    /// [`Maze::center`] for every lookup.
+    pub fn center_cache(&self) -> matrix::Matrix<physical::Pos> {
+        matrix::Matrix::new_with_data(self.width(), self.height(), |pos| {
+            self.center(pos)
+        })
+    }
+
+    /// Transforms a position relative to the shape to a physical position.
+    ///
+    /// The aspect of this maze is applied, and the axes are swapped for
+    /// transposed mazes.
+    ///
+    /// # Arguments
+    /// *  `pos` - The position to transform.
+    pub(crate) fn transform(&self, pos: physical::Pos) -> physical::Pos {
+        let pos = physical::Pos {
+            x: pos.x * self.aspect.0,
+            y: pos.y * self.aspect.1,
+        };
+        if self.transposed {
+            physical::Pos { x: pos.y, y: pos.x }
+        } else {
+            pos
+        }
+    }
+
+    /// Transforms a physical position to a position relative to the shape.
+    ///
+    /// This is the inverse of [`Maze::transform`].
+    ///
+    /// # Arguments
+    /// *  `pos` - The physical position.
+    pub(crate) fn untransform(&self, pos: physical::Pos) -> physical::Pos {
+        let pos = if self.transposed {
+            physical::Pos { x: pos.y, y: pos.x }
+        } else {
+            pos
+        };
+        physical::Pos {
+            x: pos.x / self.aspect.0,
+            y: pos.y / self.aspect.1,
+        }
+    }
+
+    /// The physical midpoint of a wall.
+    ///
+    /// # Arguments
+    /// *  `wall_pos` - The wall position.
+    pub(crate) fn wall_midpoint(&self, wall_pos: WallPos) -> physical::Pos {
+        let (start, end) = self.corners(wall_pos);
+        start.midpoint(end)
     }
 
     /// The matrix position whose centre is closest to a physical position.
@@ -348,7 +680,7 @@ where
     /// # Arguments
     /// *  `pos` - The physical position.
     pub fn room_at(&self, pos: physical::Pos) -> matrix::Pos {
-        self.shape.physical_to_cell(pos)
+        self.shape.physical_to_cell(self.untransform(pos))
     }
 
     /// The matrix position whose centre is closest to a physical position
@@ -360,7 +692,28 @@ where
     /// # Arguments
     /// *  `pos` - The physical position.
     pub fn wall_pos_at(&self, pos: physical::Pos) -> WallPos {
-        self.shape.physical_to_wall_pos(pos)
+        self.shape.physical_to_wall_pos(self.untransform(pos))
+    }
+
+    /// Toggles the wall closest to a physical position.
+    ///
+    /// The wall is found using [`Maze::wall_pos_at`], so a position close to
+    /// the centre of a room still resolves to one of its walls. The return
+    /// value is the wall that was toggled, expressed from a room inside of
+    /// the maze, or `None` if neither side of the wall is inside of the maze.
+    ///
+    /// # Arguments
+    /// *  `pos` - The physical position.
+    pub fn toggle_wall_at(&mut self, pos: physical::Pos) -> Option<WallPos> {
+        let wall_pos = self.wall_pos_at(pos);
+        let wall_pos = if self.is_inside(wall_pos.0) {
+            wall_pos
+        } else {
+            Some(self.back(wall_pos)).filter(|&(pos, _)| self.is_inside(pos))?
+        };
+
+        self.set_open(wall_pos, !self.is_open(wall_pos));
+        Some(wall_pos)
     }
 
     /// Yields all rooms that are touched by the rectangle described.
@@ -400,10 +753,7 @@ where
                     || self
                         .walls(pos)
                         .iter()
-                        .map(|wall| physical::Pos {
-                            x: center.x + wall.span.0.dx,
-                            y: center.y + wall.span.0.dy,
-                        })
+                        .map(|wall| self.corners((pos, wall)).0)
                         .any(|pos| {
                             pos.x >= left
                                 && pos.y >= top
@@ -455,8 +805,36 @@ pub fn surround(
     top.chain(right).chain(bottom).chain(left)
 }
 
+/// Finds a wall of any shape by its name.
+///
+/// Wall names are on the form `"Shape:WALL"`, as found in
+/// [`wall::Wall::name`].
+///
+/// # Example
+///
+/// ```
+/// # use maze::shape::*;
+///
+/// assert_eq!(
+///     wall_by_name("Hex:UP_LEFT0").map(|wall| wall.shape),
+///     Some(Shape::Hex),
+/// );
+/// assert!(wall_by_name("Hex:NOWHERE").is_none());
+/// ```
+///
+/// # Arguments
+/// *  `name` - The name of the wall.
+pub fn wall_by_name(name: &str) -> Option<&'static wall::Wall> {
+    [Shape::Hex, Shape::Quad, Shape::QuadDiagonal, Shape::Tri]
+        .iter()
+        .flat_map(|shape| shape.all_walls().iter())
+        .find(|wall| wall.name == name)
+        .copied()
+}
+
 pub mod hex;
 pub mod quad;
+pub mod quad_diagonal;
 pub mod tri;
 
 #[cfg(test)]
@@ -556,12 +934,234 @@ mod tests {
         );
     }
 
+    #[test]
+    fn shape_create() {
+        for shape in [Shape::Hex, Shape::Quad, Shape::Tri] {
+            let maze = shape.create::<()>(7, 4);
+            assert_eq!(shape, maze.shape());
+            assert_eq!((7, 4), (maze.width(), maze.height()));
+        }
+    }
+
     #[test]
     fn shape_from_str() {
         assert_eq!("tri".parse(), Ok(Shape::Tri),);
         assert_eq!("quad".parse(), Ok(Shape::Quad),);
         assert_eq!("hex".parse(), Ok(Shape::Hex),);
-        assert_eq!("invalid".parse::<Shape>(), Err("invalid".to_owned()));
+        assert_eq!("quad-diagonal".parse(), Ok(Shape::QuadDiagonal),);
+        assert_eq!(
+            "invalid".parse::<Shape>(),
+            Err(crate::ParseError::UnknownShape("invalid".to_owned())),
+        );
+    }
+
+    #[maze_test]
+    fn reflect_wall_opposite(maze: TestMaze) {
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                if let Some(opposite) = maze.opposite((pos, wall)) {
+                    assert_eq!((pos, opposite), maze.reflect_wall((pos, wall)));
+                }
+            }
+        }
+    }
+
+    #[maze_test(tri)]
+    fn reflect_wall_farthest(maze: TestMaze) {
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                let (_, reflected) = maze.reflect_wall((pos, wall));
+                assert_ne!(wall, reflected);
+
+                let origin = maze.wall_midpoint((pos, wall));
+                let distance =
+                    |other| (maze.wall_midpoint((pos, other)) - origin).value();
+                assert!(maze.walls(pos).iter().all(|&other| {
+                    distance(other) <= distance(reflected) + 0.00001
+                }));
+            }
+        }
+    }
+
+    #[maze_test(quad)]
+    fn aspect_room_at(maze: TestMaze) {
+        let maze = maze.with_aspect((2.0, 1.0)).unwrap();
+        for pos in maze.positions() {
+            assert_eq!(pos, maze.room_at(maze.center(pos)));
+            for &wall in maze.walls(pos) {
+                let (corner, _) = maze.corners((pos, wall));
+                assert!((corner.x - maze.center(pos).x).abs() > 0.99);
+            }
+        }
+    }
+
+    #[maze_test(quad)]
+    fn aspect_viewbox(maze: TestMaze) {
+        let unit = maze.viewbox();
+        let scaled = maze.clone().with_aspect((2.0, 0.5)).unwrap().viewbox();
+        assert!(nearly_equal(unit.width * 2.0, scaled.width));
+        assert!(nearly_equal(unit.height * 0.5, scaled.height));
+    }
+
+    #[maze_test(quad)]
+    fn aspect_minimal_dimensions(maze: TestMaze) {
+        let aspect = (2.0, 1.0);
+        let (w, h) = maze
+            .shape()
+            .minimal_dimensions_with_aspect(10.0, 10.0, aspect);
+        let viewbox = maze
+            .shape()
+            .create::<()>(w, h)
+            .with_aspect(aspect)
+            .unwrap()
+            .viewbox();
+        assert!(viewbox.width >= 10.0 && viewbox.height >= 10.0);
+        assert!(w < h);
+    }
+
+    #[maze_test(hex, tri)]
+    fn aspect_unsupported(maze: TestMaze) {
+        assert!(maze.clone().with_aspect((2.0, 1.0)).is_err());
+        assert!(maze.with_aspect((1.0, 1.0)).is_ok());
+    }
+
+    #[maze_test]
+    fn aspect_invalid(maze: TestMaze) {
+        assert!(maze.with_aspect((0.0, 1.0)).is_err());
+    }
+
+    #[maze_test]
+    fn center_cache(maze: TestMaze) {
+        let cache = maze.center_cache();
+        assert_eq!((maze.width(), maze.height()), (cache.width, cache.height),);
+        for pos in maze.positions() {
+            assert!(is_close(maze.center(pos), cache[pos]));
+        }
+    }
+
+    #[maze_test(quad)]
+    fn center_cache_aspect(maze: TestMaze) {
+        let maze = maze.with_aspect((2.0, 0.5)).unwrap();
+        let cache = maze.center_cache();
+        for pos in maze.positions() {
+            assert!(is_close(maze.center(pos), cache[pos]));
+        }
+    }
+
+    #[maze_test]
+    fn shape_corners(maze: TestMaze) {
+        for row in -1..=maze.height() as isize {
+            for col in -1..=maze.width() as isize {
+                let pos = matrix_pos(col, row);
+                for &wall in maze.walls(pos) {
+                    assert_eq!(
+                        maze.corners((pos, wall)),
+                        maze.shape().corners((pos, wall)),
+                    );
+                }
+            }
+        }
+    }
+
+    #[maze_test]
+    fn walls_cw_contiguous(maze: TestMaze) {
+        use std::f32::consts::TAU;
+
+        for pos in maze.positions() {
+            let walls = maze.walls_cw(pos);
+            assert_eq!(maze.walls(pos).len(), walls.len());
+            for (i, current) in walls.iter().enumerate() {
+                let next = walls[(i + 1) % walls.len()];
+                let d = wall::Wall::normalized_angle(
+                    next.span.0.a - current.span.1.a,
+                );
+                assert!(
+                    d < 0.0001 || TAU - d < 0.0001,
+                    "{:?} is not followed by {:?} at {:?}",
+                    current,
+                    next,
+                    pos,
+                );
+            }
+            assert!(walls.windows(2).all(|pair| {
+                wall::Wall::normalized_angle(pair[0].span.0.a)
+                    < wall::Wall::normalized_angle(pair[1].span.0.a)
+            }));
+        }
+    }
+
+    #[maze_test(quad)]
+    fn shape_corners_aspect(maze: TestMaze) {
+        let maze = maze.with_aspect((2.0, 0.5)).unwrap();
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                let (a, b) = maze.shape().corners((pos, wall));
+                let (c, d) = maze.corners((pos, wall));
+                assert!(nearly_equal(a.x * 2.0, c.x));
+                assert!(nearly_equal(a.y * 0.5, c.y));
+                assert!(nearly_equal(b.x * 2.0, d.x));
+                assert!(nearly_equal(b.y * 0.5, d.y));
+            }
+        }
+    }
+
+    #[maze_test]
+    fn create_visited(maze: TestMaze) {
+        let maze = maze.shape().create_visited(maze.width(), maze.height());
+
+        for pos in maze.positions() {
+            assert!(maze[pos].visited);
+            assert_eq!(0, maze[pos].open_walls());
+        }
+    }
+
+    #[maze_test]
+    fn toggle_wall_at_twice(maze: TestMaze) {
+        let mut maze = maze.initialize(
+            crate::initialize::Method::Winding,
+            &mut crate::initialize::LFSR::new(12345),
+        );
+        let original = crate::export::to_bitmask_matrix(&maze);
+
+        for pos in maze.positions().collect::<Vec<_>>() {
+            let center = maze.center(pos);
+            let wall_pos = maze.toggle_wall_at(center).unwrap();
+            assert_ne!(
+                crate::export::to_bitmask_matrix(&maze),
+                original,
+                "for {:?}",
+                wall_pos,
+            );
+            assert_eq!(Some(wall_pos), maze.toggle_wall_at(center));
+            assert_eq!(crate::export::to_bitmask_matrix(&maze), original);
+        }
+    }
+
+    #[maze_test]
+    fn toggle_wall_at_midpoint(maze: TestMaze) {
+        let mut maze = maze;
+        let pos = matrix_pos(1, 1);
+        for &wall in maze.walls(pos) {
+            let (center, midpoint) =
+                (maze.center(pos), maze.wall_midpoint((pos, wall)));
+            let click = physical::Pos {
+                x: 0.2 * center.x + 0.8 * midpoint.x,
+                y: 0.2 * center.y + 0.8 * midpoint.y,
+            };
+            assert_eq!(Some((pos, wall)), maze.toggle_wall_at(click));
+            assert!(maze.is_open((pos, wall)));
+            assert!(maze.is_open(maze.back((pos, wall))));
+        }
+    }
+
+    #[maze_test]
+    fn toggle_wall_at_outside(maze: TestMaze) {
+        let mut maze = maze;
+        let original = crate::export::to_bitmask_matrix(&maze);
+        let far = maze.center(matrix_pos(-5, -5));
+
+        assert_eq!(None, maze.toggle_wall_at(far));
+        assert_eq!(crate::export::to_bitmask_matrix(&maze), original);
     }
 
     #[maze_test]
@@ -608,6 +1208,49 @@ mod tests {
         }
     }
 
+    #[maze_test]
+    fn minimal_dimensions_with_mask(maze: TestMaze) {
+        // A centred disc, with a radius of a third of the maze
+        let mask = |pos: physical::Pos| {
+            (pos.x - 0.5).powi(2) + (pos.y - 0.5).powi(2) < 1.0 / 9.0
+        };
+        for size in [4.0, 10.0, 25.0] {
+            let (w, h) = maze
+                .shape
+                .minimal_dimensions_with_mask(size, size, mask)
+                .unwrap();
+            let full = maze.shape.minimal_dimensions(size, size);
+            assert!(w >= full.0 && h >= full.1);
+
+            let masked = maze.shape.create_masked(w, h, &mask);
+            let corners = crate::analysis::outline(&masked)
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>();
+            let xs = corners.iter().map(|p| p.x);
+            let ys = corners.iter().map(|p| p.y);
+            let actual_width = xs.clone().fold(f32::MIN, f32::max)
+                - xs.fold(f32::MAX, f32::min);
+            let actual_height = ys.clone().fold(f32::MIN, f32::max)
+                - ys.fold(f32::MAX, f32::min);
+
+            // The size is reached, and not exceeded by more than a few rooms
+            let tolerance = 0.15 * size + 2.0;
+            assert!(actual_width >= size, "{} < {}", actual_width, size);
+            assert!(actual_height >= size, "{} < {}", actual_height, size);
+            assert!(actual_width < size + tolerance, "{}", actual_width);
+            assert!(actual_height < size + tolerance, "{}", actual_height);
+        }
+    }
+
+    #[test]
+    fn minimal_dimensions_with_empty_mask() {
+        assert_eq!(
+            None,
+            Shape::Quad.minimal_dimensions_with_mask(5.0, 5.0, |_| false),
+        );
+    }
+
     #[maze_test]
     fn room_at(maze: TestMaze) {
         let d = 0.95;
@@ -625,6 +1268,45 @@ mod tests {
         }
     }
 
+    #[maze_test]
+    fn room_at_interior(maze: TestMaze) {
+        // Rooms are convex, so every point between the centre and a point on
+        // a wall is inside the room; rooms outside of the maze, where
+        // physical coordinates may be negative, are included
+        let steps = 8;
+        for row in -2..maze.height() as isize + 2 {
+            for col in -2..maze.width() as isize + 2 {
+                let pos = matrix_pos(col, row);
+                let center = maze.center(pos);
+                assert_eq!(pos, maze.room_at(center));
+                for &wall in maze.walls(pos) {
+                    let (start, end) = maze.corners((pos, wall));
+                    for i in 0..=steps {
+                        let s = i as f32 / steps as f32;
+                        for j in 1..steps {
+                            let t = 0.95 * j as f32 / steps as f32;
+                            let edge = physical::Pos {
+                                x: start.x + s * (end.x - start.x),
+                                y: start.y + s * (end.y - start.y),
+                            };
+                            let p = physical::Pos {
+                                x: center.x + t * (edge.x - center.x),
+                                y: center.y + t * (edge.y - center.y),
+                            };
+                            assert_eq!(
+                                pos,
+                                maze.room_at(p),
+                                "for {:?} at {:?}",
+                                p,
+                                pos,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
+
     #[maze_test]
     fn wall_pos_at(maze: TestMaze) {
         let steps = 10;
@@ -638,8 +1320,7 @@ mod tests {
                     maze.walls(pos)
                         .iter()
                         .cloned()
-                        .filter(|wall| wall.in_span(a))
-                        .next()
+                        .find(|wall| wall.in_span(a))
                         .unwrap(),
                 );
                 for r in &[0.1, 0.3, 0.5] {
@@ -664,7 +1345,7 @@ mod tests {
             .filter(|pos| pos.row == 0)
             .map(|pos| maze.center(pos))
             .fold(
-                (std::f32::MAX, std::f32::MAX, std::f32::MIN, std::f32::MIN),
+                (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
                 |(l, t, r, b), p| {
                     (l.min(p.x), t.min(p.y), r.max(p.x), b.max(p.y))
                 },
@@ -699,7 +1380,7 @@ mod tests {
                 })
             })
             .fold(
-                (std::f32::MAX, std::f32::MAX, std::f32::MIN, std::f32::MIN),
+                (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
                 |(l, t, r, b), p| {
                     (l.min(p.x), t.min(p.y), r.max(p.x), b.max(p.y))
                 },
@@ -721,6 +1402,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn wall_by_name_known() {
+        for (name, wall) in [
+            ("Hex:UP_LEFT0", &hex::walls::UP_LEFT0),
+            ("Quad:LEFT", &quad::walls::LEFT),
+            ("Tri:RIGHT1", &tri::walls::RIGHT1),
+        ] {
+            assert_eq!(Some(wall), wall_by_name(name));
+        }
+    }
+
+    #[test]
+    fn wall_by_name_invalid() {
+        assert_eq!(None, wall_by_name(""));
+        assert_eq!(None, wall_by_name("Quad:UP_LEFT"));
+        assert_eq!(None, wall_by_name("quad:LEFT"));
+    }
+
+    #[test]
+    fn room_area_perimeter() {
+        let sqrt_3 = 3f32.sqrt();
+        for (shape, area, perimeter) in [
+            (Shape::Quad, 2.0, 4.0 * 2f32.sqrt()),
+            (Shape::Hex, 3.0 * sqrt_3 / 2.0, 6.0),
+            (Shape::Tri, 3.0 * sqrt_3 / 4.0, 3.0 * sqrt_3),
+            (
+                Shape::QuadDiagonal,
+                2.0 * 2f32.sqrt(),
+                16.0 * (std::f32::consts::PI / 8.0).sin(),
+            ),
+        ] {
+            assert!(
+                (shape.room_area() - area).abs() < 1e-5,
+                "{} != {} for {}",
+                shape.room_area(),
+                area,
+                shape,
+            );
+            assert!(
+                (shape.room_perimeter() - perimeter).abs() < 1e-5,
+                "{} != {} for {}",
+                shape.room_perimeter(),
+                perimeter,
+                shape,
+            );
+        }
+    }
+
+    #[test]
+    fn wall_by_index_all() {
+        for shape in [Shape::Hex, Shape::Quad, Shape::QuadDiagonal, Shape::Tri]
+        {
+            for &wall in shape.all_walls() {
+                assert_eq!(Some(wall), shape.wall_by_index(wall.index));
+                assert_eq!(Some(wall), wall_by_name(wall.name));
+            }
+            assert_eq!(None, shape.wall_by_index(shape.all_walls().len()));
+        }
+    }
+
+    #[test]
+    fn walls_tile_circle() {
+        for shape in [Shape::Hex, Shape::Quad, Shape::QuadDiagonal, Shape::Tri]
+        {
+            assert_walls_tile_circle(shape);
+        }
+    }
+
     #[maze_test]
     fn previous_and_next_wall(maze: TestMaze) {
         for pos in maze.positions() {
@@ -728,7 +1477,7 @@ mod tests {
                 let a1 = wall::Wall::normalized_angle(wall.span.0.a);
                 let a2 = wall::Wall::normalized_angle(wall.previous.span.1.a);
                 assert!(
-                    (a1 - a2).abs() < std::f32::EPSILON * 16.0,
+                    (a1 - a2).abs() < f32::EPSILON * 16.0,
                     "first wall {:?} for {:?} ({} != {})",
                     wall,
                     maze.shape(),
@@ -738,7 +1487,7 @@ mod tests {
                 let a1 = wall::Wall::normalized_angle(wall.span.1.a);
                 let a2 = wall::Wall::normalized_angle(wall.next.span.0.a);
                 assert!(
-                    (a1 - a2).abs() < std::f32::EPSILON * 16.0,
+                    (a1 - a2).abs() < f32::EPSILON * 16.0,
                     "second wall {:?} for {:?} ({} != {})",
                     wall,
                     maze.shape(),
@@ -133,6 +133,10 @@ pub fn walls(_pos: matrix::Pos) -> &'static [&'static wall::Wall] {
     WALLS
 }
 
+pub fn heuristic(a: matrix::Pos, b: matrix::Pos) -> f32 {
+    ((a.col - b.col).abs() + (a.row - b.row).abs()) as f32
+}
+
 pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     physical::Pos {
         x: (pos.col as f32 + 0.5) * MULTIPLICATOR,
@@ -147,7 +151,7 @@ pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
     }
 }
 
-#[cfg_attr(feature = "cargo-clippy", allow(clippy::collapsible_else_if))]
+#[allow(clippy::collapsible_else_if)]
 pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
     let matrix_pos = physical_to_cell(pos);
     let center = cell_to_physical(matrix_pos);
@@ -265,6 +269,21 @@ mod tests {
         );
     }
 
+    #[maze_test(quad)]
+    fn corner_walls_adjacent(maze: TestMaze) {
+        for pos in maze.positions() {
+            for wall in maze.walls(pos) {
+                let corner_walls =
+                    maze.corner_walls((pos, wall)).collect::<Vec<_>>();
+                assert_eq!(wall.corner_wall_offsets.len(), 3);
+                assert_eq!(corner_walls.len(), 4);
+                for pair in corner_walls.windows(2) {
+                    assert_eq!(maze.back(pair[0]).0, pair[1].0);
+                }
+            }
+        }
+    }
+
     #[maze_test(quad)]
     fn follow_wall_single_room(maze: TestMaze) {
         assert_eq!(
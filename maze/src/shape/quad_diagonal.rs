@@ -0,0 +1,390 @@
+//! # Quad mazes with diagonal passages
+//!
+//! This is an experimental shape where every room is a square grid cell with
+//! its corners cut off, making it an octagon with four axis aligned walls and
+//! four diagonal walls. The diagonal walls lead to the diagonal neighbours of
+//! a room.
+//!
+//! Octagons do not tile the plane; a small square gap remains between the
+//! corner cuts of four rooms meeting at a grid corner, and the two diagonal
+//! passages through such a gap cross. The corner cuts are rendered as walls,
+//! so mazes of this shape look like quad mazes with chamfered corners.
+
+use crate::matrix;
+use crate::physical;
+use crate::wall;
+
+use crate::wall::{Angle, Offset};
+use crate::WallPos;
+
+/// A span step angle
+///
+/// This is half the angle span used by a single wall.
+const D: f32 = 2.0 * std::f32::consts::PI / 16.0;
+
+/// cos(22.5°)
+const COS_22: f32 = 0.923_879_5;
+
+/// sin(22.5°)
+const SIN_22: f32 = 0.382_683_43;
+
+/// The scale factor when converting maze coordinates to physical coordinates
+///
+/// This is the distance between the axis aligned walls of a room.
+const MULTIPLICATOR: f32 = 2.0 * COS_22;
+
+define_shape! {
+    << QuadDiagonal >>
+
+    LEFT(0) = {
+        corner_wall_offsets: &[
+            Offset { dx: -1, dy: 0, wall: &DOWN_RIGHT },
+        ],
+        dir: (-1, 0),
+        span: (
+            Angle {
+                a: 7.0 * D,
+                dx: -COS_22,
+                dy: SIN_22,
+            },
+            Angle {
+                a: 9.0 * D,
+                dx: -COS_22,
+                dy: -SIN_22,
+            },
+        ),
+        previous: &DOWN_LEFT,
+        next: &UP_LEFT,
+    },
+    UP_LEFT(1) = {
+        corner_wall_offsets: &[
+            Offset { dx: -1, dy: 0, wall: &RIGHT },
+        ],
+        dir: (-1, -1),
+        span: (
+            Angle {
+                a: 9.0 * D,
+                dx: -COS_22,
+                dy: -SIN_22,
+            },
+            Angle {
+                a: 11.0 * D,
+                dx: -SIN_22,
+                dy: -COS_22,
+            },
+        ),
+        previous: &LEFT,
+        next: &UP,
+    },
+    UP(2) = {
+        corner_wall_offsets: &[
+            Offset { dx: 0, dy: -1, wall: &DOWN_LEFT },
+        ],
+        dir: (0, -1),
+        span: (
+            Angle {
+                a: 11.0 * D,
+                dx: -SIN_22,
+                dy: -COS_22,
+            },
+            Angle {
+                a: 13.0 * D,
+                dx: SIN_22,
+                dy: -COS_22,
+            },
+        ),
+        previous: &UP_LEFT,
+        next: &UP_RIGHT,
+    },
+    UP_RIGHT(3) = {
+        corner_wall_offsets: &[
+            Offset { dx: 0, dy: -1, wall: &DOWN },
+        ],
+        dir: (1, -1),
+        span: (
+            Angle {
+                a: 13.0 * D,
+                dx: SIN_22,
+                dy: -COS_22,
+            },
+            Angle {
+                a: 15.0 * D,
+                dx: COS_22,
+                dy: -SIN_22,
+            },
+        ),
+        previous: &UP,
+        next: &RIGHT,
+    },
+    RIGHT(4) = {
+        corner_wall_offsets: &[
+            Offset { dx: 1, dy: 0, wall: &UP_LEFT },
+        ],
+        dir: (1, 0),
+        span: (
+            Angle {
+                a: 15.0 * D,
+                dx: COS_22,
+                dy: -SIN_22,
+            },
+            Angle {
+                a: D,
+                dx: COS_22,
+                dy: SIN_22,
+            },
+        ),
+        previous: &UP_RIGHT,
+        next: &DOWN_RIGHT,
+    },
+    DOWN_RIGHT(5) = {
+        corner_wall_offsets: &[
+            Offset { dx: 1, dy: 0, wall: &LEFT },
+        ],
+        dir: (1, 1),
+        span: (
+            Angle {
+                a: D,
+                dx: COS_22,
+                dy: SIN_22,
+            },
+            Angle {
+                a: 3.0 * D,
+                dx: SIN_22,
+                dy: COS_22,
+            },
+        ),
+        previous: &RIGHT,
+        next: &DOWN,
+    },
+    DOWN(6) = {
+        corner_wall_offsets: &[
+            Offset { dx: 0, dy: 1, wall: &UP_RIGHT },
+        ],
+        dir: (0, 1),
+        span: (
+            Angle {
+                a: 3.0 * D,
+                dx: SIN_22,
+                dy: COS_22,
+            },
+            Angle {
+                a: 5.0 * D,
+                dx: -SIN_22,
+                dy: COS_22,
+            },
+        ),
+        previous: &DOWN_RIGHT,
+        next: &DOWN_LEFT,
+    },
+    DOWN_LEFT(7) = {
+        corner_wall_offsets: &[
+            Offset { dx: 0, dy: 1, wall: &UP },
+        ],
+        dir: (-1, 1),
+        span: (
+            Angle {
+                a: 5.0 * D,
+                dx: -SIN_22,
+                dy: COS_22,
+            },
+            Angle {
+                a: 7.0 * D,
+                dx: -COS_22,
+                dy: SIN_22,
+            },
+        ),
+        previous: &DOWN,
+        next: &LEFT,
+    }
+}
+
+/// The walls
+static WALLS: &[&wall::Wall] = &[
+    &walls::LEFT,
+    &walls::UP_LEFT,
+    &walls::UP,
+    &walls::UP_RIGHT,
+    &walls::RIGHT,
+    &walls::DOWN_RIGHT,
+    &walls::DOWN,
+    &walls::DOWN_LEFT,
+];
+
+pub fn minimal_dimensions(width: f32, height: f32) -> (usize, usize) {
+    let height = (height.max(MULTIPLICATOR) / MULTIPLICATOR).ceil() as usize;
+
+    let width = (width.max(MULTIPLICATOR) / MULTIPLICATOR).ceil() as usize;
+
+    (width, height)
+}
+
+pub fn back_index(wall: usize) -> usize {
+    wall ^ 0b0100
+}
+
+pub fn opposite(wall_pos: WallPos) -> Option<&'static wall::Wall> {
+    let (_, wall) = wall_pos;
+    Some(walls::ALL[(wall.index + walls::ALL.len() / 2) % walls::ALL.len()])
+}
+
+pub fn walls(_pos: matrix::Pos) -> &'static [&'static wall::Wall] {
+    WALLS
+}
+
+pub fn heuristic(a: matrix::Pos, b: matrix::Pos) -> f32 {
+    (a.col - b.col).abs().max((a.row - b.row).abs()) as f32
+}
+
+pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
+    physical::Pos {
+        x: (pos.col as f32 + 0.5) * MULTIPLICATOR,
+        y: (pos.row as f32 + 0.5) * MULTIPLICATOR,
+    }
+}
+
+pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
+    matrix::Pos {
+        col: (pos.x / MULTIPLICATOR).floor() as isize,
+        row: (pos.y / MULTIPLICATOR).floor() as isize,
+    }
+}
+
+pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
+    let matrix_pos = physical_to_cell(pos);
+    let center = cell_to_physical(matrix_pos);
+    let angle = (pos.y - center.y).atan2(pos.x - center.x);
+
+    (
+        matrix_pos,
+        WALLS
+            .iter()
+            .find(|wall| wall.in_span(angle))
+            .copied()
+            .unwrap_or(&walls::RIGHT),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use crate::Shape;
+
+    /// The diagonal walls and their backs.
+    const DIAGONALS: &[(&wall::Wall, &wall::Wall, (isize, isize))] = &[
+        (&walls::UP_LEFT, &walls::DOWN_RIGHT, (-1, -1)),
+        (&walls::UP_RIGHT, &walls::DOWN_LEFT, (1, -1)),
+        (&walls::DOWN_RIGHT, &walls::UP_LEFT, (1, 1)),
+        (&walls::DOWN_LEFT, &walls::UP_RIGHT, (-1, 1)),
+    ];
+
+    #[test]
+    fn back_diagonal() {
+        let maze = Shape::QuadDiagonal.create::<()>(5, 5);
+        let pos = matrix_pos(2, 2);
+        for &(wall, back, (dx, dy)) in DIAGONALS {
+            let other = matrix_pos(pos.col + dx, pos.row + dy);
+            assert_eq!((other, back), maze.back((pos, wall)));
+            assert_eq!((pos, wall), maze.back(maze.back((pos, wall))));
+            assert_eq!(Some(wall), maze.wall_between(pos, other));
+        }
+    }
+
+    #[test]
+    fn back_all() {
+        let maze = Shape::QuadDiagonal.create::<()>(5, 5);
+        for pos in maze.positions() {
+            for &wall in maze.walls(pos) {
+                assert_eq!((pos, wall), maze.back(maze.back((pos, wall))));
+            }
+        }
+    }
+
+    #[test]
+    fn opposite_diagonal() {
+        let maze = Shape::QuadDiagonal.create::<()>(5, 5);
+        let pos = matrix_pos(2, 2);
+        for &(wall, other, _) in DIAGONALS {
+            assert_eq!(Some(other), maze.opposite((pos, wall)));
+            assert_eq!(Some(wall), maze.opposite((pos, other)));
+        }
+        assert_eq!(Some(&walls::RIGHT), maze.opposite((pos, &walls::LEFT)),);
+        assert_eq!(Some(&walls::DOWN), maze.opposite((pos, &walls::UP)));
+    }
+
+    #[test]
+    fn corners_shared() {
+        let maze = Shape::QuadDiagonal.create::<()>(5, 5);
+        let pos = matrix_pos(2, 2);
+        for &wall in maze.walls(pos) {
+            let corner = maze.corners((pos, wall)).0;
+            for wall_pos in maze.corner_walls((pos, wall)) {
+                let other = maze.corners(wall_pos).0;
+                assert!(
+                    (corner - other).value() < 0.0001,
+                    "{:?} does not start at the corner of {:?}",
+                    wall_pos,
+                    wall,
+                );
+            }
+
+            // The back of an axis aligned wall is the same line segment
+            let (start, end) = maze.corners((pos, wall));
+            let (back_start, back_end) = maze.corners(maze.back((pos, wall)));
+            if wall.dir.0 == 0 || wall.dir.1 == 0 {
+                assert!((start - back_end).value() < 0.0001);
+                assert!((end - back_start).value() < 0.0001);
+            }
+        }
+    }
+
+    #[test]
+    fn wall_pos_at() {
+        let maze = Shape::QuadDiagonal.create::<()>(5, 5);
+        let pos = matrix_pos(2, 2);
+        for &wall in maze.walls(pos) {
+            let (start, end) = maze.corners((pos, wall));
+            let center = maze.center(pos);
+            let target = physical::Pos {
+                x: center.x + 0.4 * (start.x + end.x - 2.0 * center.x),
+                y: center.y + 0.4 * (start.y + end.y - 2.0 * center.y),
+            };
+            assert_eq!((pos, wall), maze.wall_pos_at(target));
+            assert_eq!(pos, maze.room_at(target));
+        }
+    }
+
+    #[test]
+    fn initialize_perfect() {
+        let maze = Shape::QuadDiagonal.create::<()>(8, 6).initialize(
+            crate::initialize::Method::Winding,
+            &mut crate::initialize::LFSR::new(12345),
+        );
+
+        let rooms = maze.positions().count();
+        let passages = maze
+            .positions()
+            .flat_map(|pos| maze.doors(pos).map(move |wall| (pos, wall)))
+            .count()
+            / 2;
+        assert_eq!(rooms - 1, passages);
+        let start = matrix_pos(0, 0);
+        assert!(maze.positions().all(|pos| maze.walk(start, pos).is_some()));
+        assert!(maze.positions().any(|pos| {
+            maze.doors(pos)
+                .any(|wall| wall.dir.0 != 0 && wall.dir.1 != 0)
+        }));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn to_path_d_terminates() {
+        use crate::render::svg::ToPath;
+
+        let maze = Shape::QuadDiagonal.create::<()>(8, 6).initialize(
+            crate::initialize::Method::Braid,
+            &mut crate::initialize::LFSR::new(12345),
+        );
+        assert!(!maze.to_path_d().is_empty());
+    }
+}
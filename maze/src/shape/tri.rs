@@ -218,6 +218,14 @@ pub fn walls(pos: matrix::Pos) -> &'static [&'static wall::Wall] {
     }
 }
 
+pub fn heuristic(a: matrix::Pos, b: matrix::Pos) -> f32 {
+    // Every room has only one vertical neighbour, so two vertical steps are
+    // always separated by at least one horizontal step
+    let dx = (a.col - b.col).abs();
+    let dy = (a.row - b.row).abs();
+    (dy + dx.max(dy - 1)) as f32
+}
+
 pub fn cell_to_physical(pos: matrix::Pos) -> physical::Pos {
     physical::Pos {
         x: (pos.col as f32 + 1.0) * HORIZONTAL_MULTIPLICATOR,
@@ -251,7 +259,7 @@ pub fn physical_to_cell(pos: physical::Pos) -> matrix::Pos {
     }
 }
 
-#[cfg_attr(feature = "cargo-clippy", allow(clippy::collapsible_if))]
+#[allow(clippy::collapsible_if)]
 pub fn physical_to_wall_pos(pos: physical::Pos) -> WallPos {
     let matrix_pos = physical_to_cell(pos);
     let flipped = (matrix_pos.col + matrix_pos.row) & 1 == 1;
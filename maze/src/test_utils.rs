@@ -16,13 +16,80 @@ pub fn is_close(expected: physical::Pos, actual: physical::Pos) -> bool {
 /// Determines whether two floating point values are close enough to be
 /// considered equal.
 ///
-/// This function lowers the resolution to `std::f32::EPSILON * 4.0`.
+/// This function lowers the resolution to `f32::EPSILON * 4.0`.
 ///
 /// # Arguments
 /// *  `a` - One value.
 /// *  `b` - Another value.
 pub fn nearly_equal(a: f32, b: f32) -> bool {
-    return a == b || (a - b).abs() < std::f32::EPSILON * 4.0;
+    a == b || (a - b).abs() < f32::EPSILON * 4.0
+}
+
+/// Asserts that the walls of every room of a shape exactly partition the
+/// circle.
+///
+/// The spans of the walls of a room must be non-empty, must not overlap and
+/// must not leave any gaps; together they must cover _[0, 2𝜋)_ exactly once.
+/// Rooms of several positions are checked, to cover shapes whose rooms differ
+/// between rows or columns.
+///
+/// This is intended to validate the geometry of new shapes.
+///
+/// # Arguments
+/// *  `shape` - The shape to check.
+///
+/// # Panics
+/// This function panics if the walls of any room do not tile the circle.
+pub fn assert_walls_tile_circle(shape: Shape) {
+    use std::f32::consts::TAU;
+
+    let tolerance = f32::EPSILON * 16.0;
+    for row in -2..4 {
+        for col in -2..4 {
+            let pos = matrix_pos(col, row);
+            let mut spans = shape
+                .walls(pos)
+                .iter()
+                .map(|wall| {
+                    let start = wall::Wall::normalized_angle(wall.span.0.a);
+                    let length = wall::Wall::normalized_angle(
+                        wall.span.1.a - wall.span.0.a,
+                    );
+                    assert!(
+                        length > tolerance,
+                        "empty span for {:?} at {:?} for {:?}",
+                        wall,
+                        pos,
+                        shape,
+                    );
+                    (start, length, wall)
+                })
+                .collect::<Vec<_>>();
+            spans.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+
+            let total = spans.iter().map(|&(_, length, _)| length).sum::<f32>();
+            assert!(
+                (total - TAU).abs() < tolerance * spans.len() as f32,
+                "spans at {:?} for {:?} cover {} radians",
+                pos,
+                shape,
+                total,
+            );
+
+            for (i, &(start, length, wall)) in spans.iter().enumerate() {
+                let (next, _, next_wall) = spans[(i + 1) % spans.len()];
+                let d = wall::Wall::normalized_angle(start + length - next);
+                assert!(
+                    d < tolerance || TAU - d < tolerance,
+                    "{:?} and {:?} at {:?} for {:?} overlap or leave a gap",
+                    wall,
+                    next_wall,
+                    pos,
+                    shape,
+                );
+            }
+        }
+    }
 }
 
 /// A simple helper to create a matrix position.
@@ -146,13 +213,9 @@ impl<'a> Navigator<'a> {
         for<'r> P: FnMut(&'r &&wall::Wall) -> bool,
     {
         if self.pos.is_none() {
-            self.pos = self
-                .maze
-                .positions()
-                .filter(|&pos| {
-                    self.maze.walls(pos).iter().any(|wall| predicate(&wall))
-                })
-                .next();
+            self.pos = self.maze.positions().find(|&pos| {
+                self.maze.walls(pos).iter().any(|wall| predicate(&wall))
+            });
         }
         let pos = self.pos.unwrap();
         self.log.push(pos);
@@ -162,13 +225,12 @@ impl<'a> Navigator<'a> {
             .walls(pos)
             .iter()
             .filter(predicate)
-            .filter(|wall| {
+            .find(|wall| {
                 self.maze.is_inside(matrix_pos(
                     pos.col + wall.dir.0,
                     pos.row + wall.dir.1,
                 ))
             })
-            .next()
             .unwrap();
         self.maze.set_open((pos, wall), open);
         self.pos = Some(matrix_pos(pos.col + wall.dir.0, pos.row + wall.dir.1));
@@ -1,6 +1,4 @@
-use std::collections::BinaryHeap;
-
-use bit_set::BitSet;
+use std::collections::{BinaryHeap, VecDeque};
 
 use crate::matrix;
 
@@ -24,6 +22,10 @@ where
     /// minimal set of rooms required to pass through to get from start to
     /// finish, including `from` and ` to`.
     ///
+    /// If several paths are equally short, the same one is always returned
+    /// for the same maze; ties are broken by room position, never by memory
+    /// layout or hashing.
+    ///
     /// # Example
     ///
     /// ```
@@ -55,61 +57,313 @@ where
     /// # Arguments
     /// *  `from` - The starting position.
     /// *  `to` - The desired goal.
-    pub fn walk(&self, from: matrix::Pos, to: matrix::Pos) -> Option<Path<T>> {
+    pub fn walk(
+        &self,
+        from: matrix::Pos,
+        to: matrix::Pos,
+    ) -> Option<Path<'_, T>> {
+        // Reverse the positions to return the rooms in correct order
+        let (start, end) = (to, from);
+
+        self.search(start, end, usize::MAX)
+            .unwrap_or(None)
+            .map(|rooms| Path::new(self, start, end, rooms))
+    }
+
+    /// Walks from `from` to `to` along the shortest path, giving up after a
+    /// number of steps.
+    ///
+    /// This behaves like [`Maze::walk`], but every room evaluated counts
+    /// against a budget. This allows predictable bailing out for huge mazes.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `to` - The desired goal.
+    /// *  `max_expansions` - The maximum number of rooms to evaluate.
+    ///
+    /// # Errors
+    /// If the goal has not been reached after evaluating `max_expansions`
+    /// rooms, [`BudgetExceeded`] is returned.
+    pub fn walk_bounded(
+        &self,
+        from: matrix::Pos,
+        to: matrix::Pos,
+        max_expansions: usize,
+    ) -> Result<Option<Path<'_, T>>, BudgetExceeded> {
         // Reverse the positions to return the rooms in correct order
         let (start, end) = (to, from);
 
-        // The heuristic for a room position
-        let h = |pos: matrix::Pos| {
-            let dx = (pos.col - end.col).abs();
-            let dy = (pos.row - end.row).abs();
-            (dx * dx + dy * dy) as u32
+        self.search(start, end, max_expansions)
+            .map(|rooms| rooms.map(|rooms| Path::new(self, start, end, rooms)))
+    }
+
+    /// Calculates the number of steps along the path from `from` to `to`.
+    ///
+    /// The path is the same as the one returned by [`Maze::walk`], so if the
+    /// rooms are connected, the return value is one less than the number of
+    /// rooms on that path. No path is constructed, which makes this method
+    /// suitable for bulk queries.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `to` - The desired goal.
+    pub fn distance(&self, from: matrix::Pos, to: matrix::Pos) -> Option<u32> {
+        self.search(to, from, usize::MAX)
+            .unwrap_or(None)
+            .map(|rooms| {
+                let mut count = 0;
+                let mut current = from;
+                while current != to {
+                    current = rooms[current]
+                        .came_from
+                        .expect("attempted to backtrace an incomplete path!");
+                    count += 1;
+                }
+                count
+            })
+    }
+
+    /// Searches for a path from `start` to `end`.
+    ///
+    /// If the rooms are connected, the return value is the room matrix where
+    /// following the `came_from` fields from `end` leads to `start`. The
+    /// search stops as soon as `end` is reached.
+    ///
+    /// # Arguments
+    /// *  `start` - The starting position.
+    /// *  `end` - The desired goal.
+    /// *  `max_expansions` - The maximum number of rooms to evaluate.
+    ///
+    /// # Errors
+    /// If the budget is exhausted before `end` is reached, [`BudgetExceeded`]
+    /// is returned.
+    fn search(
+        &self,
+        start: matrix::Pos,
+        end: matrix::Pos,
+        max_expansions: usize,
+    ) -> Result<Option<Matrix<Room>>, BudgetExceeded> {
+        // The heuristic for a room position; on a torus the goal may also be
+        // reached across the edges, so we use its nearest wrapped copy
+        let shape = self.shape();
+        let h = |pos: matrix::Pos| match self.topology() {
+            crate::Topology::Bounded => shape.heuristic(pos, end) as u32,
+            crate::Topology::Torus => {
+                let (width, height) =
+                    (self.width() as isize, self.height() as isize);
+                (-1..=1)
+                    .flat_map(|i| (-1..=1).map(move |j| (i, j)))
+                    .map(|(i, j)| {
+                        shape.heuristic(
+                            pos,
+                            matrix::Pos {
+                                col: end.col + i * width,
+                                row: end.row + j * height,
+                            },
+                        ) as u32
+                    })
+                    .min()
+                    .unwrap()
+            }
         };
 
-        // The room positions pending evaluation and their cost
+        // The room positions pending evaluation; rooms with a lower cost have
+        // a higher priority
         let mut open_set = OpenSet::new(self.width(), self.height());
-        open_set.push(std::u32::MAX, start);
+        let priority = |f: u32| u32::MAX - f;
 
         let mut rooms = Matrix::<Room>::new(self.width(), self.height());
         rooms[start].g = 0;
         rooms[start].f = h(start);
+        open_set.push(priority(rooms[start].f), start);
 
+        let mut expansions = 0;
         while let Some(current) = open_set.pop() {
             // Have we reached the target?
             if current == end {
-                return Some(Path::new(self, start, end, rooms));
+                return Ok(Some(rooms));
+            }
+
+            // A room is pushed again whenever a shorter path to it is found,
+            // so skip any outdated entries
+            if rooms[current].visited {
+                continue;
+            }
+
+            if expansions == max_expansions {
+                return Err(BudgetExceeded);
             }
+            expansions += 1;
 
             rooms[current].visited = true;
             for wall in self.doors(current) {
-                // Find the next room, and continue if we have already evaluated
-                // it to a better distance, or it is outside of the maze
+                // Find the next room, and continue if it is outside of the maze
                 let (next, _) = self.back((current, wall));
-                if !self.is_inside(next)
-                    || (rooms[next].visited
-                        && rooms[next].g <= rooms[current].g + 1)
-                {
+                if !self.is_inside(next) {
                     continue;
                 }
 
                 // The cost to get to this room is one more that the room from
-                // which we came
+                // which we came; only a shorter path than the best known is
+                // of interest, in which case the room is evaluated again
                 let g = rooms[current].g + 1;
-                let f = g + h(next);
-
-                let current_in_open_set = open_set.contains(current);
-                if !current_in_open_set || g < rooms[current].g {
+                if g < rooms[next].g {
+                    let f = g + h(next);
                     rooms[next].g = g;
                     rooms[next].f = f;
+                    rooms[next].visited = false;
                     rooms[next].came_from = Some(current);
+                    open_set.push(priority(f), next);
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Finds all rooms reachable from a room within a number of steps.
+    ///
+    /// Every step passes through an open wall, and rooms outside of the maze
+    /// are never entered. Unlike a full distance field, the search stops once
+    /// `steps` is exhausted, so this is cheap for small radii.
+    ///
+    /// The rooms are returned in order of increasing distance, starting with
+    /// `from`. If `from` is outside of the maze, nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    /// *  `steps` - The maximum number of steps.
+    pub fn reachable_within(
+        &self,
+        from: matrix::Pos,
+        steps: u32,
+    ) -> Vec<matrix::Pos> {
+        let mut result = Vec::new();
+        let mut seen = Matrix::<bool>::new(self.width(), self.height());
+        if let Some(v) = seen.get_mut(from) {
+            *v = true;
+        } else {
+            return result;
+        }
+
+        let mut queue = VecDeque::new();
+        queue.push_back((from, 0));
+        while let Some((pos, distance)) = queue.pop_front() {
+            result.push(pos);
+            if distance == steps {
+                continue;
+            }
+            for next in self.neighbors(pos) {
+                if let Some(v) = seen.get_mut(next) {
+                    if !*v {
+                        *v = true;
+                        queue.push_back((next, distance + 1));
+                    }
+                }
+            }
+        }
+
+        result
+    }
 
-                    if !current_in_open_set {
-                        open_set.push(f, next);
+    /// Counts the rooms reachable from a room.
+    ///
+    /// This is the size of the connected component containing `from`,
+    /// including `from` itself. Rooms outside of the maze are never entered,
+    /// and no list of positions is built, so this is cheaper than
+    /// [`Maze::reachable_within`] when only the size is of interest.
+    ///
+    /// If `from` is outside of the maze, `0` is returned.
+    ///
+    /// # Arguments
+    /// *  `from` - The starting position.
+    pub fn reachable_count(&self, from: matrix::Pos) -> usize {
+        let mut seen = Matrix::<bool>::new(self.width(), self.height());
+        if let Some(v) = seen.get_mut(from) {
+            *v = true;
+        } else {
+            return 0;
+        }
+
+        let mut count = 0;
+        let mut queue = VecDeque::new();
+        queue.push_back(from);
+        while let Some(pos) = queue.pop_front() {
+            count += 1;
+            for next in self.neighbors(pos) {
+                if let Some(v) = seen.get_mut(next) {
+                    if !*v {
+                        *v = true;
+                        queue.push_back(next);
                     }
                 }
             }
         }
 
+        count
+    }
+
+    /// Finds the shortest path from any of several sources to any of several
+    /// targets.
+    ///
+    /// This performs a single breadth-first search from all sources at once,
+    /// which is much cheaper than walking between every pair. The path
+    /// returned starts in one of `sources` and ends in the target nearest to
+    /// any source; if several paths are equally short, the one from the
+    /// earliest source in `sources` is preferred.
+    ///
+    /// Positions outside of the maze are ignored. If no target is reachable
+    /// from any source, nothing is returned.
+    ///
+    /// # Arguments
+    /// *  `sources` - The possible starting positions.
+    /// *  `targets` - The possible goals.
+    pub fn walk_multi(
+        &self,
+        sources: &[matrix::Pos],
+        targets: &[matrix::Pos],
+    ) -> Option<Vec<matrix::Pos>> {
+        let mut is_target = Matrix::<bool>::new(self.width(), self.height());
+        for &target in targets {
+            if let Some(v) = is_target.get_mut(target) {
+                *v = true;
+            }
+        }
+
+        // The room from which each room was first reached; sources are marked
+        // as reached from themselves
+        let mut came_from =
+            Matrix::<Option<matrix::Pos>>::new(self.width(), self.height());
+        let mut queue = VecDeque::new();
+        for &source in sources {
+            if let Some(v @ None) = came_from.get_mut(source) {
+                *v = Some(source);
+                queue.push_back(source);
+            }
+        }
+
+        while let Some(pos) = queue.pop_front() {
+            if is_target[pos] {
+                let mut path = vec![pos];
+                let mut current = pos;
+                while let Some(previous) =
+                    came_from[current].filter(|&previous| previous != current)
+                {
+                    path.push(previous);
+                    current = previous;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            for next in self.neighbors(pos) {
+                if let Some(v @ None) = came_from.get_mut(next) {
+                    *v = Some(pos);
+                    queue.push_back(next);
+                }
+            }
+        }
+
         None
     }
 
@@ -126,8 +380,72 @@ where
     ) -> impl Iterator<Item = FollowWallItem> + '_ {
         Follower::new(self, wall_pos)
     }
+
+    /// Describes a path as the turns taken in every room along it.
+    ///
+    /// The heading when entering and leaving a room is the direction between
+    /// the physical centres of consecutive rooms, so this works for every
+    /// shape. A path of `n` rooms yields `n - 2` turns, one for every room
+    /// except the first and the last.
+    ///
+    /// # Arguments
+    /// *  `path` - The rooms of the path.
+    pub fn directions(&self, path: &[matrix::Pos]) -> Vec<Turn> {
+        let headings = path
+            .windows(2)
+            .map(|pair| self.center(pair[1]) - self.center(pair[0]))
+            .collect::<Vec<_>>();
+        headings
+            .windows(2)
+            .map(|pair| {
+                let (a, b) = (pair[0], pair[1]);
+                let angle =
+                    (a.x * b.y - a.y * b.x).atan2(a.x * b.x + a.y * b.y);
+                if angle.abs() < std::f32::consts::FRAC_PI_4 {
+                    Turn::Straight
+                } else if angle.abs() > 3.0 * std::f32::consts::FRAC_PI_4 {
+                    Turn::Back
+                } else if angle > 0.0 {
+                    Turn::Right
+                } else {
+                    Turn::Left
+                }
+            })
+            .collect()
+    }
+}
+
+/// A turn taken in a room along a path.
+///
+/// Left and right are as seen in the rendered maze, where the vertical axis
+/// points downwards.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Turn {
+    /// The path continues roughly in the same direction.
+    Straight,
+
+    /// The path turns to the left.
+    Left,
+
+    /// The path turns to the right.
+    Right,
+
+    /// The path turns back the way it came.
+    Back,
+}
+
+/// The error returned when a bounded walk runs out of its budget.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BudgetExceeded;
+
+impl std::fmt::Display for BudgetExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "budget exceeded")
+    }
 }
 
+impl std::error::Error for BudgetExceeded {}
+
 /// A path through a maze.
 ///
 /// This struct describes the path through a maze by maintaining a mapping from
@@ -329,9 +647,8 @@ type PriorityPos = (u32, matrix::Pos);
 
 /// A set of rooms and priorities.
 ///
-/// This struct supports adding a position with a priority, retrieving the
-/// position with the highest priority and querying whether a position is in the
-/// set.
+/// This struct supports adding a position with a priority and retrieving the
+/// position with the highest priority. A position may be added several times.
 struct OpenSet {
     /// The width of the set.
     width: usize,
@@ -341,9 +658,6 @@ struct OpenSet {
 
     /// The heap containing prioritised positions.
     heap: BinaryHeap<PriorityPos>,
-
-    /// The positions present in the heap.
-    present: BitSet,
 }
 
 impl OpenSet {
@@ -353,7 +667,6 @@ impl OpenSet {
             width,
             height,
             heap: BinaryHeap::new(),
-            present: BitSet::with_capacity(width * height),
         }
     }
 
@@ -363,32 +676,17 @@ impl OpenSet {
     /// *  priority` - The priority of the position.
     /// *  pos` - The position.
     pub fn push(&mut self, priority: u32, pos: matrix::Pos) {
-        if let Some(index) = self.index(pos) {
+        if self.index(pos).is_some() {
             self.heap.push((priority, pos));
-            self.present.insert(index);
         }
     }
 
     /// Pops the room with the highest priority.
-    pub fn pop(&mut self) -> Option<matrix::Pos> {
-        if let Some(pos) = self.heap.pop().map(|(_, pos)| pos) {
-            if let Some(index) = self.index(pos) {
-                self.present.remove(index);
-            }
-            Some(pos)
-        } else {
-            None
-        }
-    }
-
-    /// Checks whether a position is in the set.
     ///
-    /// # Arguments
-    /// *  `pos` - The position.
-    pub fn contains(&mut self, pos: matrix::Pos) -> bool {
-        self.index(pos)
-            .map(|i| self.present.contains(i))
-            .unwrap_or(false)
+    /// Rooms with equal priority are popped in descending order of position,
+    /// so the order never depends on anything but the pushed values.
+    pub fn pop(&mut self) -> Option<matrix::Pos> {
+        self.heap.pop().map(|(_, pos)| pos)
     }
 
     /// Calculates the index of a position.
@@ -415,8 +713,197 @@ mod tests {
     use maze_test::maze_test;
 
     use super::*;
+    use crate::initialize;
     use crate::test_utils::*;
 
+    #[maze_test]
+    fn reachable_within_zero(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        assert_eq!(
+            vec![matrix_pos(2, 2)],
+            maze.reachable_within(matrix_pos(2, 2), 0),
+        );
+        assert!(maze.reachable_within(matrix_pos(-1, 0), 5).is_empty());
+    }
+
+    #[maze_test]
+    fn reachable_within_grows(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let from = matrix_pos(2, 2);
+
+        let mut previous = 0;
+        for steps in 0..100 {
+            let reachable = maze.reachable_within(from, steps);
+            assert!(reachable.len() >= previous);
+            assert!(reachable
+                .iter()
+                .all(|&pos| { maze.distance(from, pos).unwrap() <= steps }));
+            previous = reachable.len();
+        }
+        assert_eq!(maze.positions().count(), previous);
+    }
+
+    #[maze_test]
+    fn reachable_count_perfect(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+
+        assert_eq!(
+            maze.visited_positions().count(),
+            maze.reachable_count(matrix_pos(2, 2)),
+        );
+        assert_eq!(0, maze.reachable_count(matrix_pos(-1, 0)));
+    }
+
+    #[maze_test]
+    fn reachable_count_islands(maze: TestMaze) {
+        let mid = maze.width() as isize / 2;
+        let mut rng = initialize::LFSR::new(12345);
+        let maze = maze
+            .initialize_filter(initialize::Method::Winding, &mut rng, |pos| {
+                pos.col < mid
+            })
+            .initialize_filter(initialize::Method::Winding, &mut rng, |pos| {
+                pos.col > mid
+            });
+
+        assert_eq!(
+            maze.positions().filter(|pos| pos.col < mid).count(),
+            maze.reachable_count(matrix_pos(0, 0)),
+        );
+        assert_eq!(
+            maze.positions().filter(|pos| pos.col > mid).count(),
+            maze.reachable_count(matrix_pos(mid + 1, 0)),
+        );
+        assert_eq!(1, maze.reachable_count(matrix_pos(mid, 0)));
+    }
+
+    #[maze_test]
+    fn walk_multi_nearest_exit(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let from = matrix_pos(1, 1);
+        let near = matrix_pos(3, 2);
+        let far = matrix_pos(maze.width() as isize - 1, 4);
+        let (near, far) =
+            if maze.distance(from, near) <= maze.distance(from, far) {
+                (near, far)
+            } else {
+                (far, near)
+            };
+
+        for targets in [[near, far], [far, near]] {
+            let path = maze.walk_multi(&[from], &targets).unwrap();
+            assert_eq!(
+                maze.walk(from, near)
+                    .unwrap()
+                    .into_iter()
+                    .collect::<Vec<_>>(),
+                path,
+            );
+        }
+    }
+
+    #[maze_test]
+    fn walk_multi_sources(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+        let sources =
+            [matrix_pos(0, 0), matrix_pos(maze.width() as isize - 1, 0)];
+        let target = matrix_pos(2, 3);
+
+        let path = maze.walk_multi(&sources, &[target]).unwrap();
+        let shortest = sources
+            .iter()
+            .map(|&source| maze.distance(source, target).unwrap())
+            .min()
+            .unwrap();
+        assert!(sources.contains(&path[0]));
+        assert_eq!(Some(&target), path.last());
+        assert_eq!(shortest as usize + 1, path.len());
+        assert!(path.windows(2).all(|w| maze.connected(w[0], w[1])));
+    }
+
+    #[maze_test]
+    fn walk_multi_unreachable(maze: TestMaze) {
+        assert_eq!(
+            Some(vec![matrix_pos(1, 1)]),
+            maze.walk_multi(&[matrix_pos(1, 1)], &[matrix_pos(1, 1)]),
+        );
+        assert_eq!(
+            None,
+            maze.walk_multi(&[matrix_pos(0, 0)], &[matrix_pos(1, 1)]),
+        );
+        assert_eq!(None, maze.walk_multi(&[], &[matrix_pos(1, 1)]));
+        assert_eq!(
+            None,
+            maze.walk_multi(&[matrix_pos(-1, 0)], &[matrix_pos(-1, 0)]),
+        );
+    }
+
+    #[test]
+    fn directions_l_shaped() {
+        let maze = crate::Shape::Quad.create::<()>(5, 5);
+        let path = [
+            matrix_pos(0, 0),
+            matrix_pos(1, 0),
+            matrix_pos(2, 0),
+            matrix_pos(2, 1),
+            matrix_pos(2, 2),
+            matrix_pos(1, 2),
+            matrix_pos(2, 2),
+        ];
+
+        assert_eq!(
+            vec![
+                Turn::Straight,
+                Turn::Right,
+                Turn::Straight,
+                Turn::Right,
+                Turn::Back,
+            ],
+            maze.directions(&path),
+        );
+        assert_eq!(
+            vec![Turn::Left],
+            maze.directions(&[
+                matrix_pos(2, 2),
+                matrix_pos(2, 1),
+                matrix_pos(1, 1),
+            ]),
+        );
+    }
+
+    #[maze_test]
+    fn directions_length(maze: TestMaze) {
+        let maze = maze.initialize(
+            initialize::Method::Winding,
+            &mut initialize::LFSR::new(12345),
+        );
+        let path = maze
+            .walk(matrix_pos(0, 0), matrix_pos(4, 4))
+            .unwrap()
+            .into_iter()
+            .collect::<Vec<_>>();
+
+        assert_eq!(path.len() - 2, maze.directions(&path).len());
+        assert!(maze.directions(&path[..2]).is_empty());
+        assert!(maze.directions(&[]).is_empty());
+    }
+
     #[maze_test]
     fn walk_single(maze: TestMaze) {
         let map = Matrix::<Room>::new_with_data(10, 10, |_| Room {
@@ -460,6 +947,42 @@ mod tests {
         assert!(maze.walk(matrix_pos(0, 0), matrix_pos(0, 1)).is_none());
     }
 
+    #[test]
+    fn walk_stable() {
+        let maze = crate::Shape::Quad.create::<()>(4, 4).initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+        let walk = || {
+            maze.walk(matrix_pos(0, 0), matrix_pos(3, 3))
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>()
+        };
+
+        // The search is started from the goal
+        let expected = vec![
+            matrix_pos(0, 0),
+            matrix_pos(1, 0),
+            matrix_pos(2, 0),
+            matrix_pos(3, 0),
+            matrix_pos(3, 1),
+            matrix_pos(3, 2),
+            matrix_pos(3, 3),
+        ];
+        for _ in 0..100 {
+            assert_eq!(expected, walk());
+        }
+        assert_eq!(
+            expected,
+            maze.clone()
+                .walk(matrix_pos(0, 0), matrix_pos(3, 3))
+                .unwrap()
+                .into_iter()
+                .collect::<Vec<_>>(),
+        );
+    }
+
     #[maze_test]
     fn walk_same(maze: TestMaze) {
         let from = matrix_pos(0, 0);
@@ -511,6 +1034,128 @@ mod tests {
         );
     }
 
+    #[maze_test]
+    fn walk_heuristic_admissible(maze: TestMaze) {
+        for method in [initialize::Method::Clear, initialize::Method::Braid] {
+            let maze = maze
+                .clone()
+                .initialize(method, &mut initialize::LFSR::new(12345));
+            for from in [matrix_pos(0, 0), matrix_pos(3, 2)] {
+                for to in maze.positions() {
+                    let distance = maze.distance(from, to).unwrap();
+                    let heuristic = maze.shape().heuristic(from, to);
+                    assert!(
+                        heuristic <= distance as f32,
+                        "{} > {} from {:?} to {:?}",
+                        heuristic,
+                        distance,
+                        from,
+                        to,
+                    );
+                    if method == initialize::Method::Clear
+                        && maze.shape() != crate::Shape::Tri
+                    {
+                        assert_eq!(distance as f32, heuristic);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn walk_heuristic_admissible_diagonal() {
+        let maze = crate::Shape::QuadDiagonal.create::<()>(6, 5).initialize(
+            initialize::Method::Clear,
+            &mut initialize::LFSR::new(12345),
+        );
+        let from = matrix_pos(1, 1);
+        for to in maze.positions() {
+            assert_eq!(
+                maze.distance(from, to).unwrap() as f32,
+                maze.shape().heuristic(from, to),
+            );
+        }
+    }
+
+    #[test]
+    fn walk_heuristic_torus() {
+        let maze = crate::Shape::Quad
+            .create::<()>(6, 5)
+            .with_topology(crate::Topology::Torus)
+            .unwrap()
+            .initialize(
+                initialize::Method::Clear,
+                &mut initialize::LFSR::new(12345),
+            );
+        assert_eq!(Some(1), maze.distance(matrix_pos(0, 0), matrix_pos(5, 0)));
+        assert_eq!(Some(2), maze.distance(matrix_pos(0, 0), matrix_pos(5, 4)));
+    }
+
+    #[test]
+    fn walk_bounded_exceeded() {
+        let maze = TestMaze::new(crate::Shape::Quad, 100, 100).initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+        assert_eq!(
+            Some(BudgetExceeded),
+            maze.walk_bounded(matrix_pos(0, 0), matrix_pos(99, 99), 10)
+                .err(),
+        );
+    }
+
+    #[test]
+    fn walk_bounded_sufficient() {
+        let maze = TestMaze::new(crate::Shape::Quad, 100, 100).initialize(
+            initialize::Method::Braid,
+            &mut initialize::LFSR::new(12345),
+        );
+        let (from, to) = (matrix_pos(0, 0), matrix_pos(99, 99));
+        assert_eq!(
+            maze.walk(from, to)
+                .map(|path| path.into_iter().collect::<Vec<_>>()),
+            maze.walk_bounded(from, to, 10 * 100 * 100)
+                .unwrap()
+                .map(|path| path.into_iter().collect::<Vec<_>>()),
+        );
+    }
+
+    #[maze_test]
+    fn walk_bounded_disconnected(maze: TestMaze) {
+        assert!(maze
+            .walk_bounded(matrix_pos(0, 0), matrix_pos(0, 1), 1)
+            .unwrap()
+            .is_none());
+    }
+
+    #[maze_test]
+    fn distance_matches_walk(maze: TestMaze) {
+        for method in [initialize::Method::Braid, initialize::Method::Winding] {
+            let maze = maze
+                .clone()
+                .initialize(method, &mut initialize::LFSR::new(12345));
+            for from in maze.positions() {
+                for to in maze.positions() {
+                    assert_eq!(
+                        maze.walk(from, to)
+                            .map(|path| path.into_iter().count() as u32 - 1),
+                        maze.distance(from, to),
+                    );
+                }
+            }
+        }
+    }
+
+    #[maze_test]
+    fn distance_disconnected(maze: TestMaze) {
+        assert!(maze.distance(matrix_pos(0, 0), matrix_pos(0, 1)).is_none());
+    }
+
+    #[maze_test]
+    fn distance_same(maze: TestMaze) {
+        assert_eq!(Some(0), maze.distance(matrix_pos(0, 0), matrix_pos(0, 0)),);
+    }
+
     #[test]
     fn pop_empty() {
         let mut os = OpenSet::new(10, 10);
@@ -536,20 +1181,4 @@ mod tests {
         os.push(5, matrix_pos(5, 6));
         assert_eq!(os.pop(), Some(expected.1));
     }
-
-    #[test]
-    fn contains_same() {
-        let mut os = OpenSet::new(10, 10);
-        let expected = (10, matrix_pos(1, 2));
-
-        assert!(!os.contains(expected.1));
-        os.push(0, matrix_pos(3, 4));
-        assert!(!os.contains(expected.1));
-        os.push(expected.0, expected.1);
-        assert!(os.contains(expected.1));
-        os.push(5, matrix_pos(5, 6));
-        assert!(os.contains(expected.1));
-        os.pop();
-        assert!(!os.contains(expected.1));
-    }
 }
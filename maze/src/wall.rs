@@ -169,12 +169,7 @@ impl<'de> Deserialize<'de> for &'static Wall {
         D: Deserializer<'de>,
     {
         let wall_name = String::deserialize(deserializer)?;
-        crate::shape::hex::walls::ALL
-            .iter()
-            .chain(crate::shape::quad::walls::ALL.iter())
-            .chain(crate::shape::tri::walls::ALL.iter())
-            .find(|wall| wall.name == wall_name)
-            .copied()
+        crate::shape::wall_by_name(&wall_name)
             .ok_or_else(|| D::Error::custom("expected a wall name"))
     }
 }
@@ -248,7 +243,7 @@ mod tests {
 
         for pos in maze.positions() {
             for wall in maze.walls(pos) {
-                let d = 16.0 * std::f32::EPSILON;
+                let d = 16.0 * f32::EPSILON;
                 assert_span(wall, wall.span.0.a + d);
                 assert_not_span(wall, wall.span.0.a - d);
                 assert_span(wall.previous, wall.span.0.a - d);
@@ -293,7 +288,7 @@ mod tests {
         for pos in maze.positions() {
             let walls = maze.walls(pos);
             for wall in walls {
-                let d = 16.0 * std::f32::EPSILON;
+                let d = 16.0 * f32::EPSILON;
                 assert!(
                     wall.in_span(wall.previous.span.1.a + d),
                     "invalid wall order {:?}: {:?} <=> {:?}",
@@ -335,8 +330,7 @@ mod tests {
                     .filter(|&a| {
                         maze.walls(matrix::Pos { col, row: 0 })
                             .iter()
-                            .filter(|wall| wall.in_span(a))
-                            .next()
+                            .find(|wall| wall.in_span(a))
                             .is_none()
                     }),
             );
@@ -40,7 +40,7 @@ where
     ) -> matrix::Matrix<T>;
 }
 
-impl<'a, C, I, T, U> Splitter<C, T, U> for &'a mut I
+impl<C, I, T, U> Splitter<C, T, U> for &mut I
 where
     C: Cells,
     I: Iterator<Item = (physical::Pos, U)>,
@@ -1,5 +1,7 @@
 use std::str;
 
+mod names;
+
 /// A colour.
 #[derive(Clone, Copy, Default)]
 pub struct Color {
@@ -22,13 +24,37 @@ impl Color {
         Self { alpha: 0, ..self }
     }
 
+    /// Converts this colour to a string on the form `#RRGGBBAA`.
+    ///
+    /// Unlike the [`Display`](std::fmt::Display) implementation, this
+    /// includes the alpha component.
+    pub fn to_rgba_string(&self) -> String {
+        format!(
+            "#{:02X}{:02X}{:02X}{:02X}",
+            self.red, self.green, self.blue, self.alpha,
+        )
+    }
+
+    /// Converts this colour to a CSS value on the form `rgba(r, g, b, a)`.
+    ///
+    /// The alpha component is expressed as a value between 0 and 1.
+    pub fn to_css(&self) -> String {
+        format!(
+            "rgba({}, {}, {}, {})",
+            self.red,
+            self.green,
+            self.blue,
+            f32::from(self.alpha) / 255.0,
+        )
+    }
+
     /// Fades one colour to another.
     ///
     /// # Arguments
     /// *  `other` - The other colour.
     /// *  `w` - The weight of this colour. If this is `1.0` or greater, `self`
-    ///   colour is returned; if this is 0.0 or less, `other` is returned;
-    ///   otherwise a linear interpolation between the colours is returned.
+    ///    colour is returned; if this is 0.0 or less, `other` is returned;
+    ///    otherwise a linear interpolation between the colours is returned.
     pub fn fade(self, other: Self, w: f32) -> Color {
         if w >= 1.0 {
             self
@@ -50,75 +76,279 @@ impl Color {
 }
 
 impl str::FromStr for Color {
-    type Err = String;
+    type Err = maze::ParseError;
 
     /// Converts a string to a colour.
     ///
-    /// This method supports colours on the form `#RRGGBB` and `#RRGGBBAA`,
-    /// where `RR`, `GG`, `BB` and `AA` are the red, green, blue and alpha
-    /// components hex encoded.
+    /// This method supports the following formats:
+    /// *  `#RGB`, `#RGBA`, `#RRGGBB` and `#RRGGBBAA`, where `R`, `G`, `B` and
+    ///    `A` are the hex encoded red, green, blue and alpha components. The
+    ///    short forms are expanded by repeating every digit.
+    /// *  `rgb(r, g, b)` and `rgba(r, g, b, a)`, where `r`, `g` and `b` are
+    ///    integers between 0 and 255 and `a` a value between 0 and 1.
+    /// *  The named colours of CSS, such as `red` and `rebeccapurple`, and
+    ///    `transparent`.
     ///
     /// # Arguments
     /// *  `s` - The string to convert.
-    fn from_str(s: &str) -> Result<Color, String> {
-        if !s.starts_with('#') || s.len() % 2 == 0 {
-            Err(format!("unknown colour value: {}", s))
+    fn from_str(s: &str) -> Result<Color, Self::Err> {
+        let s = s.trim();
+        let error = |reason: String| maze::ParseError::InvalidColor {
+            value: s.to_owned(),
+            reason,
+        };
+        if let Some(digits) = s.strip_prefix('#') {
+            Self::from_hex(digits).map_err(error)
+        } else if let Some(args) = function(s, "rgba") {
+            Self::from_components(args, true).map_err(error)
+        } else if let Some(args) = function(s, "rgb") {
+            Self::from_components(args, false).map_err(error)
+        } else if s.eq_ignore_ascii_case("transparent") {
+            Ok(Color::default())
         } else {
-            let data = s
-                .bytes()
-                // Skip the initial '#'
-                .skip(1)
-                // Hex decode and create list
-                .map(|c| {
-                    if c.is_ascii_digit() {
-                        Some(c - b'0')
-                    } else if (b'A'..=b'F').contains(&c) {
-                        Some(c - b'A' + 10)
-                    } else if (b'a'..=b'f').contains(&c) {
-                        Some(c - b'a' + 10)
-                    } else {
-                        None
+            let name = s.to_ascii_lowercase();
+            names::NAMED
+                .binary_search_by(|&(n, _)| n.cmp(name.as_str()))
+                .map(|i| {
+                    let (red, green, blue) = names::NAMED[i].1;
+                    Color {
+                        red,
+                        green,
+                        blue,
+                        alpha: 255,
                     }
                 })
-                .collect::<Vec<_>>()
-                // Join every byte
+                .map_err(|_| error("unknown colour value".to_owned()))
+        }
+    }
+}
+
+impl Color {
+    /// Parses hex encoded colour components.
+    ///
+    /// # Arguments
+    /// *  `digits` - The hex digits, without the leading `#`.
+    fn from_hex(digits: &str) -> Result<Self, String> {
+        let values = digits
+            .chars()
+            .map(|c| {
+                c.to_digit(16)
+                    .map(|v| v as u8)
+                    .ok_or_else(|| format!("invalid hex digit: {}", c))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let data = match values.len() {
+            3 | 4 => values.iter().map(|&v| v << 4 | v).collect::<Vec<_>>(),
+            6 | 8 => values
                 .chunks(2)
-                .map(|c| {
-                    if let (Some(msb), Some(lsb)) = (c[0], c[1]) {
-                        Some(msb << 4 | lsb)
-                    } else {
-                        None
-                    }
-                })
-                // Ensure all values are valid
-                .take_while(Option::is_some)
-                .map(Option::unwrap)
-                .collect::<Vec<_>>();
-
-            match data.len() {
-                3 => Ok(Color {
-                    red: data[0],
-                    green: data[1],
-                    blue: data[2],
-                    alpha: 255,
-                }),
-                4 => Ok(Color {
-                    red: data[1],
-                    green: data[2],
-                    blue: data[3],
-                    alpha: data[0],
-                }),
-                _ => Err(format!("invalid colour format: {}", s)),
-            }
+                .map(|c| c[0] << 4 | c[1])
+                .collect::<Vec<_>>(),
+            n => return Err(format!("invalid number of digits: {}", n)),
+        };
+
+        Ok(Color {
+            red: data[0],
+            green: data[1],
+            blue: data[2],
+            alpha: data.get(3).copied().unwrap_or(255),
+        })
+    }
+
+    /// Parses comma separated decimal colour components.
+    ///
+    /// # Arguments
+    /// *  `args` - The components.
+    /// *  `alpha` - Whether an alpha component is expected.
+    fn from_components(args: &str, alpha: bool) -> Result<Self, String> {
+        let parts = args.split(',').map(str::trim).collect::<Vec<_>>();
+        let expected = if alpha { 4 } else { 3 };
+        if parts.len() != expected {
+            return Err(format!(
+                "expected {} components, found {}",
+                expected,
+                parts.len(),
+            ));
         }
+
+        let component = |s: &str| {
+            s.parse::<u8>()
+                .map_err(|_| format!("invalid colour component: {}", s))
+        };
+        let alpha = if alpha {
+            match parts[3].parse::<f32>() {
+                Ok(a) if (0.0..=1.0).contains(&a) => (a * 255.0).round() as u8,
+                _ => return Err(format!("invalid alpha value: {}", parts[3])),
+            }
+        } else {
+            255
+        };
+
+        Ok(Color {
+            red: component(parts[0])?,
+            green: component(parts[1])?,
+            blue: component(parts[2])?,
+            alpha,
+        })
     }
 }
 
-impl ToString for Color {
+/// Extracts the arguments of a functional notation value.
+///
+/// # Arguments
+/// *  `s` - The string to parse.
+/// *  `name` - The name of the function.
+fn function<'a>(s: &'a str, name: &str) -> Option<&'a str> {
+    let rest = s.get(..name.len()).and_then(|prefix| {
+        if prefix.eq_ignore_ascii_case(name) {
+            s[name.len()..].trim_start().strip_prefix('(')
+        } else {
+            None
+        }
+    })?;
+    rest.strip_suffix(')')
+}
+
+impl std::fmt::Display for Color {
     /// Converts a colour to a string.
     ///
     /// This method ignores the alpha component.
-    fn to_string(&self) -> String {
-        format!("#{:02.X}{:02.X}{:02.X}", self.red, self.green, self.blue)
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02.X}{:02.X}{:02.X}", self.red, self.green, self.blue)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extracts the components of a colour.
+    ///
+    /// # Arguments
+    /// *  `color` - The colour.
+    fn components(color: Color) -> (u8, u8, u8, u8) {
+        (color.red, color.green, color.blue, color.alpha)
+    }
+
+    #[test]
+    fn parse_long() {
+        assert_eq!(
+            (0x12, 0x34, 0x56, 255),
+            components("#123456".parse().unwrap()),
+        );
+    }
+
+    #[test]
+    fn parse_shorthand() {
+        assert_eq!(
+            (0x11, 0xaa, 0xff, 255),
+            components("#1aF".parse().unwrap()),
+        );
+        assert_eq!(
+            (0x11, 0xaa, 0xff, 0x88),
+            components("#1aF8".parse().unwrap()),
+        );
+    }
+
+    #[test]
+    fn parse_alpha() {
+        let color = "#11223344".parse::<Color>().unwrap();
+        assert_eq!((0x11, 0x22, 0x33, 0x44), components(color));
+        assert_eq!(
+            components(color),
+            components(
+                format!(
+                    "#{:02X}{:02X}{:02X}{:02X}",
+                    color.red, color.green, color.blue, color.alpha,
+                )
+                .parse()
+                .unwrap()
+            ),
+        );
+    }
+
+    #[test]
+    fn parse_named() {
+        assert_eq!((255, 0, 0, 255), components("red".parse().unwrap()));
+        assert_eq!(
+            (0x66, 0x33, 0x99, 255),
+            components("RebeccaPurple".parse().unwrap()),
+        );
+        assert_eq!((0, 0, 0, 0), components("transparent".parse().unwrap()));
+    }
+
+    #[test]
+    fn parse_functional() {
+        assert_eq!((1, 2, 3, 255), components("rgb(1, 2, 3)".parse().unwrap()),);
+        assert_eq!(
+            (1, 2, 3, 128),
+            components("rgba(1,2,3,0.5)".parse().unwrap()),
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in [
+            "",
+            "#",
+            "#12",
+            "#12345",
+            "#12345g",
+            "notacolour",
+            "rgb(1, 2)",
+            "rgb(1, 2, 256)",
+            "rgba(1, 2, 3, 2)",
+            "rgb(1, 2, 3",
+        ] {
+            match s.parse::<Color>() {
+                Err(e @ maze::ParseError::InvalidColor { .. }) => {
+                    assert!(e.to_string().contains(s));
+                }
+                _ => panic!("{} was parsed", s),
+            }
+        }
+    }
+
+    #[test]
+    fn named_sorted() {
+        assert!(names::NAMED.windows(2).all(|w| w[0].0 < w[1].0));
+    }
+
+    #[test]
+    fn rgba_string_round_trip() {
+        let color = "#11223344".parse::<Color>().unwrap();
+        assert_eq!("#11223344", color.to_rgba_string());
+        for alpha in 0..=255 {
+            let color = Color { alpha, ..color };
+            assert_eq!(
+                components(color),
+                components(color.to_rgba_string().parse().unwrap()),
+            );
+        }
+    }
+
+    #[test]
+    fn css_round_trip() {
+        let color = "#112233".parse::<Color>().unwrap();
+        assert_eq!("rgba(17, 34, 51, 1)", color.to_css());
+        for alpha in 0..=255 {
+            let color = Color { alpha, ..color };
+            assert_eq!(
+                components(color),
+                components(color.to_css().parse().unwrap()),
+            );
+        }
+    }
+
+    #[test]
+    fn display_ignores_alpha() {
+        let color = "#11223344".parse::<Color>().unwrap();
+        assert_eq!("#112233", color.to_string());
+    }
+
+    #[test]
+    fn transparent() {
+        let color = "#123456".parse::<Color>().unwrap().transparent();
+        assert_eq!((0x12, 0x34, 0x56, 0), components(color));
     }
 }
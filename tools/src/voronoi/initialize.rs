@@ -12,6 +12,9 @@ where
 {
     methods: Vec<initialize::Method>,
 
+    /// The number of walls to open between neighbouring segments.
+    connectors_per_edge: usize,
+
     _marker: ::std::marker::PhantomData<R>,
 }
 
@@ -69,10 +72,27 @@ where
     pub fn new(methods: Vec<initialize::Method>) -> Self {
         Self {
             methods,
+            connectors_per_edge: 1,
             _marker: ::std::marker::PhantomData,
         }
     }
 
+    /// Sets the number of walls to open between neighbouring segments.
+    ///
+    /// By default a single wall is opened, which yields a perfect maze if all
+    /// methods generate perfect mazes. Opening more walls yields looser
+    /// mazes. If the boundary between two segments has fewer walls, all of
+    /// them are opened.
+    ///
+    /// # Arguments
+    /// *  `connectors_per_edge` - The number of walls to open.
+    pub fn with_connectors_per_edge(self, connectors_per_edge: usize) -> Self {
+        Self {
+            connectors_per_edge,
+            ..self
+        }
+    }
+
     /// The initialisation methods.
     pub fn methods(&self) -> &Vec<initialize::Method> {
         &self.methods
@@ -99,6 +119,36 @@ where
         filter: F,
         points: P,
     ) -> InitializedMaze<T>
+    where
+        F: Fn(matrix::Pos) -> bool,
+        T: Clone,
+        P: Iterator<Item = super::Point<usize>>,
+    {
+        self.initialize_progress(maze, rng, filter, points, None)
+    }
+
+    /// Initialises a maze by applying all methods defined for this collection
+    /// while reporting progress.
+    ///
+    /// This is equivalent to [`Methods::initialize`], but `progress` is called
+    /// with the fraction of completion, in the range _[0, 1]_. Every method
+    /// accounts for an equal part of the progress. The reported values never
+    /// decrease, and the last one is always `1.0`.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to initialise.
+    /// *  `rng` - A random number generator.
+    /// *  `filter` - An additional filter applied to all methods.
+    /// *  `points` - The points and weights to generate a Voronoi diagram.
+    /// *  `progress` - A function receiving the progress, if any.
+    pub fn initialize_progress<F, T, P>(
+        self,
+        maze: maze::Maze<T>,
+        rng: &mut R,
+        filter: F,
+        points: P,
+        mut progress: Option<&mut dyn FnMut(f32)>,
+    ) -> InitializedMaze<T>
     where
         F: Fn(matrix::Pos) -> bool,
         T: Clone,
@@ -109,17 +159,34 @@ where
             super::matrix(&maze, points.take(self.methods.len()).collect());
 
         // Use a different initialisation method for each segment
+        let count = self.methods.len() as f32;
         let mut maze = self.methods.into_iter().enumerate().fold(
             maze,
             |maze, (i, method)| {
-                maze.initialize_filter(method, rng, |pos| {
-                    filter(pos) && areas[pos] == i
-                })
+                let segment = |pos| filter(pos) && areas[pos] == i;
+                match progress.as_mut() {
+                    Some(progress) => maze.initialize_filter_progress(
+                        method,
+                        rng,
+                        segment,
+                        Some(&mut |p| progress((i as f32 + p) / count)),
+                    ),
+                    None => maze.initialize_filter(method, rng, segment),
+                }
             },
         );
 
         // Make sure all segments are connected
-        initialize::connect_all(&mut maze, rng, filter);
+        initialize::connect_all_with(
+            &mut maze,
+            rng,
+            filter,
+            self.connectors_per_edge,
+        );
+
+        if let Some(progress) = progress {
+            progress(1.0);
+        }
 
         InitializedMaze { maze, areas }
     }
@@ -155,7 +222,125 @@ where
     fn default() -> Self {
         Self {
             methods: vec![initialize::Method::default()],
+            connectors_per_edge: 1,
             _marker: ::std::marker::PhantomData,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Initialises a maze split into a left and a right segment.
+    ///
+    /// # Arguments
+    /// *  `connectors_per_edge` - The number of walls to open between the
+    ///    segments.
+    fn split(connectors_per_edge: usize) -> InitializedMaze<()> {
+        let maze = maze::Shape::Quad.create::<()>(10, 6);
+        let viewbox = maze.viewbox();
+        let points = [0.25, 0.75].iter().enumerate().map(|(i, &x)| {
+            (
+                i,
+                (
+                    physical::Pos {
+                        x: viewbox.corner.x + x * viewbox.width,
+                        y: viewbox.center().y,
+                    },
+                    1.0,
+                ),
+            )
+        });
+
+        Methods::new(vec![initialize::Method::Winding; 2])
+            .with_connectors_per_edge(connectors_per_edge)
+            .initialize(
+                maze,
+                &mut initialize::LFSR::new(12345),
+                |_| true,
+                points,
+            )
+    }
+
+    /// Counts the open walls between rooms in different segments.
+    ///
+    /// # Arguments
+    /// *  `initialized` - The initialised maze.
+    fn connectors(initialized: &InitializedMaze<()>) -> usize {
+        let maze = &initialized.maze;
+        maze.positions()
+            .flat_map(|pos| maze.doors(pos).map(move |wall| (pos, wall)))
+            .map(|(pos, wall)| (pos, maze.back((pos, wall)).0))
+            .filter(|&(pos, next)| {
+                maze.is_inside(next)
+                    && initialized.areas[pos] != initialized.areas[next]
+            })
+            .count()
+            / 2
+    }
+
+    #[test]
+    fn initialize_progress() {
+        let maze = maze::Shape::Quad.create::<()>(10, 6);
+        let viewbox = maze.viewbox();
+        let mut rng = initialize::LFSR::new(12345);
+        let points = Methods::random_points(viewbox, &mut rng)
+            .take(3)
+            .collect::<Vec<_>>();
+        let mut reported = Vec::new();
+        let initialized = Methods::new(vec![initialize::Method::Winding; 3])
+            .initialize_progress(
+                maze,
+                &mut initialize::LFSR::new(12345),
+                |_| true,
+                points.into_iter(),
+                Some(&mut |progress| reported.push(progress)),
+            );
+
+        assert!(initialized
+            .maze
+            .positions()
+            .all(|pos| initialized.maze[pos].visited));
+        assert!(reported.len() > 3);
+        assert!(reported.windows(2).all(|w| w[0] <= w[1]));
+        assert_eq!(Some(&1.0), reported.last());
+    }
+
+    #[test]
+    fn initialize_single_connector() {
+        assert_eq!(1, connectors(&split(1)));
+    }
+
+    #[test]
+    fn initialize_two_connectors() {
+        assert_eq!(2, connectors(&split(2)));
+    }
+
+    #[test]
+    fn initialize_connectors_limited() {
+        // The boundary between the segments has one wall per row
+        assert_eq!(6, connectors(&split(100)));
+    }
+
+    #[test]
+    fn initialize_masked_separate_segments() {
+        // Masking out the middle columns leaves no edge between the segments
+        let initialized = Methods::new(vec![initialize::Method::Winding; 2])
+            .initialize(
+                maze::Shape::Quad.create::<()>(10, 6),
+                &mut initialize::LFSR::new(12345),
+                |pos| pos.col != 4 && pos.col != 5,
+                [0.0, 10.0]
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &x)| (i, (physical::Pos { x, y: 3.0 }, 1.0))),
+            );
+
+        assert_eq!(0, connectors(&initialized));
+        let maze = &initialized.maze;
+        for pos in maze.positions() {
+            assert_eq!(pos.col != 4 && pos.col != 5, maze[pos].visited);
+        }
+    }
+}
@@ -1,8 +1,20 @@
-use actix_web::{get, web, App, HttpResponse, HttpServer, Responder};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use actix_web::{get, post, web, App, HttpResponse, HttpServer, Responder};
 use serde::Deserialize;
 
 mod types;
 
+/// The environment variable overriding the capacity of the cache.
+const CACHE_CAPACITY_VAR: &str = "MAZE_CACHE_CAPACITY";
+
+/// The default maximum number of rendered mazes to keep in the cache.
+const CACHE_CAPACITY: usize = 256;
+
+/// The time for which rendered mazes are kept in the cache.
+const CACHE_TTL: Duration = Duration::from_secs(10 * 60);
+
 #[derive(Deserialize)]
 struct Query {
     seed: Option<types::Seed>,
@@ -10,26 +22,199 @@ struct Query {
 }
 #[get("/{maze_type}/{dimensions}/image.svg")]
 async fn maze_svg(
-    (path, query): (
-        web::Path<(types::MazeType, types::Dimensions)>,
-        web::Query<Query>,
-    ),
+    path: web::Path<(types::MazeType, types::Dimensions)>,
+    query: web::Query<Query>,
+    cache: web::Data<Mutex<types::Cache>>,
 ) -> impl Responder {
     let (maze_type, dimensions) = path.into_inner();
     let Query { seed, solve } = query.into_inner();
-    HttpResponse::from(types::Maze {
+
+    // Mazes with a generated seed will never be requested again, so caching
+    // them would only evict useful entries
+    let cache = seed.is_some().then(|| cache.get_ref());
+    types::Maze {
         maze_type,
         dimensions,
         seed: seed.unwrap_or_else(types::Seed::random),
         solve: solve.unwrap_or(false),
+        mask: None,
+    }
+    .respond(cache)
+}
+
+#[post("/{maze_type}/{dimensions}/image.svg")]
+async fn masked_maze_svg(
+    (path, query, body): (
+        web::Path<(types::MazeType, types::Dimensions)>,
+        web::Query<Query>,
+        web::Bytes,
+    ),
+) -> impl Responder {
+    let (maze_type, dimensions) = path.into_inner();
+    let Query { seed, solve } = query.into_inner();
+    match types::Mask::from_png(&body, &dimensions) {
+        Ok(mask) => HttpResponse::from(types::Maze {
+            maze_type,
+            dimensions,
+            seed: seed.unwrap_or_else(types::Seed::random),
+            solve: solve.unwrap_or(false),
+            mask: Some(mask),
+        }),
+        Err(e) => HttpResponse::BadRequest().body(e),
+    }
+}
+
+/// The capacity of the cache.
+///
+/// This is read from the environment variable [`CACHE_CAPACITY_VAR`], and
+/// defaults to [`CACHE_CAPACITY`] if it is not set.
+///
+/// # Panics
+/// This function panics if the environment variable is not a valid number.
+fn cache_capacity() -> usize {
+    std::env::var(CACHE_CAPACITY_VAR).map_or(CACHE_CAPACITY, |value| {
+        value.parse().unwrap_or_else(|_| {
+            panic!("invalid {}: {}", CACHE_CAPACITY_VAR, value)
+        })
     })
 }
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
-    HttpServer::new(|| App::new().service(maze_svg))
-        .bind("0.0.0.0:8000")
-        .unwrap()
-        .run()
-        .await
+    let cache = web::Data::new(Mutex::new(types::Cache::new(
+        cache_capacity(),
+        CACHE_TTL,
+    )));
+    HttpServer::new(move || {
+        App::new()
+            .app_data(cache.clone())
+            .service(maze_svg)
+            .service(masked_maze_svg)
+    })
+    .bind("0.0.0.0:8000")
+    .unwrap()
+    .run()
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test;
+
+    use super::*;
+
+    /// Encodes an image where only the right half is white.
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(image::RgbImage::from_fn(
+            width,
+            height,
+            |x, _| image::Rgb([if x < width / 2 { 0 } else { 255 }; 3]),
+        ))
+        .write_to(&mut data, image::ImageOutputFormat::Png)
+        .unwrap();
+        data.into_inner()
+    }
+
+    #[actix_web::test]
+    async fn maze_svg_cached() {
+        let cache = web::Data::new(Mutex::new(types::Cache::new(
+            4,
+            Duration::from_secs(60),
+        )));
+        let app = test::init_service(
+            App::new().app_data(cache.clone()).service(maze_svg),
+        )
+        .await;
+        let mut bodies = Vec::new();
+        for _ in 0..2 {
+            let request = test::TestRequest::get()
+                .uri("/hex/5x4/image.svg?seed=12345&solve=true")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert!(response.status().is_success());
+            assert_eq!("12345", response.headers().get("X-Maze-Seed").unwrap());
+            bodies.push(test::read_body(response).await);
+        }
+
+        assert_eq!(bodies[0], bodies[1]);
+        let cache = cache.lock().unwrap();
+        assert_eq!((1, 1), (cache.hits(), cache.misses()));
+    }
+
+    #[actix_web::test]
+    async fn maze_svg_random_seed_not_cached() {
+        let cache = web::Data::new(Mutex::new(types::Cache::new(
+            4,
+            Duration::from_secs(60),
+        )));
+        let app = test::init_service(
+            App::new().app_data(cache.clone()).service(maze_svg),
+        )
+        .await;
+        for _ in 0..2 {
+            let request = test::TestRequest::get()
+                .uri("/hex/5x4/image.svg")
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert!(response.status().is_success());
+        }
+
+        let cache = cache.lock().unwrap();
+        assert_eq!((0, 0), (cache.hits(), cache.misses()));
+    }
+
+    #[actix_web::test]
+    async fn masked_maze_svg_omits_rooms() {
+        let app = test::init_service(App::new().service(masked_maze_svg)).await;
+        let request = test::TestRequest::post()
+            .uri("/quad/4x2/image.svg?seed=12345&solve=true")
+            .set_payload(png(4, 2))
+            .to_request();
+        let response = test::call_service(&app, request).await;
+        assert!(response.status().is_success());
+        let body = test::read_body(response).await;
+        let body = std::str::from_utf8(&body).unwrap();
+
+        // All walls and the solution must be in the right half
+        let limit = maze::Shape::Quad
+            .create::<()>(4, 2)
+            .center(maze::matrix::Pos { col: 1, row: 0 })
+            .x;
+        let mut paths = 0;
+        for event in svg::read(body).unwrap() {
+            if let svg::parser::Event::Tag("path", _, attributes) = event {
+                paths += 1;
+                let data =
+                    svg::node::element::path::Data::parse(&attributes["d"])
+                        .unwrap();
+                for command in data.iter() {
+                    match command {
+                        svg::node::element::path::Command::Move(_, p)
+                        | svg::node::element::path::Command::Line(_, p) => {
+                            assert!(p[0] > limit, "{} in {}", p[0], body);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        assert_eq!(2, paths);
+    }
+
+    #[actix_web::test]
+    async fn masked_maze_svg_invalid() {
+        let app = test::init_service(App::new().service(masked_maze_svg)).await;
+        for payload in [b"not an image".to_vec(), png(2, 2)] {
+            let request = test::TestRequest::post()
+                .uri("/quad/4x2/image.svg")
+                .set_payload(payload)
+                .to_request();
+            let response = test::call_service(&app, request).await;
+            assert_eq!(
+                actix_web::http::StatusCode::BAD_REQUEST,
+                response.status(),
+            );
+        }
+    }
 }
@@ -0,0 +1,208 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use actix_web::web::Bytes;
+
+/// The parameters uniquely identifying a rendered maze.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CacheKey {
+    /// The shape of the maze.
+    pub shape: maze::Shape,
+
+    /// The width of the maze.
+    pub width: usize,
+
+    /// The height of the maze.
+    pub height: usize,
+
+    /// The seed used to initialise the maze.
+    pub seed: u64,
+
+    /// Whether the solution is drawn.
+    pub solve: bool,
+}
+
+/// A cached rendering.
+struct Entry {
+    /// The rendered document.
+    data: Bytes,
+
+    /// The time at which this entry was inserted.
+    created: Instant,
+
+    /// The value of the use counter when this entry was last used.
+    used: u64,
+}
+
+/// An in-memory cache of rendered mazes.
+///
+/// Once the cache is full, the least recently used entry is evicted to make
+/// room for a new one. Entries older than the time-to-live are never
+/// returned.
+///
+/// Finding the least recently used entry requires a scan of all entries, so
+/// the capacity should be kept moderate.
+pub struct Cache {
+    /// The maximum number of entries.
+    capacity: usize,
+
+    /// The maximum age of entries.
+    ttl: Duration,
+
+    /// The cached entries.
+    entries: HashMap<CacheKey, Entry>,
+
+    /// A counter incremented on every use of an entry.
+    uses: u64,
+
+    /// The number of lookups finding an entry.
+    hits: usize,
+
+    /// The number of lookups not finding an entry.
+    misses: usize,
+}
+
+impl Cache {
+    /// Creates an empty cache.
+    ///
+    /// # Arguments
+    /// *  `capacity` - The maximum number of entries. A capacity of `0`
+    ///    disables caching.
+    /// *  `ttl` - The maximum age of entries.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity,
+            ttl,
+            entries: HashMap::with_capacity(capacity),
+            uses: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Looks up a rendered maze.
+    ///
+    /// An expired entry is removed and counts as a miss.
+    ///
+    /// # Arguments
+    /// *  `key` - The parameters of the maze.
+    pub fn get(&mut self, key: &CacheKey) -> Option<Bytes> {
+        let ttl = self.ttl;
+        if self
+            .entries
+            .get(key)
+            .is_some_and(|entry| entry.created.elapsed() > ttl)
+        {
+            self.entries.remove(key);
+        }
+
+        self.uses += 1;
+        match self.entries.get_mut(key) {
+            Some(entry) => {
+                self.hits += 1;
+                entry.used = self.uses;
+                Some(entry.data.clone())
+            }
+            None => {
+                self.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Stores a rendered maze.
+    ///
+    /// If the cache is full, the least recently used entry is evicted first.
+    ///
+    /// # Arguments
+    /// *  `key` - The parameters of the maze.
+    /// *  `data` - The rendered document.
+    pub fn insert(&mut self, key: CacheKey, data: Bytes) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if !self.entries.contains_key(&key)
+            && self.entries.len() >= self.capacity
+        {
+            if let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| key.clone())
+            {
+                self.entries.remove(&oldest);
+            }
+        }
+
+        self.uses += 1;
+        self.entries.insert(
+            key,
+            Entry {
+                data,
+                created: Instant::now(),
+                used: self.uses,
+            },
+        );
+    }
+
+    /// The number of lookups that found an entry.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// The number of lookups that did not find an entry.
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a key for a quadratic maze.
+    ///
+    /// # Arguments
+    /// *  `seed` - The seed of the key.
+    fn key(seed: u64) -> CacheKey {
+        CacheKey {
+            shape: maze::Shape::Quad,
+            width: 5,
+            height: 5,
+            seed,
+            solve: false,
+        }
+    }
+
+    #[test]
+    fn evicts_least_recently_used() {
+        let mut cache = Cache::new(2, Duration::from_secs(60));
+        cache.insert(key(1), Bytes::from_static(b"1"));
+        cache.insert(key(2), Bytes::from_static(b"2"));
+        assert!(cache.get(&key(1)).is_some());
+        cache.insert(key(3), Bytes::from_static(b"3"));
+
+        assert_eq!(Some(Bytes::from_static(b"1")), cache.get(&key(1)));
+        assert_eq!(None, cache.get(&key(2)));
+        assert_eq!(Some(Bytes::from_static(b"3")), cache.get(&key(3)));
+        assert_eq!((3, 1), (cache.hits(), cache.misses()));
+    }
+
+    #[test]
+    fn expires() {
+        let mut cache = Cache::new(2, Duration::ZERO);
+        cache.insert(key(1), Bytes::from_static(b"1"));
+        std::thread::sleep(Duration::from_millis(1));
+
+        assert_eq!(None, cache.get(&key(1)));
+    }
+
+    #[test]
+    fn disabled() {
+        let mut cache = Cache::new(0, Duration::from_secs(60));
+        cache.insert(key(1), Bytes::from_static(b"1"));
+
+        assert_eq!(None, cache.get(&key(1)));
+    }
+}
@@ -1,7 +1,7 @@
 use serde::Deserialize;
 
 /// Dimensions of a maze.
-#[derive(Debug, Deserialize, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
 #[serde(try_from = "String")]
 pub struct Dimensions {
     /// The width.
@@ -0,0 +1,80 @@
+use maze::matrix;
+use maze_maker::types::MaskInitializer;
+
+/// The intensity threshold above which a pixel of a mask is part of the maze.
+const THRESHOLD: f32 = 0.5;
+
+/// A mask limiting the rooms that are part of a maze.
+pub struct Mask(MaskInitializer<super::Seed>);
+
+impl Mask {
+    /// Decodes a mask from a PNG image.
+    ///
+    /// The image is stretched over the entire maze, and light pixels mark
+    /// rooms that are part of the maze.
+    ///
+    /// # Arguments
+    /// *  `data` - The encoded image.
+    /// *  `dimensions` - The dimensions of the maze.
+    ///
+    /// # Errors
+    /// An error is returned if the image cannot be decoded, or if it has
+    /// fewer pixels than the maze has rooms along either axis.
+    pub fn from_png(
+        data: &[u8],
+        dimensions: &super::Dimensions,
+    ) -> Result<Self, String> {
+        let image =
+            image::load_from_memory_with_format(data, image::ImageFormat::Png)
+                .map_err(|_| String::from("invalid mask image"))?
+                .to_rgb8();
+        let (width, height) = image.dimensions();
+        if (width as usize) < dimensions.width
+            || (height as usize) < dimensions.height
+        {
+            Err(format!(
+                "the mask is smaller than the maze: {}x{}",
+                width, height,
+            ))
+        } else {
+            Ok(Self(MaskInitializer::from_image(image, THRESHOLD)))
+        }
+    }
+
+    /// Calculates the rooms of a maze that are part of this mask.
+    ///
+    /// # Arguments
+    /// *  `maze` - The maze to which to apply the mask.
+    pub fn candidates(&self, maze: &maze::Maze<()>) -> matrix::Matrix<bool> {
+        self.0.candidates(maze)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::types::Dimensions;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut data = std::io::Cursor::new(Vec::new());
+        image::DynamicImage::ImageRgb8(image::RgbImage::new(width, height))
+            .write_to(&mut data, image::ImageOutputFormat::Png)
+            .unwrap();
+        data.into_inner()
+    }
+
+    #[test]
+    fn from_png() {
+        let dimensions = Dimensions {
+            width: 4,
+            height: 2,
+        };
+
+        assert!(Mask::from_png(&png(4, 2), &dimensions).is_ok());
+        assert!(Mask::from_png(&png(40, 20), &dimensions).is_ok());
+        assert!(Mask::from_png(&png(3, 2), &dimensions).is_err());
+        assert!(Mask::from_png(&png(4, 1), &dimensions).is_err());
+        assert!(Mask::from_png(b"not an image", &dimensions).is_err());
+    }
+}
@@ -1,11 +1,16 @@
 use serde::Deserialize;
 
 /// A maze type, convertible from a query string.
-#[derive(Deserialize)]
+#[derive(Clone, Copy, Deserialize)]
 #[serde(transparent)]
 pub struct MazeType(maze::Shape);
 
 impl MazeType {
+    /// The shape of mazes of this type.
+    pub fn shape(self) -> maze::Shape {
+        self.0
+    }
+
     pub fn create<T>(self, dimensions: super::Dimensions) -> maze::Maze<T>
     where
         T: Clone + Copy + Default,
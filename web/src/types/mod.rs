@@ -1,66 +1,147 @@
+use std::sync::{Mutex, PoisonError};
+
+use actix_web::web::Bytes;
 use actix_web::HttpResponse;
 use svg::Node;
 
 use maze::initialize;
 use maze::render::svg::ToPath;
 
+mod cache;
+pub use self::cache::*;
 mod maze_type;
 pub use self::maze_type::*;
 mod dimensions;
 pub use self::dimensions::*;
+mod mask;
+pub use self::mask::*;
 mod seed;
 pub use self::seed::*;
 
 /// The maximum nmber of rooms.
 const MAX_ROOMS: usize = 1000;
 
+/// The number of decimals of the coordinates of generated paths.
+const PRECISION: usize = 3;
+
 /// A responder providing an image of a maze.
 pub struct Maze {
     pub maze_type: MazeType,
     pub dimensions: Dimensions,
     pub seed: Seed,
     pub solve: bool,
+    pub mask: Option<Mask>,
 }
 
-impl From<Maze> for HttpResponse {
-    fn from(mut source: Maze) -> Self {
-        let room_count = source.dimensions.width * source.dimensions.height;
-        if room_count > MAX_ROOMS {
-            HttpResponse::InsufficientStorage()
-                .body("the requested maze is too large")
+impl Maze {
+    /// The key identifying this maze in a [`Cache`].
+    ///
+    /// Masked mazes are never cached, so `None` is returned for them.
+    pub fn key(&self) -> Option<CacheKey> {
+        if self.mask.is_some() {
+            None
         } else {
-            let maze = source
-                .maze_type
-                .create::<()>(source.dimensions)
-                .initialize(initialize::Method::Branching, &mut source.seed);
+            Some(CacheKey {
+                shape: self.maze_type.shape(),
+                width: self.dimensions.width,
+                height: self.dimensions.height,
+                seed: self.seed.value(),
+                solve: self.solve,
+            })
+        }
+    }
+
+    /// Generates a response for this maze, using a cache if available.
+    ///
+    /// If the maze is found in the cache, it is not generated again;
+    /// otherwise the rendered document is added to the cache.
+    ///
+    /// # Arguments
+    /// *  `cache` - The cache of rendered mazes, if any.
+    pub fn respond(mut self, cache: Option<&Mutex<Cache>>) -> HttpResponse {
+        let room_count = self.dimensions.width * self.dimensions.height;
+        if room_count > MAX_ROOMS {
+            return HttpResponse::InsufficientStorage()
+                .body("the requested maze is too large");
+        }
+
+        // A panic while holding the lock cannot leave the cache inconsistent,
+        // so a poisoned lock is still usable
+        let key = cache.zip(self.key());
+        let cached = key.as_ref().and_then(|(cache, key)| {
+            cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get(key)
+        });
+        let data = match cached {
+            Some(data) => data,
+            None => {
+                let data = Bytes::from(self.render());
+                if let Some((cache, key)) = key {
+                    cache
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .insert(key, data.clone());
+                }
+                data
+            }
+        };
+
+        HttpResponse::Ok()
+            .content_type("image/svg+xml")
+            .insert_header(("X-Maze-Seed", self.seed.to_string()))
+            .body(data)
+    }
+
+    /// Generates and renders this maze.
+    fn render(&mut self) -> String {
+        let maze = self.maze_type.create::<()>(self.dimensions);
+        let maze = match &self.mask {
+            Some(mask) => {
+                let candidates = mask.candidates(&maze);
+                maze.initialize_filter(
+                    initialize::Method::Branching,
+                    &mut self.seed,
+                    |pos| candidates[pos],
+                )
+            }
+            None => {
+                maze.initialize(initialize::Method::Branching, &mut self.seed)
+            }
+        };
 
-            let mut container = svg::node::element::Group::new();
-            container.append(
-                svg::node::element::Path::new()
-                    .set("class", "walls")
-                    .set("d", maze.to_path_d()),
-            );
-            if source.solve {
+        let mut container = svg::node::element::Group::new();
+        container.append(
+            svg::node::element::Path::new()
+                .set("class", "walls")
+                .set("d", maze.to_rounded_path_d(PRECISION)),
+        );
+        if self.solve {
+            // Walk between the first and last rooms of the maze, which may be
+            // limited by a mask
+            let mut rooms = maze.visited_positions();
+            if let Some(path) = rooms
+                .next()
+                .zip(rooms.last())
+                .and_then(|(from, to)| maze.walk(from, to))
+            {
                 container.append(
-                    svg::node::element::Path::new().set("class", "path").set(
-                        "d",
-                        maze.walk(
-                            maze::matrix::Pos { col: 0, row: 0 },
-                            maze::matrix::Pos {
-                                col: maze.width() as isize - 1,
-                                row: maze.height() as isize - 1,
-                            },
-                        )
-                        .unwrap()
-                        .to_path_d(),
-                    ),
+                    svg::node::element::Path::new()
+                        .set("class", "path")
+                        .set("d", path.to_rounded_path_d(PRECISION)),
                 );
             }
-            let data = svg::Document::new()
-                .set("viewBox", maze.viewbox().tuple())
-                .add(container)
-                .to_string();
-            HttpResponse::Ok().content_type("image/svg+xml").body(data)
         }
+        svg::Document::new()
+            .set("viewBox", maze.viewbox().tuple())
+            .add(container)
+            .to_string()
+    }
+}
+
+impl From<Maze> for HttpResponse {
+    fn from(source: Maze) -> Self {
+        source.respond(None)
     }
 }
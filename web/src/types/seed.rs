@@ -1,23 +1,87 @@
+use std::fmt;
+use std::num::IntErrorKind;
+use std::str::FromStr;
+
 use serde::Deserialize;
 
 use maze::initialize;
 
 /// A random seed.
 #[derive(Debug, Deserialize, Eq, PartialEq)]
-#[serde(transparent)]
+#[serde(try_from = "String")]
 pub struct Seed {
+    /// The seed value.
+    value: u64,
+
     /// The LFSR initialised with the seed.
     lfsr: initialize::LFSR,
 }
 
 impl Seed {
     pub fn random() -> Self {
+        rand::random::<u64>().into()
+    }
+
+    /// The original seed value.
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+}
+
+impl From<u64> for Seed {
+    fn from(value: u64) -> Self {
         Self {
-            lfsr: initialize::LFSR::new(rand::random()),
+            value,
+            lfsr: initialize::LFSR::new(value),
         }
     }
 }
 
+impl FromStr for Seed {
+    type Err = String;
+
+    /// Parses a seed.
+    ///
+    /// The seed may be either a decimal number, or a hexadecimal number
+    /// prefixed with `0x`. No sign is allowed.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, radix) =
+            match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                Some(digits) => (digits, 16),
+                None => (s, 10),
+            };
+
+        // from_str_radix accepts a leading plus sign
+        if digits.starts_with(['+', '-']) {
+            return Err(format!("invalid seed: {}", s));
+        }
+
+        u64::from_str_radix(digits, radix)
+            .map(Self::from)
+            .map_err(|e| match e.kind() {
+                IntErrorKind::PosOverflow => {
+                    format!("seed out of range: {}", s)
+                }
+                _ => format!("invalid seed: {}", s),
+            })
+    }
+}
+
+impl TryFrom<String> for Seed {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for Seed {
+    /// Formats the original seed value as a decimal number.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
 impl initialize::Randomizer for Seed {
     fn range(&mut self, a: usize, b: usize) -> usize {
         self.lfsr.range(a, b)
@@ -35,12 +99,59 @@ mod tests {
     #[test]
     fn deserialize() {
         assert_eq!(
-            Seed {
-                lfsr: initialize::LFSR::new(1234)
-            },
+            Seed::from(1234),
             serde_urlencoded::from_str::<Vec<(String, Seed)>>("seed=1234")
                 .unwrap()[0]
                 .1,
         );
     }
+
+    #[test]
+    fn parse_decimal() {
+        assert_eq!(Ok(Seed::from(1234)), "1234".parse());
+        assert_eq!(Ok(Seed::from(u64::MAX)), u64::MAX.to_string().parse());
+    }
+
+    #[test]
+    fn parse_hex() {
+        assert_eq!(Ok(Seed::from(0x1234)), "0x1234".parse());
+        assert_eq!(Ok(Seed::from(0xabcdef)), "0XABCdef".parse());
+        assert_eq!(
+            serde_urlencoded::from_str::<Vec<(String, Seed)>>("seed=0xff")
+                .unwrap()[0]
+                .1,
+            Seed::from(255),
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert_eq!(
+            Err(String::from("invalid seed: 12a")),
+            "12a".parse::<Seed>(),
+        );
+        assert_eq!(Err(String::from("invalid seed: 0x")), "0x".parse::<Seed>(),);
+        assert_eq!(Err(String::from("invalid seed: ")), "".parse::<Seed>());
+        assert_eq!(Err(String::from("invalid seed: -1")), "-1".parse::<Seed>(),);
+        assert_eq!(
+            Err(String::from("invalid seed: +12")),
+            "+12".parse::<Seed>(),
+        );
+        assert_eq!(
+            Err(String::from("invalid seed: 0x+ff")),
+            "0x+ff".parse::<Seed>(),
+        );
+        assert_eq!(
+            Err(String::from("seed out of range: 0x10000000000000000")),
+            "0x10000000000000000".parse::<Seed>(),
+        );
+    }
+
+    #[test]
+    fn display() {
+        let mut seed = "0xff".parse::<Seed>().unwrap();
+        initialize::Randomizer::random(&mut seed);
+        assert_eq!("255", seed.to_string());
+        assert_eq!(Ok(Seed::from(255)), seed.to_string().parse());
+    }
 }